@@ -0,0 +1,200 @@
+use std::{sync::LazyLock, time::Duration};
+
+use k8s_openapi::chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::crd::{CanaryAnalysis, CanaryStatus};
+
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+/// An instant query answers with one sample; anything much bigger is not what we asked for.
+const MAX_RESPONSE_BYTES: usize = 1 << 20;
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(60);
+const DEFAULT_FAILURE_LIMIT: u32 = 3;
+
+/// Shared by canary analysis and scale-to-zero, so connections to Prometheus are reused.
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .timeout(QUERY_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+});
+
+pub enum Verdict {
+    Passed,
+    Failed(f64),
+    /// Prometheus could not be asked or gave no usable answer; the canary holds its step.
+    Inconclusive(String),
+    /// Not queried this pass and the last query did not pass, so the step holds.
+    Waiting,
+}
+
+/// Queries Prometheus at most once per `intervalSeconds`; in between, the last query stands.
+/// `last` is the status of the same canary, if any.
+pub async fn evaluate(
+    analysis: &CanaryAnalysis,
+    last: Option<&CanaryStatus>,
+    now: DateTime<Utc>,
+) -> Verdict {
+    if !due(analysis, last, now) {
+        return match last {
+            Some(last) if last.analysis_failures == 0 => Verdict::Passed,
+            _ => Verdict::Waiting,
+        };
+    }
+    match query(&analysis.prometheus_url, &analysis.query).await {
+        Ok(value) if value <= analysis.threshold => Verdict::Passed,
+        Ok(value) => Verdict::Failed(value),
+        Err(reason) => Verdict::Inconclusive(reason),
+    }
+}
+
+/// Whether the interval since the last query is up.
+pub fn due(analysis: &CanaryAnalysis, last: Option<&CanaryStatus>, now: DateTime<Utc>) -> bool {
+    let interval = analysis
+        .interval_seconds
+        .map_or(DEFAULT_INTERVAL, Duration::from_secs);
+    let analyzed_at = last
+        .and_then(|c| c.analyzed_at.as_deref())
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok());
+    analyzed_at
+        .is_none_or(|t| (now - t.with_timezone(&Utc)).to_std().unwrap_or_default() >= interval)
+}
+
+/// Failed queries in a row once `verdict` is counted; anything but a pass or a failure leaves
+/// the count as it was.
+pub fn failures(previous: u32, verdict: &Verdict) -> u32 {
+    match verdict {
+        Verdict::Passed => 0,
+        Verdict::Failed(_) => previous + 1,
+        Verdict::Inconclusive(_) | Verdict::Waiting => previous,
+    }
+}
+
+/// Whether `failures` in a row are enough to give up on the canary.
+pub fn exhausted(analysis: &CanaryAnalysis, failures: u32) -> bool {
+    failures
+        >= analysis
+            .failure_limit
+            .unwrap_or(DEFAULT_FAILURE_LIMIT)
+            .max(1)
+}
+
+/// The first sample of an instant query.
+pub async fn sample(prometheus_url: &str, promql: &str) -> Result<f64, String> {
+    query(prometheus_url, promql).await
+}
+
+async fn query(base_url: &str, promql: &str) -> Result<f64, String> {
+    let url = format!("{}/api/v1/query", base_url.trim_end_matches('/'));
+    let mut response = CLIENT
+        .get(&url)
+        .query(&[("query", promql)])
+        .header(reqwest::header::ACCEPT, "application/json")
+        .send()
+        .await
+        .map_err(|e| format!("querying {}: {}", url, e))?;
+    if !response.status().is_success() {
+        return Err(format!("Prometheus answered {}", response.status()));
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("reading from {}: {}", url, e))?
+    {
+        if body.len() + chunk.len() > MAX_RESPONSE_BYTES {
+            return Err(format!(
+                "Prometheus response exceeds {} bytes",
+                MAX_RESPONSE_BYTES
+            ));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    parse_sample(&body)
+}
+
+/// The value of a `scalar` result, or of the first sample of a `vector`.
+fn parse_sample(body: &[u8]) -> Result<f64, String> {
+    let body: Value = serde_json::from_slice(body).map_err(|e| format!("bad JSON: {}", e))?;
+    let sample = match body.pointer("/data/resultType").and_then(Value::as_str) {
+        Some("scalar") => body.pointer("/data/result/1"),
+        _ => body.pointer("/data/result/0/value/1"),
+    };
+    sample
+        .and_then(Value::as_str)
+        .ok_or("query returned no samples")?
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite())
+        .ok_or_else(|| "query returned a non-numeric sample".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parse(body: Value) -> Result<f64, String> {
+        parse_sample(body.to_string().as_bytes())
+    }
+
+    #[test]
+    fn samples_are_read_from_vectors_and_scalars() {
+        let vector = json!({"status": "success", "data": {"resultType": "vector", "result": [
+            {"metric": {}, "value": [1700000000.0, "0.25"]},
+            {"metric": {}, "value": [1700000000.0, "0.75"]},
+        ]}});
+        assert_eq!(parse(vector), Ok(0.25));
+        let scalar = json!({"status": "success", "data": {
+            "resultType": "scalar", "result": [1700000000.0, "3"],
+        }});
+        assert_eq!(parse(scalar), Ok(3.0));
+    }
+
+    #[test]
+    fn unusable_answers_are_errors() {
+        let empty = json!({"status": "success", "data": {"resultType": "vector", "result": []}});
+        assert!(parse(empty).is_err());
+        let nan = json!({"data": {"resultType": "vector", "result": [{"value": [0, "NaN"]}]}});
+        assert!(parse(nan).is_err());
+        let text = json!({"data": {"resultType": "vector", "result": [{"value": [0, "high"]}]}});
+        assert!(parse(text).is_err());
+        assert!(parse_sample(b"<html>").is_err());
+    }
+
+    #[test]
+    fn queries_wait_for_the_interval() {
+        let analysis = CanaryAnalysis {
+            interval_seconds: Some(30),
+            ..Default::default()
+        };
+        let now = Utc::now();
+        let analyzed = |seconds_ago| CanaryStatus {
+            analyzed_at: Some((now - Duration::from_secs(seconds_ago)).to_rfc3339()),
+            ..Default::default()
+        };
+        assert!(due(&analysis, None, now));
+        assert!(!due(&analysis, Some(&analyzed(10)), now));
+        assert!(due(&analysis, Some(&analyzed(30)), now));
+    }
+
+    #[test]
+    fn rollback_needs_consecutive_failures() {
+        let analysis = CanaryAnalysis::default();
+        let mut count = 0;
+        for verdict in [
+            Verdict::Failed(2.0),
+            Verdict::Inconclusive("timed out".into()),
+            Verdict::Failed(2.0),
+        ] {
+            count = failures(count, &verdict);
+        }
+        assert_eq!(count, 2);
+        assert!(!exhausted(&analysis, count));
+        // a pass in between starts the count over
+        assert_eq!(failures(count, &Verdict::Passed), 0);
+        count = failures(count, &Verdict::Failed(2.0));
+        assert!(exhausted(&analysis, count));
+    }
+}
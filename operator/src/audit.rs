@@ -0,0 +1,56 @@
+use std::{
+    fs, io,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use kube::{Resource, ResourceExt};
+use serde::Serialize;
+
+use crate::config::Config;
+
+/// Writes `obj` to `AUDIT_DIR` as `<unix-nanos>-<namespace>-<kind>-<name>.yaml` just before it
+/// is applied, then drops the oldest records beyond `AUDIT_MAX_FILES`. A no-op when auditing is
+/// off; a failed write fails the apply, so nothing goes out unrecorded.
+pub fn record<K>(config: &Config, kind: &str, obj: &K) -> io::Result<()>
+where
+    K: Resource + Serialize,
+{
+    let Some(dir) = &config.audit_dir else {
+        return Ok(());
+    };
+    fs::create_dir_all(dir)?;
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let file = format!(
+        "{:020}-{}-{}-{}.yaml",
+        nanos,
+        obj.namespace().unwrap_or_default(),
+        kind,
+        obj.name_any()
+    );
+    let yaml = serde_yaml::to_string(obj).map_err(io::Error::other)?;
+    fs::write(dir.join(file), yaml)?;
+
+    rotate(dir, config.audit_max_files)
+}
+
+/// Timestamps are zero-padded, so name order is write order and the first names go first.
+fn rotate(dir: &Path, keep: usize) -> io::Result<()> {
+    let mut records: Vec<_> = fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "yaml"))
+        .collect();
+    if records.len() <= keep {
+        return Ok(());
+    }
+    records.sort();
+    for path in &records[..records.len() - keep] {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Delay before a failed reconcile is retried.
+pub const RETRY_DELAY: Duration = Duration::from_secs(10);
+
+struct Streak {
+    generation: Option<i64>,
+    count: u32,
+    last_failure: Instant,
+    retry_in: Duration,
+}
+
+/// Per-object record of consecutive reconcile failures, keyed by `namespace/name`.
+#[derive(Clone, Default)]
+pub struct FailureTracker(Arc<Mutex<HashMap<String, Streak>>>);
+
+impl FailureTracker {
+    /// Counts a failure that will be retried after `retry_in`, starting a fresh streak when the
+    /// spec generation has moved on.
+    pub fn record_failure(&self, key: &str, generation: Option<i64>, retry_in: Duration) -> u32 {
+        let mut streaks = self.0.lock().unwrap();
+        let streak = streaks.entry(key.into()).or_insert(Streak {
+            generation,
+            count: 0,
+            last_failure: Instant::now(),
+            retry_in,
+        });
+        if streak.generation != generation {
+            streak.generation = generation;
+            streak.count = 0;
+        }
+        streak.count += 1;
+        streak.last_failure = Instant::now();
+        streak.retry_in = retry_in;
+        streak.count
+    }
+
+    pub fn reset(&self, key: &str) {
+        self.0.lock().unwrap().remove(key);
+    }
+
+    /// Time left before a failed object should be retried. Recording the failure in status
+    /// triggers a watch event, so without this the retry delay would be skipped entirely.
+    pub fn backoff_remaining(&self, key: &str, generation: Option<i64>) -> Option<Duration> {
+        let streaks = self.0.lock().unwrap();
+        let streak = streaks.get(key).filter(|s| s.generation == generation)?;
+        streak
+            .retry_in
+            .checked_sub(streak.last_failure.elapsed())
+            .filter(|d| !d.is_zero())
+    }
+}
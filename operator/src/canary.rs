@@ -0,0 +1,173 @@
+use std::time::Duration;
+
+use k8s_openapi::chrono::{DateTime, Utc};
+
+use crate::crd::{CanaryStatus, CanaryStep};
+
+pub const ABORT_ANNOTATION: &str = "ml.jedimindtricks.example/canary-abort";
+
+/// How often a held canary is re-checked for availability.
+const HOLD_RECHECK: Duration = Duration::from_secs(15);
+
+pub struct CanaryDecision {
+    pub status: CanaryStatus,
+    pub advanced: bool,
+    pub degraded: bool,
+    pub requeue_after: Option<Duration>,
+}
+
+pub fn default_steps() -> Vec<CanaryStep> {
+    [10, 25, 50, 100]
+        .into_iter()
+        .map(|weight| CanaryStep {
+            weight,
+            pause_seconds: 60,
+        })
+        .collect()
+}
+
+/// Shadow replicas for a canary currently at `weight` percent when `scaleCanary` is set.
+pub fn scaled_replicas(replicas: i32, weight: i32) -> i32 {
+    if replicas <= 0 {
+        return replicas;
+    }
+    ((replicas * weight.clamp(0, 100) + 99) / 100).max(1)
+}
+
+/// Moves the canary forward by at most one step. A step only advances once its pause has
+/// elapsed with the canary fully available; while unavailable the step is held and its pause
+/// restarts, and a new canary image (or a previously aborted canary) starts the sequence over
+/// from the first step. A due step whose analysis has not passed is retried shortly instead.
+/// A canary that has not had a ready replica yet is still starting, so holding it is not
+/// reported as degraded.
+pub fn progress(
+    steps: &[CanaryStep],
+    image: &str,
+    current: Option<&CanaryStatus>,
+    canary_available: bool,
+    canary_ready: bool,
+    analysis_passed: bool,
+    now: DateTime<Utc>,
+) -> CanaryDecision {
+    let mut status = match current {
+        Some(c)
+            if c.image == image && !c.aborted && !c.standby && (c.step as usize) < steps.len() =>
+        {
+            c.clone()
+        }
+        _ => CanaryStatus {
+            image: image.into(),
+            step: 0,
+            weight: steps[0].weight,
+            step_started_at: Some(now.to_rfc3339()),
+            aborted: false,
+            analysis_failed: false,
+            standby: false,
+            started: false,
+            analyzed_at: None,
+            analysis_failures: 0,
+        },
+    };
+    status.started |= canary_ready;
+
+    if !canary_available {
+        status.step_started_at = Some(now.to_rfc3339());
+        return CanaryDecision {
+            degraded: status.started,
+            status,
+            advanced: false,
+            requeue_after: Some(HOLD_RECHECK),
+        };
+    }
+
+    let step = status.step as usize;
+    if step + 1 >= steps.len() {
+        return CanaryDecision {
+            status,
+            advanced: false,
+            degraded: false,
+            requeue_after: None,
+        };
+    }
+
+    let started = status
+        .step_started_at
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or(now);
+    let elapsed = (now - started).to_std().unwrap_or_default();
+    let pause = Duration::from_secs(steps[step].pause_seconds);
+
+    if elapsed < pause {
+        return CanaryDecision {
+            status,
+            advanced: false,
+            degraded: false,
+            requeue_after: Some(pause - elapsed),
+        };
+    }
+
+    if !analysis_passed {
+        return CanaryDecision {
+            status,
+            advanced: false,
+            degraded: false,
+            requeue_after: Some(HOLD_RECHECK),
+        };
+    }
+
+    status.step += 1;
+    status.weight = steps[step + 1].weight;
+    status.step_started_at = Some(now.to_rfc3339());
+
+    CanaryDecision {
+        status,
+        advanced: true,
+        degraded: false,
+        requeue_after: Some(Duration::from_secs(steps[step + 1].pause_seconds)),
+    }
+}
+
+/// Sends all traffic back to live and parks the canary until the abort annotation is cleared,
+/// or for a failed analysis, until a new shadow image is rolled out.
+pub fn abort(image: &str, current: Option<&CanaryStatus>, analysis_failed: bool) -> CanaryDecision {
+    CanaryDecision {
+        status: CanaryStatus {
+            image: image.into(),
+            step: current.map(|c| c.step).unwrap_or(0),
+            weight: 0,
+            step_started_at: None,
+            aborted: true,
+            analysis_failed,
+            standby: false,
+            started: current.is_some_and(|c| c.started),
+            analyzed_at: None,
+            analysis_failures: 0,
+        },
+        advanced: false,
+        degraded: false,
+        requeue_after: None,
+    }
+}
+
+/// Keeps all traffic on live while the shadow is a zero-replica standby.
+pub fn standby(image: &str) -> CanaryDecision {
+    CanaryDecision {
+        status: CanaryStatus {
+            image: image.into(),
+            step: 0,
+            weight: 0,
+            step_started_at: None,
+            aborted: false,
+            analysis_failed: false,
+            standby: true,
+            started: false,
+            analyzed_at: None,
+            analysis_failures: 0,
+        },
+        advanced: false,
+        degraded: false,
+        requeue_after: None,
+    }
+}
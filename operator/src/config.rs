@@ -0,0 +1,434 @@
+use std::{collections::BTreeMap, env, path::PathBuf, time::Duration};
+
+/// Instance that handles ModelDeployments without a `managed-by` annotation.
+pub const DEFAULT_INSTANCE: &str = "dev";
+
+/// Operator-level settings read from the environment at startup.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    pub defaults_configmap: Option<String>,
+    pub defaults_namespace: String,
+    pub max_replicas_cap: Option<i32>,
+    pub metrics_addr: String,
+    /// Upper bound on a single reconcile pass so a hung API call can't pin a worker.
+    pub reconcile_timeout: Duration,
+    /// DNS domain of the cluster (`CLUSTER_DOMAIN`), for the names alias Services point at.
+    pub cluster_domain: String,
+    /// Fraction (0 to below 1) the periodic requeue is randomly moved by either way
+    /// (`REQUEUE_JITTER`).
+    pub requeue_jitter: f64,
+    /// Periodic requeue interval for each phase.
+    pub resync: ResyncIntervals,
+    /// ModelDeployments reconciled at once (`RECONCILE_CONCURRENCY`); 0, the default, is
+    /// unbounded.
+    pub reconcile_concurrency: u16,
+    /// Entries of `services` and of `extraResources` a single reconcile applies at once
+    /// (`CHILD_CONCURRENCY`, at least 1). Only those two lists fan out; every other child,
+    /// and the deletion of stale entries, is written one at a time. The worst case against
+    /// the API server is this times `RECONCILE_CONCURRENCY`, so bound that one too before
+    /// raising this.
+    pub child_concurrency: usize,
+    /// Only ModelDeployments matching this selector are watched, so instances can shard by label.
+    pub label_selector: Option<String>,
+    /// `targetNamespace`s allowed per ModelDeployment namespace (`TARGET_NAMESPACES`, as
+    /// comma-separated `source=target` pairs, where a target of `*` allows any), e.g.
+    /// `models=serving-a,models=serving-b`. Empty allows none, so children stay next to their
+    /// ModelDeployment unless an operator admin says otherwise.
+    pub target_namespaces: BTreeMap<String, Vec<String>>,
+    /// Namespace ModelDeployments are watched in (`WATCH_NAMESPACE`), so the operator runs
+    /// with namespaced RBAC; unset watches every namespace.
+    pub watch_namespace: Option<String>,
+    /// Field manager for every server-side apply, children and status alike; give each operator
+    /// instance its own so `managedFields` tells them apart.
+    pub field_manager: String,
+    /// Kinds (or `*`) whose apply conflicts are resolved by forcing ownership, with a Warning
+    /// event. Empty by default, so fields owned by other managers are respected.
+    pub force_apply_kinds: Vec<String>,
+    /// Kinds `extraResources` may contain (`EXTRA_RESOURCE_KINDS`, comma-separated, or `*`
+    /// for any), as `group/Kind`, or a bare `Kind` for the core group; by default `ConfigMap`
+    /// and `monitoring.coreos.com/ServiceMonitor`.
+    pub extra_resource_kinds: Vec<String>,
+    /// Lets `volumes` mount directories of the node (`ALLOW_HOST_PATH=true`). Off by default,
+    /// since a hostPath volume can read or write anything on the node.
+    pub allow_host_path: bool,
+    /// Registries images may come from (`REGISTRY_ALLOWLIST`, comma-separated), as hosts or
+    /// `host/path` prefixes where `*` matches anything, e.g. `*.corp.example,ghcr.io/acme/*`.
+    /// Empty allows every registry.
+    pub registry_allowlist: Vec<String>,
+    /// Write status with server-side apply; `STATUS_PATCH=merge` falls back to a merge patch.
+    pub status_apply: bool,
+    /// Server-side apply the ModelDeployment CRD at startup (`INSTALL_CRD=true`) instead of
+    /// only checking that it is installed.
+    pub install_crd: bool,
+    /// Name of this operator instance (`INSTANCE_NAME`), matched against each object's
+    /// `managed-by` annotation.
+    pub instance: String,
+    /// Reporting instance on the events this process emits (`OPERATOR_INSTANCE`), by default
+    /// the pod name from `POD_NAME` (downward API) or the hostname, so events can be traced to
+    /// the replica that wrote them.
+    pub reporter_instance: String,
+    /// Directory every applied child is also written to as YAML (`AUDIT_DIR`); unset disables
+    /// the audit trail.
+    pub audit_dir: Option<PathBuf>,
+    /// Audit records kept before the oldest are deleted (`AUDIT_MAX_FILES`).
+    pub audit_max_files: usize,
+    /// Reconciles kept per object for `/debug/reconciles/<namespace>/<name>`
+    /// (`RECONCILE_HISTORY`); 0 keeps none.
+    pub history_size: usize,
+    pub features: Features,
+}
+
+/// Switches for individual reconcile behaviours, each read from `FEATURE_<NAME>=true|false`
+/// so risky ones can be turned off per environment without a new image.
+#[derive(Debug, Clone)]
+pub struct Features {
+    /// `FEATURE_CANARY_ANALYSIS` (on): query Prometheus before each canary step. Off, steps
+    /// advance on their pauses alone.
+    pub canary_analysis: bool,
+    /// `FEATURE_AUTO_PROMOTION` (on): advance canary steps once their pause is up. Off, a
+    /// canary stays at the step it has reached.
+    pub auto_promotion: bool,
+    /// `FEATURE_AUTO_ROLLBACK` (on): abort a canary whose analysis fails. Off, the failure is
+    /// only reported and the step held.
+    pub auto_rollback: bool,
+    /// `FEATURE_SETTLED_SKIP` (on): leave the children of settled objects alone between full
+    /// resyncs.
+    pub settled_skip: bool,
+    /// `FEATURE_IMAGE_CHECK` (off): look up each new live and shadow image in its registry and
+    /// warn when it does not exist. Needs egress to the registries; only public images can be
+    /// confirmed.
+    pub image_check: bool,
+}
+
+impl Default for Features {
+    fn default() -> Self {
+        Self {
+            canary_analysis: true,
+            auto_promotion: true,
+            auto_rollback: true,
+            settled_skip: true,
+            image_check: false,
+        }
+    }
+}
+
+impl Features {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let flag =
+            |name: &str, default: bool| parse_var(&format!("FEATURE_{}", name)).unwrap_or(default);
+        Self {
+            canary_analysis: flag("CANARY_ANALYSIS", defaults.canary_analysis),
+            auto_promotion: flag("AUTO_PROMOTION", defaults.auto_promotion),
+            auto_rollback: flag("AUTO_ROLLBACK", defaults.auto_rollback),
+            settled_skip: flag("SETTLED_SKIP", defaults.settled_skip),
+            image_check: flag("IMAGE_CHECK", defaults.image_check),
+        }
+    }
+}
+
+/// How often an object with nothing pending is requeued, by its last computed phase: broken or
+/// converging ones are watched closely, stable ones left mostly alone. Each is read from
+/// `RESYNC_<PHASE>_SECS`.
+#[derive(Debug, Clone)]
+pub struct ResyncIntervals {
+    /// `RESYNC_PROGRESSING_SECS` (10).
+    pub progressing: Duration,
+    /// `RESYNC_DEGRADED_SECS` (10).
+    pub degraded: Duration,
+    /// `RESYNC_AVAILABLE_SECS` (300).
+    pub available: Duration,
+    /// `RESYNC_DEFAULT_SECS` (60): any other phase, e.g. Suspended, or none yet.
+    pub default: Duration,
+}
+
+impl Default for ResyncIntervals {
+    fn default() -> Self {
+        Self {
+            progressing: Duration::from_secs(10),
+            degraded: Duration::from_secs(10),
+            available: Duration::from_secs(300),
+            default: Duration::from_secs(60),
+        }
+    }
+}
+
+impl ResyncIntervals {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let secs = |name: &str, default: Duration| {
+            parse_var(&format!("RESYNC_{}_SECS", name))
+                .filter(|s| *s > 0)
+                .map_or(default, Duration::from_secs)
+        };
+        Self {
+            progressing: secs("PROGRESSING", defaults.progressing),
+            degraded: secs("DEGRADED", defaults.degraded),
+            available: secs("AVAILABLE", defaults.available),
+            default: secs("DEFAULT", defaults.default),
+        }
+    }
+
+    pub fn for_phase(&self, phase: Option<&str>) -> Duration {
+        match phase {
+            Some("Progressing") => self.progressing,
+            Some("Degraded") => self.degraded,
+            Some("Available") => self.available,
+            _ => self.default,
+        }
+    }
+}
+
+impl Config {
+    pub fn from_env() -> Self {
+        Self {
+            defaults_configmap: env::var("DEFAULTS_CONFIGMAP").ok(),
+            defaults_namespace: env::var("DEFAULTS_CONFIGMAP_NAMESPACE")
+                .unwrap_or_else(|_| "default".into()),
+            max_replicas_cap: parse_var("MAX_REPLICAS_CAP"),
+            metrics_addr: env::var("METRICS_ADDR").unwrap_or_else(|_| "0.0.0.0:9090".into()),
+            label_selector: env::var("LABEL_SELECTOR").ok().filter(|s| !s.is_empty()),
+            watch_namespace: env::var("WATCH_NAMESPACE").ok().filter(|s| !s.is_empty()),
+            target_namespaces: parse_target_namespaces(
+                &env::var("TARGET_NAMESPACES").unwrap_or_default(),
+            ),
+            field_manager: env::var("FIELD_MANAGER")
+                .ok()
+                .filter(|s| !s.is_empty())
+                .unwrap_or_else(|| "model-operator".into()),
+            force_apply_kinds: env::var("FORCE_APPLY_KINDS")
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|k| !k.is_empty())
+                .map(String::from)
+                .collect(),
+            extra_resource_kinds: env::var("EXTRA_RESOURCE_KINDS")
+                .unwrap_or_else(|_| "ConfigMap,monitoring.coreos.com/ServiceMonitor".into())
+                .split(',')
+                .map(str::trim)
+                .filter(|k| !k.is_empty())
+                .map(String::from)
+                .collect(),
+            allow_host_path: parse_var("ALLOW_HOST_PATH").unwrap_or(false),
+            registry_allowlist: env::var("REGISTRY_ALLOWLIST")
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .map(String::from)
+                .collect(),
+            status_apply: match env::var("STATUS_PATCH").as_deref() {
+                Ok("merge") => false,
+                Ok("apply") | Err(_) => true,
+                Ok(other) => {
+                    tracing::warn!("Ignoring invalid value {:?} for STATUS_PATCH", other);
+                    true
+                }
+            },
+            install_crd: parse_var("INSTALL_CRD").unwrap_or(false),
+            instance: env::var("INSTANCE_NAME")
+                .ok()
+                .filter(|s| !s.is_empty())
+                .unwrap_or_else(|| DEFAULT_INSTANCE.into()),
+            reporter_instance: ["OPERATOR_INSTANCE", "POD_NAME", "HOSTNAME"]
+                .into_iter()
+                .find_map(|name| env::var(name).ok().filter(|s| !s.is_empty()))
+                .or_else(|| {
+                    std::fs::read_to_string("/etc/hostname")
+                        .ok()
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                })
+                .unwrap_or_else(|| DEFAULT_INSTANCE.into()),
+            audit_dir: env::var_os("AUDIT_DIR")
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from),
+            audit_max_files: parse_var("AUDIT_MAX_FILES").unwrap_or(1000),
+            history_size: parse_var("RECONCILE_HISTORY").unwrap_or(20),
+            features: Features::from_env(),
+            requeue_jitter: parse_var::<f64>("REQUEUE_JITTER")
+                .filter(|j| (0.0..1.0).contains(j))
+                .unwrap_or(0.1),
+            resync: ResyncIntervals::from_env(),
+            reconcile_concurrency: parse_var("RECONCILE_CONCURRENCY").unwrap_or(0),
+            child_concurrency: parse_var::<usize>("CHILD_CONCURRENCY")
+                .filter(|&n| n > 0)
+                .unwrap_or(4),
+            reconcile_timeout: Duration::from_secs(
+                parse_var("RECONCILE_TIMEOUT_SECS").unwrap_or(120),
+            ),
+            cluster_domain: env::var("CLUSTER_DOMAIN")
+                .ok()
+                .filter(|s| !s.is_empty())
+                .unwrap_or_else(|| "cluster.local".into()),
+        }
+    }
+}
+
+fn parse_var<T: std::str::FromStr>(name: &str) -> Option<T> {
+    let raw = env::var(name).ok()?;
+    match raw.parse() {
+        Ok(value) => Some(value),
+        Err(_) => {
+            tracing::warn!("Ignoring invalid value {:?} for {}", raw, name);
+            None
+        }
+    }
+}
+
+/// Reads `TARGET_NAMESPACES`, skipping malformed pairs with a warning.
+fn parse_target_namespaces(raw: &str) -> BTreeMap<String, Vec<String>> {
+    let mut allowed: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for pair in raw.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        match pair.split_once('=').map(|(s, t)| (s.trim(), t.trim())) {
+            Some((source, target)) if !source.is_empty() && !target.is_empty() => {
+                allowed
+                    .entry(source.into())
+                    .or_default()
+                    .push(target.into());
+            }
+            _ => tracing::warn!("Ignoring invalid TARGET_NAMESPACES entry {:?}", pair),
+        }
+    }
+    allowed
+}
+
+impl Config {
+    /// Whether a ModelDeployment in `source` may put its children in `target`.
+    pub fn target_namespace_allowed(&self, source: &str, target: &str) -> bool {
+        source == target
+            || self
+                .target_namespaces
+                .get(source)
+                .is_some_and(|targets| targets.iter().any(|t| t == "*" || t == target))
+    }
+}
+
+/// Checks `LABEL_SELECTOR` syntax up front: the API server would otherwise reject every watch
+/// request and the operator would sit there retrying.
+pub fn check_label_selector(selector: &str) -> Result<(), String> {
+    let valid_key = |key: &str| {
+        !key.is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_./".contains(c))
+    };
+    let valid_value = |value: &str| {
+        value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c))
+    };
+
+    // split on commas that are not inside an `in (...)` value list
+    let mut terms = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in selector.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                terms.push(&selector[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    terms.push(&selector[start..]);
+
+    for term in terms.iter().map(|t| t.trim()) {
+        let ok = if let Some(key) = term.strip_prefix('!') {
+            valid_key(key.trim())
+        } else if let Some((key, rest)) = term
+            .split_once(" notin ")
+            .or_else(|| term.split_once(" in "))
+        {
+            let rest = rest.trim();
+            valid_key(key.trim())
+                && rest.starts_with('(')
+                && rest.ends_with(')')
+                && rest[1..rest.len() - 1]
+                    .split(',')
+                    .all(|v| valid_value(v.trim()))
+        } else if let Some((key, value)) = term
+            .split_once("!=")
+            .or_else(|| term.split_once("=="))
+            .or_else(|| term.split_once('='))
+        {
+            valid_key(key.trim()) && valid_value(value.trim())
+        } else {
+            valid_key(term)
+        };
+        if !ok {
+            return Err(format!(
+                "invalid LABEL_SELECTOR term {:?} in {:?}",
+                term, selector
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn label_selector_accepts_every_operator() {
+        for selector in [
+            "app",
+            "!canary",
+            "app=model",
+            "app==model",
+            "env!=prod",
+            "tier in (gpu, cpu)",
+            "tier notin (gpu,cpu)",
+            "example.com/team=ml,app in (a,b),!legacy",
+        ] {
+            assert_eq!(check_label_selector(selector), Ok(()), "{}", selector);
+        }
+    }
+
+    #[test]
+    fn label_selector_ignores_whitespace_around_terms() {
+        assert_eq!(check_label_selector(" app = model , ! legacy "), Ok(()));
+        assert_eq!(
+            check_label_selector("tier in ( gpu , cpu ),env=prod"),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn label_selector_rejects_malformed_terms() {
+        for selector in [
+            "",
+            "=model",
+            "app=a b",
+            "app,,env",
+            "tier in gpu",
+            "tier in (gpu",
+            "tier notin (gpu, c pu)",
+            "!",
+            "app:model",
+        ] {
+            assert!(check_label_selector(selector).is_err(), "{:?}", selector);
+        }
+    }
+
+    #[test]
+    fn target_namespaces_are_grouped_by_source() {
+        let allowed =
+            parse_target_namespaces(" models=serving-a, models = serving-b,ops=*,broken,=x");
+        assert_eq!(
+            allowed,
+            BTreeMap::from([
+                (
+                    "models".into(),
+                    vec!["serving-a".into(), "serving-b".into()]
+                ),
+                ("ops".into(), vec!["*".into()]),
+            ])
+        );
+        assert!(parse_target_namespaces("").is_empty());
+    }
+}
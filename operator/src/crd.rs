@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use kube::CustomResource;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -12,29 +14,434 @@ use serde::{Deserialize, Serialize};
     derive = "Default",
     status = "ModelDeploymentStatus",
     shortname = "md",
+    printcolumn = r#"{"name":"Phase","type":"string","jsonPath":".status.phase"}"#,
+    printcolumn = r#"{"name":"Live","type":"string","description":"Available/desired live replicas","jsonPath":".status.liveStatus.ready"}"#,
+    printcolumn = r#"{"name":"Shadow","type":"string","description":"Available/desired shadow replicas","jsonPath":".status.shadowStatus.ready"}"#,
+    printcolumn = r#"{"name":"Age","type":"date","jsonPath":".metadata.creationTimestamp"}"#,
     namespaced
 )]
 pub struct ModelDeploymentSpec {
     pub live: ModelVariant,
     pub shadow: Option<ModelVariant>,
 
+    /// Overrides `live.replicas`. See `effective_replicas` for the full precedence order.
+    #[serde(default)]
+    pub replicas: Option<i32>,
+
     #[serde(default)]
     pub traffic_mirror: bool,
 
+    /// Share of live requests (0-100) `trafficMirror` copies to the shadow. Defaults to 100.
+    #[serde(default)]
+    pub mirror_percent: Option<i32>,
+
+    /// Time-boxes `trafficMirror`: requests are only copied to the shadow between `start` and
+    /// `end`, which caps what an expensive shadow costs. The mirror samples
+    /// `mirrorPercent` at random, not by client, so the same caller may be mirrored one
+    /// request and not the next.
+    #[serde(default)]
+    pub mirror_window: Option<TimeWindow>,
+
+    /// Seconds a pod keeps serving after it is told to stop: the serving containers get a
+    /// `preStop` sleep this long (Kubernetes 1.30+), and deleting the ModelDeployment first
+    /// removes its IngressRoutes and waits this long before the children go.
+    #[serde(default)]
+    pub drain_seconds: Option<u32>,
+
+    /// An image to roll back to, e.g. the previous release, pulled onto the live pods' nodes
+    /// ahead of time by an init container that runs `true` from it, so a rollback starts
+    /// without a cold pull. The image must ship a `true` binary.
+    #[serde(default)]
+    pub fallback_image: Option<String>,
+
+    /// Traefik matchers that limit `trafficMirror` to some requests, e.g.
+    /// ``Method(`GET`) && PathPrefix(`/predict`)``. Matching requests take a route that
+    /// mirrors; the rest go straight to live, which still serves everything.
+    #[serde(default)]
+    pub mirror_match: Option<String>,
+
+    /// Scales live to zero once it has been idle for a while, and back to its replicas when
+    /// traffic or an activation returns. An activator (out of scope here) wakes it by setting
+    /// the `ml.jedimindtricks.example/activate` annotation to the current RFC3339 time.
+    /// Not combined with `autoscaling` or a canary.
+    #[serde(default)]
+    pub scale_to_zero: Option<ScaleToZeroSpec>,
+
+    /// Holds the mirror back when it is first set up until the shadow is fully available, so
+    /// mirrored requests don't hit a shadow with no ready pods.
+    #[serde(default)]
+    pub wait_for_shadow_ready: bool,
+
+    /// `rolling` (the default), `canary` to shift traffic to the shadow in steps, or `inPlace`
+    /// to resize running pods when only resources change instead of rolling them.
     #[serde(default = "default_rollout")]
     pub rollout_strategy: String,
 
     #[serde(default)]
     pub resources: Option<ResourceSpec>,
 
+    /// Size of a memory-backed `/dev/shm`, e.g. `2Gi`, for frameworks that pass tensors
+    /// between processes through it (PyTorch DataLoader workers, multiprocessing). The
+    /// container runtime's default is 64Mi. It counts against the pod's memory limit.
+    #[serde(default)]
+    pub shared_memory: Option<String>,
+
+    /// While `resources` is unset, recommends requests from the live pods' observed usage in
+    /// `status.resourceRecommendation`. Copying it into `resources` is left to you. Needs
+    /// metrics-server.
+    #[serde(default)]
+    pub right_size: bool,
+
     #[serde(default)]
     pub autoscaling: Option<AutoScalingSpec>,
 
     #[serde(default)]
     pub probes: Option<ProbeSpec>,
 
+    /// ConfigMap with the model's configuration, mounted through `volumes`. A hash of its data
+    /// is stamped on the pods, so changing the data rolls them.
     #[serde(default)]
     pub config_ref: Option<String>,
+
+    /// The model's configuration inline, instead of `configRef`: rendered into a
+    /// `<name>-config` ConfigMap we own and mounted read-only at `/etc/model-config`, one file
+    /// per key. Changing it rolls the pods; removing it deletes the ConfigMap.
+    #[serde(default)]
+    pub inline_config: Option<BTreeMap<String, String>>,
+
+    /// Name of the serving port, which Services and probes then refer to instead of its number;
+    /// an IANA service name of at most 15 characters.
+    #[serde(default)]
+    pub port_name: Option<String>,
+
+    /// `ClusterIP` (default), `NodePort` or `LoadBalancer` for the live and shadow Services.
+    #[serde(default)]
+    pub service_type: Option<String>,
+
+    /// CIDRs allowed through the cloud load balancer. Like `serviceAnnotations`, it is set on
+    /// every Service of type `LoadBalancer` and left off the others.
+    #[serde(default)]
+    pub load_balancer_source_ranges: Option<Vec<String>>,
+
+    /// Cloud provider settings for load balancers, e.g. an internal or NLB-type one.
+    #[serde(default)]
+    pub service_annotations: Option<BTreeMap<String, String>>,
+
+    /// `Cluster` or `Local`. `Local` keeps client source IPs and skips a hop; only applies to
+    /// NodePort and LoadBalancer Services and is left off ClusterIP ones. Rejected when every
+    /// Service, `services` included, is ClusterIP.
+    #[serde(default)]
+    pub external_traffic_policy: Option<String>,
+
+    /// `Cluster` or `Local`; `Local` only routes in-cluster traffic to pods on the same node.
+    #[serde(default)]
+    pub internal_traffic_policy: Option<String>,
+
+    /// `PreferClose` routes to endpoints in the client's zone when there are any. Needs
+    /// Kubernetes 1.31 or newer; older clusters get a warning and the field is left off.
+    #[serde(default)]
+    pub traffic_distribution: Option<String>,
+
+    /// `ClientIP` pins each client to one model pod; `None` (the Kubernetes default) does not.
+    #[serde(default)]
+    pub session_affinity: Option<String>,
+
+    /// How long a `ClientIP` pin lasts; Kubernetes defaults to 3 hours.
+    #[serde(default)]
+    pub session_affinity_timeout_seconds: Option<i32>,
+
+    /// Lists pods in the Services' endpoints before they are ready, for clients (e.g. gRPC)
+    /// that run their own health checks.
+    #[serde(default)]
+    pub publish_not_ready_addresses: Option<bool>,
+
+    /// Additional Services over the live pods, e.g. a LoadBalancer next to the default
+    /// ClusterIP. The per-role live/shadow Services are kept regardless, as
+    /// routing points at them (see `service` for handing the live one over).
+    #[serde(default)]
+    pub services: Option<Vec<ServiceConfig>>,
+
+    /// Adds an ExternalName Service named after the ModelDeployment that points at whatever
+    /// serves live traffic: the live Service, or the `scaleToZero` activator while live is at
+    /// zero. Callers keep one DNS name while the backend behind it changes.
+    #[serde(default)]
+    pub alias_service: bool,
+
+    /// Name templates for the Deployments and Services, to fit a naming standard. Also settable
+    /// for every object through the operator defaults.
+    #[serde(default)]
+    pub naming: Option<NamingSpec>,
+
+    /// Whether the operator owns the live Service. Turn `manage` off to route to a Service
+    /// managed elsewhere (Helm, Argo CD) instead; the shadow Service is still managed.
+    #[serde(default)]
+    pub service: Option<LiveServiceConfig>,
+
+    /// Additional container ports (metrics, admin, ...) also exposed on the Services.
+    #[serde(default)]
+    pub extra_ports: Option<Vec<ExtraPort>>,
+
+    /// Runs the pods in the node's network namespace, for edge and bare-metal serving without
+    /// cluster networking. Every port is then bound on the node itself.
+    #[serde(default)]
+    pub host_network: Option<bool>,
+
+    /// `false` keeps the service account token out of the pods, for models that never call
+    /// the Kubernetes API. Left to Kubernetes (mounted) when unset.
+    #[serde(default)]
+    pub automount_service_account_token: Option<bool>,
+
+    /// `false` stops Kubernetes injecting `<SERVICE>_HOST`/`_PORT` variables for every Service
+    /// in the namespace into the pods. Left to Kubernetes (injected) when unset.
+    #[serde(default)]
+    pub enable_service_links: Option<bool>,
+
+    /// Scheduler for the model pods, e.g. `volcano` for gang scheduling; the default
+    /// scheduler when unset. Annotations it reads go in `podAnnotations`.
+    #[serde(default)]
+    pub scheduler_name: Option<String>,
+
+    /// Annotations added to the model pods, e.g. `scheduling.k8s.io/group-name` for a gang
+    /// scheduler. Keys in the operator's own domain are rejected.
+    #[serde(default)]
+    pub pod_annotations: Option<BTreeMap<String, String>>,
+
+    /// Labels such as `team` or `cost-center` added to every child and to the model pods, for
+    /// cost tools that allocate by label. Never part of a selector; the operator's own labels
+    /// win on a clash.
+    #[serde(default)]
+    pub cost_labels: Option<BTreeMap<String, String>>,
+
+    /// Node port the serving port is also bound to. With `hostNetwork` it must equal the
+    /// serving port.
+    #[serde(default)]
+    pub host_port: Option<i32>,
+
+    #[serde(default)]
+    pub domain: Option<String>,
+
+    /// Parks the model: Deployments are scaled to zero (autoscaling is ignored) while
+    /// Services and routing are kept so resuming is just flipping this back.
+    #[serde(default)]
+    pub suspend: bool,
+
+    /// `LiveFirst` holds shadow changes back until live runs its release and is fully
+    /// available, so an experiment never starts next to a broken live; `ShadowFirst` holds
+    /// live back on the shadow instead. Both roll out together when unset.
+    #[serde(default)]
+    pub rollout_order: Option<String>,
+
+    /// Node placement for both variants unless a variant sets its own.
+    #[serde(default)]
+    pub scheduling: Option<SchedulingSpec>,
+
+    /// Seconds a new pod must stay ready before it counts as available, for both variants
+    /// unless a variant sets its own.
+    #[serde(default)]
+    pub min_ready_seconds: Option<i32>,
+
+    /// Seconds a rollout may go without progress before Kubernetes marks it failed, for both
+    /// variants unless a variant sets its own. Kubernetes defaults to 600.
+    #[serde(default)]
+    pub progress_deadline_seconds: Option<i32>,
+
+    /// Pod condition types (e.g. set by a service mesh) that must be True before a pod counts
+    /// as ready, and so before it is available or receives traffic.
+    #[serde(default)]
+    pub readiness_gates: Option<Vec<String>>,
+
+    /// Working directory of the model container, for runtimes that resolve model paths
+    /// relative to it; defaults to the image's own.
+    #[serde(default)]
+    pub working_dir: Option<String>,
+
+    /// `FallbackToLogsOnError` (default) puts the end of the log in a crashed container's
+    /// termination message, reported in the `ContainerCrashed` condition; `File` only reads
+    /// `/dev/termination-log`.
+    #[serde(default)]
+    pub termination_message_policy: Option<String>,
+
+    /// Keeps stdin open on the model container, for runtimes driven interactively.
+    #[serde(default)]
+    pub stdin: bool,
+
+    /// Allocates a TTY for the model container; usually combined with `stdin`.
+    #[serde(default)]
+    pub tty: bool,
+
+    /// Adds a `debug` sidecar to every model pod; clearing it removes the sidecar again.
+    #[serde(default)]
+    pub debug: bool,
+
+    /// Image for the debug sidecar; defaults to busybox.
+    #[serde(default)]
+    pub debug_sidecar_image: Option<String>,
+
+    /// Traffic weights the shadow is stepped through when `rolloutStrategy` is `canary`.
+    #[serde(default)]
+    pub canary_steps: Option<Vec<CanaryStep>>,
+
+    /// Runs the canary at its traffic share of `shadow.replicas` (rounded up, at least one)
+    /// instead of at full size, so a 10% canary costs about 10% of the replicas.
+    #[serde(default)]
+    pub scale_canary: bool,
+
+    /// Prometheus check run before each canary step; a failing result aborts the canary.
+    #[serde(default)]
+    pub canary_analysis: Option<CanaryAnalysis>,
+
+    /// Traefik entrypoints the model is exposed on; defaults to a single plain `web` route.
+    #[serde(default)]
+    pub routes: Option<Vec<RouteSpec>>,
+
+    /// Traefik rule used verbatim instead of the generated ``Host(`<name>.<domain>`)``, e.g.
+    /// ``Host(`m.example.com`) && Method(`POST`) && Path(`/predict`)``. It is passed through
+    /// unchecked, with a Warning event saying so, so a bad rule only shows up in Traefik's own
+    /// logs. `status.endpoints` lists the hosts of its ``Host()`` matchers.
+    #[serde(default)]
+    pub match_rule: Option<String>,
+
+    /// Traefik rule priority of the IngressRoutes, so the model's route wins over a catch-all
+    /// on the same host; Traefik otherwise ranks rules by length.
+    #[serde(default)]
+    pub route_priority: Option<i64>,
+
+    /// Pins each client to one backend with a cookie set by Traefik.
+    #[serde(default)]
+    pub sticky_cookie: Option<StickyCookieConfig>,
+
+    #[serde(default)]
+    pub adoption_policy: AdoptionPolicy,
+
+    /// Namespace the children are created in, when it isn't this ModelDeployment's own; the
+    /// operator's `TARGET_NAMESPACES` must allow it for this namespace. Owner references cannot cross namespaces, so such children carry an owner label
+    /// instead and are deleted by the finalizer rather than by garbage collection. Changing
+    /// it leaves the children in the previous namespace behind.
+    #[serde(default)]
+    pub target_namespace: Option<String>,
+
+    /// How children are written. `Apply` (default) uses server-side apply: fields we stop
+    /// setting are removed and fields another manager owns raise conflicts. `Merge` patches
+    /// the whole object instead, which never conflicts and always wins, but leaves fields we
+    /// stop setting behind: built-in kinds get a strategic merge patch, which merges lists by
+    /// key, while custom resources (Traefik routes, CRD `extraResources`) get a JSON merge
+    /// patch, which replaces lists whole and never propagates a removal. Drift detection is
+    /// the same for both.
+    #[serde(default)]
+    pub patch_strategy: Option<String>,
+
+    /// Raw namespaced objects (a ConfigMap, a ServiceMonitor, ...) applied next to the model
+    /// and owned by it. Each needs `apiVersion`, `kind` and `metadata.name`, and its group and
+    /// kind must be allowed by the operator's `EXTRA_RESOURCE_KINDS`. Workloads (Pods, Deployments,
+    /// Jobs, ...) are refused whatever that allows; entries removed from the list are deleted.
+    #[serde(default)]
+    #[schemars(schema_with = "raw_objects_schema")]
+    pub extra_resources: Option<Vec<serde_json::Value>>,
+
+    /// Escape hatch for pod settings without a field of their own: a JSON merge patch (RFC
+    /// 7386) applied over the generated pod template, e.g. `{"spec": {"securityContext":
+    /// {...}}}`. Lists such as `spec.containers` are replaced whole and `null` removes a
+    /// field, so it can overwrite what the operator sets; that is at your own risk. The `app`
+    /// and `role` pod labels cannot be changed.
+    #[serde(default)]
+    #[schemars(schema_with = "raw_object_schema")]
+    pub pod_template_overlay: Option<serde_json::Value>,
+
+    /// Refuses to move `live.image` to an older numeric or semver tag than the one running,
+    /// unless the `ml.jedimindtricks.example/allow-downgrade: "true"` annotation is set. The
+    /// live Deployment keeps its running image meanwhile, under an `ImageDowngradeBlocked`
+    /// condition; the rest of the spec is still applied.
+    #[serde(default)]
+    pub block_image_downgrades: bool,
+
+    /// Restricts ingress to the model pods to the listed sources, on the ports they declare.
+    /// Listing no sources blocks all ingress.
+    #[serde(default)]
+    pub network_policy: Option<NetworkPolicyConfig>,
+
+    /// A PodDisruptionBudget for the live pods, so node drains take them down a few at a time.
+    /// Inside `maintenanceWindow` it is tightened to `maxUnavailableDuringMaintenance`.
+    #[serde(default)]
+    pub disruption_budget: Option<DisruptionBudgetConfig>,
+
+    /// Change freeze: while inside the window children are left untouched, save for tightening
+    /// the `disruptionBudget`, and only status is refreshed. Spec changes made meanwhile are
+    /// applied once the window closes.
+    #[serde(default)]
+    pub maintenance_window: Option<TimeWindow>,
+
+    /// Quiet period after a spec change before it is applied. Each further change restarts
+    /// it, so a burst of edits becomes one rollout; a new object and a force-sync are applied
+    /// at once.
+    #[serde(default)]
+    pub rollout_debounce_seconds: Option<u32>,
+}
+
+impl ModelDeploymentSpec {
+    pub fn effective_routes(&self) -> Vec<RouteSpec> {
+        match &self.routes {
+            Some(routes) if !routes.is_empty() => routes.clone(),
+            _ => vec![RouteSpec {
+                entry_point: "web".into(),
+                tls: None,
+            }],
+        }
+    }
+
+    /// A shadow deliberately scaled to zero: configured and ready to start, but without pods
+    /// and therefore without traffic.
+    pub fn shadow_standby(&self) -> bool {
+        !self.suspend && self.shadow.as_ref().is_some_and(|s| s.replicas == 0)
+    }
+
+    /// The Service routing sends live traffic to: ours unless `service.manage` is off.
+    pub fn live_service_name(&self, base_name: &str) -> String {
+        self.service
+            .as_ref()
+            .filter(|s| !s.manage)
+            .and_then(|s| s.existing_name.clone())
+            .unwrap_or_else(|| self.service_name(base_name, "live"))
+    }
+
+    /// Name of the `role` (`live` or `shadow`) Deployment, and of the live HPA.
+    pub fn deployment_name(&self, base_name: &str, role: &str) -> String {
+        let template = self.naming.as_ref().and_then(|n| n.deployment.as_deref());
+        render_name(template.unwrap_or(DEFAULT_DEPLOYMENT_NAME), base_name, role)
+    }
+
+    /// Name of the `role` Service: `live`, `shadow`, or an entry in `services`.
+    pub fn service_name(&self, base_name: &str, role: &str) -> String {
+        let template = self.naming.as_ref().and_then(|n| n.service.as_deref());
+        render_name(template.unwrap_or(DEFAULT_SERVICE_NAME), base_name, role)
+    }
+
+    /// A canary routes a share of live traffic to the shadow instead of mirroring it.
+    pub fn is_canary(&self) -> bool {
+        self.rollout_strategy == "canary" && self.shadow.is_some()
+    }
+}
+
+pub const DEFAULT_DEPLOYMENT_NAME: &str = "{name}-{role}";
+pub const DEFAULT_SERVICE_NAME: &str = "{name}-{role}-svc";
+
+pub fn render_name(template: &str, base_name: &str, role: &str) -> String {
+    template
+        .replace("{name}", base_name)
+        .replace("{role}", role)
+}
+
+/// Templates over `{name}` (the ModelDeployment's, or the `adopt-from` one) and `{role}`.
+/// Changing one on a running object creates renamed children; the old ones stay until the
+/// ModelDeployment is deleted.
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NamingSpec {
+    /// `{name}-{role}` when unset.
+    #[serde(default)]
+    pub deployment: Option<String>,
+    /// `{name}-{role}-svc` when unset.
+    #[serde(default)]
+    pub service: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
@@ -43,6 +450,384 @@ pub struct ModelVariant {
     pub image: String,
     #[serde(default = "default_replicas")]
     pub replicas: i32,
+    /// When false `replicas` is left out of the Deployment entirely, for setups where an
+    /// external controller owns scaling. On live, a scaler may instead set the
+    /// `ml.jedimindtricks.example/desired-replicas` annotation, which is then applied.
+    #[serde(default = "default_true")]
+    pub manage_replicas: bool,
+    /// Port the model server listens on, 8000 by default. The role's Service exposes the same
+    /// port and routing follows it, so a shadow image listening elsewhere can still be mirrored.
+    #[serde(default)]
+    pub port: Option<i32>,
+    /// Old ReplicaSets kept for rollback. Defaults to the Kubernetes default (10) for live and
+    /// to 0 for a shadow, whose history is throwaway.
+    #[serde(default)]
+    pub revision_history_limit: Option<i32>,
+    /// Deletes old ReplicaSets of this Deployment that are scaled to zero but beyond
+    /// `revisionHistoryLimit`, such as ones a failed rollout left behind.
+    #[serde(default)]
+    pub prune_replica_sets: bool,
+    /// Sets the Deployment's own `spec.paused`: template changes are applied but not rolled
+    /// out until this is cleared. Unlike `suspend`, running pods and replica counts are kept.
+    #[serde(default)]
+    pub pause_rollout: bool,
+    /// Downloads the model in an init container and refuses to start the server unless its
+    /// SHA256 matches. The file is mounted into the server at `MODEL_PATH`.
+    #[serde(default)]
+    pub verify_checksum: Option<ModelChecksum>,
+    /// Replaces the spec-level `scheduling` for this variant, e.g. live on GPU, shadow on CPU.
+    #[serde(default)]
+    pub scheduling: Option<SchedulingSpec>,
+    /// Overrides the spec-level `minReadySeconds`.
+    #[serde(default)]
+    pub min_ready_seconds: Option<i32>,
+    /// Overrides the spec-level `progressDeadlineSeconds`, e.g. a short deadline for live so
+    /// bad rollouts fail fast while a shadow experiment gets longer.
+    #[serde(default)]
+    pub progress_deadline_seconds: Option<i32>,
+    /// Secrets (API keys, Hugging Face tokens, cloud credentials) handed to the model server.
+    #[serde(default)]
+    pub secret_refs: Option<Vec<SecretRef>>,
+    /// Environment variables for the model server, set after `MODEL_PATH`.
+    #[serde(default)]
+    pub env: Option<Vec<EnvEntry>>,
+    /// Extra volumes mounted into the model server, e.g. an object store bucket through a CSI
+    /// driver for models too large to bake into the image.
+    #[serde(default)]
+    pub volumes: Option<Vec<VolumeConfig>>,
+    /// A second container version-coupled to the model, e.g. its tokenizer. It runs in the same
+    /// pods, and image changes, canaries and rollbacks treat the two images as one release.
+    #[serde(default)]
+    pub companion: Option<CompanionContainer>,
+    /// Further serving containers in the same pods, e.g. the members of an ensemble. Unlike
+    /// the `companion` they are served: each port is exposed on the role's Service under the
+    /// model's name, and each model's readiness is reported in status.
+    #[serde(default)]
+    pub models: Option<Vec<ModelContainer>>,
+}
+
+impl ModelVariant {
+    /// The images rolled out together: `image`, plus the companion's when there is one.
+    pub fn release(&self) -> String {
+        release(
+            &self.image,
+            self.companion.as_ref().map(|c| c.image.as_str()),
+        )
+    }
+}
+
+/// How a model image and its companion's are recorded in status and events.
+pub fn release(image: &str, companion: Option<&str>) -> String {
+    match companion {
+        Some(companion) => format!("{} + {}", image, companion),
+        None => image.into(),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CompanionContainer {
+    pub image: String,
+    /// Port the companion listens on inside the pod; not exposed on the Services.
+    #[serde(default)]
+    pub port: Option<i32>,
+    #[serde(default)]
+    pub args: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelContainer {
+    /// Container and port name: a DNS label of at most 15 characters.
+    pub name: String,
+    pub image: String,
+    /// Port the model listens on; the readiness and liveness probes use it too.
+    pub port: i32,
+    /// Overrides the spec-level `resources` for this container.
+    #[serde(default)]
+    pub resources: Option<ResourceSpec>,
+    #[serde(default)]
+    pub args: Option<Vec<String>>,
+}
+
+/// A volume and where it is mounted. Exactly one source (`persistentVolumeClaim`, `emptyDir`,
+/// `configMap`, `secret`, `csi` or `hostPath`) must be set.
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct VolumeConfig {
+    pub name: String,
+    pub mount_path: String,
+    #[serde(default)]
+    pub read_only: bool,
+    #[serde(default)]
+    pub sub_path: Option<String>,
+    /// Name of the claim.
+    #[serde(default)]
+    pub persistent_volume_claim: Option<String>,
+    #[serde(default)]
+    pub empty_dir: Option<EmptyDirConfig>,
+    /// Name of the ConfigMap; each key becomes a file.
+    #[serde(default)]
+    pub config_map: Option<String>,
+    /// Name of the Secret; each key becomes a file.
+    #[serde(default)]
+    pub secret: Option<String>,
+    #[serde(default)]
+    pub csi: Option<CsiVolumeConfig>,
+    /// Directory on the node, for edge setups that pre-load models onto local disks. Only
+    /// accepted when the operator runs with `ALLOW_HOST_PATH=true`.
+    #[serde(default)]
+    pub host_path: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EmptyDirConfig {
+    /// `Memory` for a tmpfs; the node's disk otherwise.
+    #[serde(default)]
+    pub medium: Option<String>,
+    /// e.g. `20Gi`.
+    #[serde(default)]
+    pub size_limit: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CsiVolumeConfig {
+    /// e.g. `s3.csi.aws.com` or `gcsfuse.csi.storage.gke.io`.
+    pub driver: String,
+    /// Driver-specific settings such as the bucket name.
+    #[serde(default)]
+    pub volume_attributes: Option<BTreeMap<String, String>>,
+    /// Secret with the credentials the driver mounts with.
+    #[serde(default)]
+    pub node_publish_secret: Option<String>,
+}
+
+/// A literal `value` or, via `fieldRef`, a field of the pod itself (downward API).
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvEntry {
+    pub name: String,
+    #[serde(default)]
+    pub value: Option<String>,
+    /// Pod field path, e.g. `metadata.name`, `status.podIP` or `spec.nodeName`.
+    #[serde(default)]
+    pub field_ref: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretRef {
+    pub name: String,
+    /// Mounts each key as a read-only file under this directory; without it every key
+    /// becomes an environment variable.
+    #[serde(default)]
+    pub mount_path: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SchedulingSpec {
+    #[serde(default)]
+    pub node_selector: Option<BTreeMap<String, String>>,
+    /// Required node affinity; a node must match every expression.
+    #[serde(default)]
+    pub node_affinity: Option<Vec<NodeAffinityExpression>>,
+    /// Spreads the role's pods over topology domains such as zones. Each constraint selects
+    /// the role's own pods.
+    #[serde(default)]
+    pub topology_spread: Option<Vec<TopologySpread>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TopologySpread {
+    /// Node label naming the domain, e.g. `topology.kubernetes.io/zone`.
+    pub topology_key: String,
+    #[serde(default = "default_replicas")]
+    pub max_skew: i32,
+    /// DoNotSchedule (the default) or ScheduleAnyway.
+    #[serde(default)]
+    pub when_unsatisfiable: Option<String>,
+    /// Domains the pods must cover: while fewer hold matching pods, the missing ones count as
+    /// empty, so even two replicas land in two zones. Needs DoNotSchedule.
+    #[serde(default)]
+    pub min_domains: Option<i32>,
+    /// Pod label keys whose values further narrow the pods counted, e.g. `pod-template-hash`
+    /// to spread each rollout revision on its own.
+    #[serde(default)]
+    pub match_label_keys: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeAffinityExpression {
+    pub key: String,
+    /// One of In, NotIn, Exists, DoesNotExist, Gt, Lt.
+    pub operator: String,
+    #[serde(default)]
+    pub values: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelChecksum {
+    /// Fetched with busybox `wget`, which has no TLS, so it must be `http://`. Integrity comes
+    /// from `sha256`, not the transport.
+    pub url: String,
+    pub sha256: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtraPort {
+    /// Used for both the container port and the Service port; an IANA service name of at most
+    /// 15 characters.
+    pub name: String,
+    pub container_port: i32,
+    /// Node port this port is also bound to; with `hostNetwork` it must equal `containerPort`.
+    #[serde(default)]
+    pub host_port: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkPolicyConfig {
+    /// Namespaces (e.g. the Traefik namespace) whose pods may reach the model.
+    #[serde(default)]
+    pub from_namespaces: Vec<String>,
+    /// Labels of pods in the model's own namespace that may reach it.
+    #[serde(default)]
+    pub from_pod_labels: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DisruptionBudgetConfig {
+    /// Live pods a voluntary disruption may take down at once; 1 by default.
+    #[serde(default)]
+    pub max_unavailable: Option<i32>,
+    /// The same while `maintenanceWindow` is open; 0 by default, which blocks node drains
+    /// until the window closes.
+    #[serde(default)]
+    pub max_unavailable_during_maintenance: Option<i32>,
+}
+
+impl DisruptionBudgetConfig {
+    pub fn max_unavailable(&self, in_maintenance: bool) -> i32 {
+        if in_maintenance {
+            self.max_unavailable_during_maintenance.unwrap_or(0)
+        } else {
+            self.max_unavailable.unwrap_or(1)
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CanaryAnalysis {
+    /// Base URL of the Prometheus server, e.g. `http://prometheus.monitoring:9090`.
+    pub prometheus_url: String,
+    /// Instant query returning a single sample, e.g. the canary's error ratio.
+    pub query: String,
+    /// The analysis passes while the query result is at or below this value.
+    pub threshold: f64,
+    /// Seconds between queries; 60 by default. In between, the last result stands.
+    #[serde(default)]
+    pub interval_seconds: Option<u64>,
+    /// Failed queries in a row before the canary is rolled back; 3 by default. A pass starts
+    /// the count over.
+    #[serde(default)]
+    pub failure_limit: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceConfig {
+    /// Suffix of the Service name: `<name>-<suffix>-svc`.
+    pub name: String,
+    /// `ClusterIP` (default), `NodePort` or `LoadBalancer`.
+    #[serde(default)]
+    pub service_type: Option<String>,
+    /// Names of the ports to expose; all of them when unset.
+    #[serde(default)]
+    pub ports: Option<Vec<String>>,
+    /// Added to the Service, e.g. cloud load balancer settings.
+    #[serde(default)]
+    pub annotations: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveServiceConfig {
+    #[serde(default = "default_true")]
+    pub manage: bool,
+    /// Service to route live traffic to when `manage` is off. It must select the live pods and
+    /// expose the serving port.
+    #[serde(default)]
+    pub existing_name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeWindow {
+    /// RFC3339 timestamp, e.g. `2025-12-20T00:00:00Z`.
+    pub start: String,
+    /// RFC3339 timestamp; must be after `start`.
+    pub end: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CanaryStep {
+    /// Percent of the traffic the canary gets at this step, 0-100.
+    pub weight: i32,
+    #[serde(default)]
+    pub pause_seconds: u64,
+}
+
+/// What to do when a child with our generated name already exists but was not created by us.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, Default)]
+pub enum AdoptionPolicy {
+    /// Take the resource over and apply the desired state to it.
+    #[default]
+    Adopt,
+    /// Leave the resource untouched and report a conflict.
+    RejectConflict,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteSpec {
+    pub entry_point: String,
+    #[serde(default)]
+    pub tls: Option<RouteTlsSpec>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct StickyCookieConfig {
+    /// Cookie name; Traefik derives one from the backend otherwise.
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub secure: bool,
+    #[serde(default)]
+    pub http_only: bool,
+    /// `none`, `lax` or `strict`.
+    #[serde(default)]
+    pub same_site: Option<String>,
+    /// Cookie lifetime in seconds; a session cookie when unset.
+    #[serde(default)]
+    pub max_age: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteTlsSpec {
+    pub secret_name: Option<String>,
+    pub cert_resolver: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
@@ -66,6 +851,55 @@ pub struct AutoScalingSpec {
     pub min_replicas: Option<i32>,
     pub max_replicas: Option<i32>,
     pub target_cpu_utilization_percentage: Option<i32>,
+    /// Custom or external metrics (requests per second, queue depth) scaled on alongside
+    /// the CPU target, or instead of it. They need a metrics adapter in the cluster.
+    #[serde(default)]
+    pub metrics: Option<Vec<ScalingMetric>>,
+    /// How fast the HPA may scale; HPA defaults apply when unset.
+    #[serde(default)]
+    pub behavior: Option<ScalingBehavior>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ScalingMetric {
+    /// `Pods` for a metric of each model pod, e.g. requests per second, or `External` for
+    /// one from outside the cluster, e.g. the depth of a queue.
+    pub r#type: String,
+    pub name: String,
+    /// Quantity the metric is held at, e.g. `100` or `500m`. A `Pods` metric is averaged over
+    /// the pods.
+    pub target: String,
+    /// `External` only: compare the value divided by the pod count rather than the total.
+    #[serde(default)]
+    pub per_pod: bool,
+    /// `External` only: labels picking the series, e.g. the queue name.
+    #[serde(default)]
+    pub selector: Option<BTreeMap<String, String>>,
+}
+
+/// Damps scaling on bursty load, so pods (and their warmed-up caches) aren't dropped and
+/// re-created from one minute to the next.
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ScalingBehavior {
+    /// Seconds of past recommendations the HPA takes the highest of before scaling down;
+    /// the HPA default is 300.
+    #[serde(default)]
+    pub scale_down_stabilization_seconds: Option<i32>,
+    /// Likewise for scaling up, taking the lowest; the HPA default is 0.
+    #[serde(default)]
+    pub scale_up_stabilization_seconds: Option<i32>,
+    /// Most pods added per `scaleUpPeriodSeconds`. With `scaleUpPercent` as well, the larger
+    /// step wins.
+    #[serde(default)]
+    pub scale_up_pods: Option<i32>,
+    /// Most pods added per `scaleUpPeriodSeconds`, as a percentage of the current count.
+    #[serde(default)]
+    pub scale_up_percent: Option<i32>,
+    /// Window the scale-up limits apply to; defaults to 60 seconds.
+    #[serde(default)]
+    pub scale_up_period_seconds: Option<i32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
@@ -75,6 +909,16 @@ pub struct ProbeSpec {
     pub liveness_path: String,
     #[serde(default = "default_readiness")]
     pub readiness_path: String,
+    /// Port name or number the model server's probes go to, e.g. a separate health port from
+    /// `extraPorts`; the serving port by default. It must be one the container declares.
+    /// `models` containers are always probed on their own port.
+    #[serde(default)]
+    pub port: Option<String>,
+    /// Time a model server gets to load before it is restarted. Sets a startup probe on
+    /// `livenessPath` with `periodSeconds: 10` and `failureThreshold` this divided by 10,
+    /// rounded up; liveness and readiness probing waits until it passes.
+    #[serde(default)]
+    pub warmup_seconds: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
@@ -84,27 +928,304 @@ pub struct ModelDeploymentStatus {
     pub live_status: Option<ChildStatus>,
     pub shadow_status: Option<ChildStatus>,
     pub conditions: Option<Vec<Condition>>,
+    pub canary: Option<CanaryStatus>,
+    pub consecutive_failures: Option<u32>,
+    pub endpoints: Option<Vec<String>>,
+    pub last_force_sync: Option<String>,
+    /// Generation last applied to children; behind `metadata.generation` while changes wait.
+    pub observed_generation: Option<i64>,
+    pub last_image_change: Option<ImageChange>,
+    /// RFC3339 time `live.image` last changed, while live has yet to become fully available.
+    pub rollout_started_at: Option<String>,
+    /// Seconds the last live rollout took from the image change to full availability.
+    pub last_rollout_duration: Option<i64>,
+    /// RFC3339 time of the last successful reconcile, refreshed at most every 30 seconds.
+    pub last_reconcile_time: Option<String>,
+    /// `extraResources` applied so far, so entries dropped from the spec can be deleted.
+    pub extra_resources: Option<Vec<ExtraResourceRef>>,
+    /// Every child applied by the last full reconcile.
+    pub managed_resources: Option<Vec<ManagedResource>>,
+    /// Requests suggested by `rightSize` for the current live release.
+    pub resource_recommendation: Option<ResourceRecommendation>,
+    /// An HPA not created by us that scales the live Deployment, so `replicas` is left to it.
+    pub external_hpa: Option<String>,
+    /// A spec change waiting out `rolloutDebounceSeconds`.
+    pub pending_change: Option<PendingChange>,
+    /// `apiVersion` the object was last read as, to plan migrations off deprecated versions.
+    pub observed_api_version: Option<String>,
+    /// Activity tracked for `scaleToZero`.
+    pub idle: Option<IdleStatus>,
+    /// RFC3339 time deletion removed the routing, while the pods drain for `drainSeconds`.
+    pub draining_since: Option<String>,
+    /// `fallbackImage` as pre-pulled by the live pods.
+    pub fallback_image: Option<String>,
+    /// Version of the operator that last fully reconciled the object.
+    pub managed_by_version: Option<String>,
+}
+
+impl ModelDeploymentStatus {
+    /// Replaces the condition of the same type, or appends it.
+    pub fn set_condition(&mut self, condition: Condition) {
+        let conditions = self.conditions.get_or_insert_with(Vec::new);
+        match conditions.iter_mut().find(|c| c.r#type == condition.r#type) {
+            Some(existing) => *existing = condition,
+            None => conditions.push(condition),
+        }
+    }
+
+    /// Sets `Healthy`, the one condition to wait or alert on: true while every critical
+    /// condition present holds, otherwise naming the ones that don't.
+    pub fn set_healthy(&mut self) {
+        const MUST_HOLD: [&str; 3] = ["Accepted", "Ready", "RoutingReady"];
+        const MUST_NOT_HOLD: [&str; 6] = [
+            "Degraded",
+            "ContainerCrashed",
+            "CanaryAborted",
+            "Reconciling",
+            "NameConflict",
+            "ImageDowngradeBlocked",
+        ];
+        let failing: Vec<String> = self
+            .conditions
+            .iter()
+            .flatten()
+            .filter(|c| {
+                (MUST_HOLD.contains(&c.r#type.as_str()) && c.status != "True")
+                    || (MUST_NOT_HOLD.contains(&c.r#type.as_str()) && c.status == "True")
+            })
+            .map(|c| match &c.reason {
+                Some(reason) => format!("{} is {} ({})", c.r#type, c.status, reason),
+                None => format!("{} is {}", c.r#type, c.status),
+            })
+            .collect();
+        self.set_condition(Condition {
+            r#type: "Healthy".into(),
+            status: if failing.is_empty() { "True" } else { "False" }.into(),
+            reason: Some(
+                if failing.is_empty() {
+                    "AllChecksPassing"
+                } else {
+                    "ChecksFailing"
+                }
+                .into(),
+            ),
+            message: Some(if failing.is_empty() {
+                "Every critical condition holds.".into()
+            } else {
+                format!("{}.", failing.join(", "))
+            }),
+            ..Default::default()
+        });
+    }
+
+    /// Fills in the `metav1.Condition` bookkeeping before a write: every condition is marked
+    /// as computed from `generation`, and keeps the `previous` transition time unless its
+    /// status flipped, in which case it transitioned `now`.
+    pub fn stamp_conditions(
+        &mut self,
+        previous: Option<&ModelDeploymentStatus>,
+        generation: Option<i64>,
+        now: &str,
+    ) {
+        let previous = previous
+            .and_then(|s| s.conditions.as_deref())
+            .unwrap_or_default();
+        for condition in self.conditions.iter_mut().flatten() {
+            condition.observed_generation = generation;
+            condition.last_transition_time = previous
+                .iter()
+                .find(|p| p.r#type == condition.r#type && p.status == condition.status)
+                .and_then(|p| p.last_transition_time.clone())
+                .or_else(|| Some(now.to_string()));
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ScaleToZeroSpec {
+    /// Base URL of the Prometheus server, e.g. `http://prometheus.monitoring:9090`.
+    pub prometheus_url: String,
+    /// Instant query for live's request rate; any value above zero counts as traffic. An
+    /// unanswered query does too, so live is never scaled down blind.
+    pub query: String,
+    /// Seconds without traffic or activation before live is scaled to zero.
+    #[serde(default = "default_idle_seconds")]
+    pub idle_seconds: u32,
+    /// Service the route sends requests to while live is at zero, e.g. the activator that
+    /// holds them until live is back; it must listen on live's port. Applies to the
+    /// `trafficMirror` route, which stops mirroring meanwhile. Without one the route keeps
+    /// pointing at live.
+    #[serde(default)]
+    pub activator_service: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IdleStatus {
+    /// RFC3339 time live last saw traffic or an activation.
+    pub last_active: String,
+    pub scaled_to_zero: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingChange {
+    /// Hash of the spec being held back.
+    pub fingerprint: String,
+    /// RFC3339 time this spec was first seen; it is applied once quiet long enough.
+    pub first_seen: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceRecommendation {
+    /// The live release the usage was sampled from; a rollout starts over.
+    pub release: String,
+    pub cpu: String,
+    pub memory: String,
+    pub updated_at: String,
+}
+
+/// The most recent image rollout, kept as an audit trail of model versions.
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageChange {
+    pub role: String,
+    /// Releases as `image`, or `image + companion image` for variants with a companion.
+    pub from: String,
+    pub to: String,
+    pub at: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CanaryStatus {
+    /// The shadow's release this canary is for; a new one starts over.
+    pub image: String,
+    pub step: i32,
+    pub weight: i32,
+    pub step_started_at: Option<String>,
+    #[serde(default)]
+    pub aborted: bool,
+    /// Set when the abort came from a failed analysis; sticks until the shadow image changes.
+    #[serde(default)]
+    pub analysis_failed: bool,
+    /// The shadow is a zero-replica standby; the canary starts from the first step once it
+    /// is scaled up.
+    #[serde(default)]
+    pub standby: bool,
+    /// The canary has had a ready replica; until then it is still starting rather than
+    /// degraded.
+    #[serde(default)]
+    pub started: bool,
+    /// When `canaryAnalysis` last queried Prometheus.
+    #[serde(default)]
+    pub analyzed_at: Option<String>,
+    /// Analysis queries in a row that failed the threshold.
+    #[serde(default)]
+    pub analysis_failures: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtraResourceRef {
+    pub api_version: String,
+    pub kind: String,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagedResource {
+    pub kind: String,
+    pub name: String,
+}
+
+impl ManagedResource {
+    pub fn new(kind: &str, name: impl Into<String>) -> Self {
+        Self {
+            kind: kind.into(),
+            name: name.into(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct ChildStatus {
+    /// `<available>/<desired>` replicas, for the `kubectl get` columns.
+    pub ready: Option<String>,
+    pub desired_replicas: Option<i32>,
     pub available_replicas: Option<i32>,
     pub updated_replicas: Option<i32>,
+    /// Some pod's model checksum init container exited non-zero.
+    pub verification_failed: Option<bool>,
+    /// The latest non-zero container exit among the pods, as `<pod>/<container>: <reason>
+    /// (exit code <n>): <last line of the termination message>`.
+    pub last_crash: Option<String>,
+    /// The ResourceQuota message from the Deployment's `ReplicaFailure` condition while pods
+    /// can't be created because a quota is exhausted.
+    pub quota_exceeded: Option<String>,
+    /// Pods whose container is ready, per entry in `models`.
+    pub models: Option<Vec<ModelReadiness>>,
+    /// ReplicaSets of the Deployment other than its current one; counted only with
+    /// `pruneReplicaSets`.
+    pub old_replica_sets: Option<i32>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelReadiness {
+    pub name: String,
+    pub ready_pods: i32,
+}
+
+/// Shaped like `metav1.Condition`, so `kubectl wait --for=condition=...` and condition-aware
+/// tooling read it.
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct Condition {
     pub r#type: String,
     pub status: String,
     pub reason: Option<String>,
     pub message: Option<String>,
+    /// RFC3339 time `status` last changed; set when the status is written.
+    #[serde(default)]
+    pub last_transition_time: Option<String>,
+    /// `metadata.generation` the condition was computed from.
+    #[serde(default)]
+    pub observed_generation: Option<i64>,
+}
+
+fn raw_objects_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+    schemars::json_schema!({
+        "type": "array",
+        "nullable": true,
+        "items": {
+            "type": "object",
+            "x-kubernetes-preserve-unknown-fields": true
+        }
+    })
+}
+
+fn raw_object_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+    schemars::json_schema!({
+        "type": "object",
+        "nullable": true,
+        "x-kubernetes-preserve-unknown-fields": true
+    })
 }
 
 fn default_replicas() -> i32 {
     1
 }
+fn default_true() -> bool {
+    true
+}
+fn default_idle_seconds() -> u32 {
+    300
+}
 fn default_rollout() -> String {
     "rolling".into()
 }
@@ -114,3 +1235,19 @@ fn default_liveness() -> String {
 fn default_readiness() -> String {
     "/ready".into()
 }
+
+#[cfg(test)]
+pub mod testing {
+    use super::ModelDeployment;
+
+    /// ModelDeployment `model` in namespace `ns` with `spec`, which needs at least `live.image`.
+    pub fn model_deployment(spec: serde_json::Value) -> ModelDeployment {
+        serde_json::from_value(serde_json::json!({
+            "apiVersion": "ml.jedimindtricks.example/v1alpha1",
+            "kind": "ModelDeployment",
+            "metadata": {"name": "model", "namespace": "ns", "uid": "uid-1", "generation": 1},
+            "spec": spec,
+        }))
+        .unwrap()
+    }
+}
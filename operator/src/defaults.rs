@@ -0,0 +1,221 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, RwLock},
+};
+
+use futures::{StreamExt, channel::mpsc::Sender};
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::{Api, Client};
+use kube_runtime::{WatchStreamExt, watcher};
+use serde::de::DeserializeOwned;
+
+use crate::crd::{ModelDeployment, NamingSpec, ProbeSpec, ResourceLimits, ResourceSpec};
+
+pub type SharedDefaults = Arc<RwLock<OperatorDefaults>>;
+
+/// Cluster-wide defaults layered under every ModelDeployment spec.
+#[derive(Debug, Clone, Default)]
+pub struct OperatorDefaults {
+    pub domain: Option<String>,
+    pub resources: Option<ResourceSpec>,
+    pub probes: Option<ProbeSpec>,
+    pub naming: Option<NamingSpec>,
+}
+
+impl OperatorDefaults {
+    /// Reads the `domain`, `resources`, `probes` and `naming` keys; all but `domain` hold YAML
+    /// documents.
+    pub fn from_config_map(cm: &ConfigMap) -> Self {
+        let data = cm.data.clone().unwrap_or_default();
+
+        fn parse<T: DeserializeOwned>(data: &BTreeMap<String, String>, key: &str) -> Option<T> {
+            let raw = data.get(key)?;
+            match serde_yaml::from_str(raw) {
+                Ok(value) => Some(value),
+                Err(e) => {
+                    tracing::warn!("Ignoring invalid defaults key {}: {}", key, e);
+                    None
+                }
+            }
+        }
+
+        Self {
+            domain: data.get("domain").cloned(),
+            resources: parse(&data, "resources"),
+            probes: parse(&data, "probes"),
+            naming: parse(&data, "naming"),
+        }
+    }
+
+    /// Returns a copy of `md` with gaps filled from the defaults. Per-object values always win.
+    pub fn apply(&self, md: &ModelDeployment) -> ModelDeployment {
+        let mut md = md.clone();
+        let spec = &mut md.spec;
+
+        if spec.domain.is_none() {
+            spec.domain = self.domain.clone();
+        }
+        if spec.probes.is_none() {
+            spec.probes = self.probes.clone();
+        }
+        if spec.naming.is_none() {
+            spec.naming = self.naming.clone();
+        }
+        spec.resources = match (spec.resources.take(), &self.resources) {
+            (Some(own), Some(default)) => Some(ResourceSpec {
+                limits: merge_limits(own.limits, &default.limits),
+                requests: merge_limits(own.requests, &default.requests),
+            }),
+            (own, default) => own.or_else(|| default.clone()),
+        };
+
+        md
+    }
+}
+
+fn merge_limits(
+    own: Option<ResourceLimits>,
+    default: &Option<ResourceLimits>,
+) -> Option<ResourceLimits> {
+    match (own, default) {
+        (Some(own), Some(default)) => Some(ResourceLimits {
+            cpu: own.cpu.or_else(|| default.cpu.clone()),
+            memory: own.memory.or_else(|| default.memory.clone()),
+        }),
+        (own, default) => own.or_else(|| default.clone()),
+    }
+}
+
+/// Keeps `cache` in sync with the defaults ConfigMap for the lifetime of the operator, and
+/// sends on `changed` whenever it is replaced so every ModelDeployment is reconciled against
+/// the new defaults instead of waiting for its next resync. A send that finds one already
+/// queued is dropped; the pending reconcile-all covers it.
+pub async fn watch_defaults(
+    client: Client,
+    ns: String,
+    name: String,
+    cache: SharedDefaults,
+    mut changed: Sender<()>,
+) {
+    let api: Api<ConfigMap> = Api::namespaced(client, &ns);
+    let cfg = watcher::Config::default().fields(&format!("metadata.name={}", name));
+    let mut stream = watcher(api, cfg).default_backoff().boxed();
+
+    // A relist only replaces the cache once it completes, so a missing ConfigMap resets it.
+    let mut pending: Option<OperatorDefaults> = None;
+
+    while let Some(event) = stream.next().await {
+        match event {
+            Ok(watcher::Event::Apply(cm)) => {
+                tracing::info!("Loaded defaults from ConfigMap {}/{}", ns, name);
+                *cache.write().unwrap() = OperatorDefaults::from_config_map(&cm);
+                let _ = changed.try_send(());
+            }
+            Ok(watcher::Event::Delete(_)) => {
+                tracing::info!("Defaults ConfigMap {}/{} deleted", ns, name);
+                *cache.write().unwrap() = OperatorDefaults::default();
+                let _ = changed.try_send(());
+            }
+            Ok(watcher::Event::Init) => pending = Some(OperatorDefaults::default()),
+            Ok(watcher::Event::InitApply(cm)) => {
+                pending = Some(OperatorDefaults::from_config_map(&cm));
+            }
+            Ok(watcher::Event::InitDone) => {
+                if let Some(defaults) = pending.take() {
+                    *cache.write().unwrap() = defaults;
+                    let _ = changed.try_send(());
+                }
+            }
+            Err(e) => tracing::warn!("Defaults ConfigMap watch error: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crd::testing::model_deployment;
+    use serde_json::json;
+
+    fn defaults() -> OperatorDefaults {
+        OperatorDefaults::from_config_map(&ConfigMap {
+            data: Some(BTreeMap::from([
+                ("domain".into(), "models.example".into()),
+                (
+                    "resources".into(),
+                    "limits:\n  cpu: \"2\"\n  memory: 4Gi\nrequests:\n  cpu: 500m\n".into(),
+                ),
+                (
+                    "naming".into(),
+                    "deployment: \"{name}-{role}-deploy\"\n".into(),
+                ),
+            ])),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn spec_values_beat_defaults() {
+        let md = model_deployment(json!({
+            "live": {"image": "model:1"},
+            "domain": "own.example",
+            "naming": {"deployment": "{role}-{name}"},
+            "resources": {"limits": {"cpu": "1", "memory": "1Gi"}},
+        }));
+        let spec = defaults().apply(&md).spec;
+
+        assert_eq!(spec.domain.as_deref(), Some("own.example"));
+        let naming = spec.naming.unwrap();
+        assert_eq!(naming.deployment.as_deref(), Some("{role}-{name}"));
+        let limits = spec.resources.as_ref().unwrap().limits.as_ref().unwrap();
+        assert_eq!(limits.cpu.as_deref(), Some("1"));
+        assert_eq!(limits.memory.as_deref(), Some("1Gi"));
+    }
+
+    #[test]
+    fn defaults_fill_gaps_field_by_field() {
+        let md = model_deployment(json!({
+            "live": {"image": "model:1"},
+            "resources": {"limits": {"cpu": "1"}},
+        }));
+        let spec = defaults().apply(&md).spec;
+
+        assert_eq!(spec.domain.as_deref(), Some("models.example"));
+        assert_eq!(
+            spec.naming.unwrap().deployment.as_deref(),
+            Some("{name}-{role}-deploy")
+        );
+        let resources = spec.resources.unwrap();
+        let limits = resources.limits.unwrap();
+        assert_eq!(limits.cpu.as_deref(), Some("1"));
+        assert_eq!(limits.memory.as_deref(), Some("4Gi"));
+        assert_eq!(resources.requests.unwrap().cpu.as_deref(), Some("500m"));
+    }
+
+    #[test]
+    fn unset_everywhere_leaves_the_operator_default() {
+        let md = model_deployment(json!({"live": {"image": "model:1"}}));
+        let spec = OperatorDefaults::default().apply(&md).spec;
+
+        // the reconciler falls back to its built-in values for these
+        assert!(spec.domain.is_none());
+        assert!(spec.naming.is_none());
+        assert!(spec.resources.is_none());
+        assert!(spec.probes.is_none());
+    }
+
+    #[test]
+    fn invalid_keys_are_skipped_without_dropping_the_rest() {
+        let cm = ConfigMap {
+            data: Some(BTreeMap::from([
+                ("domain".into(), "models.example".into()),
+                ("resources".into(), "limits: [not, a, map".into()),
+            ])),
+            ..Default::default()
+        };
+        let defaults = OperatorDefaults::from_config_map(&cm);
+
+        assert_eq!(defaults.domain.as_deref(), Some("models.example"));
+        assert!(defaults.resources.is_none());
+    }
+}
@@ -0,0 +1,63 @@
+use std::cmp::Ordering;
+
+/// Lets a ModelDeployment with `blockImageDowngrades` move live to an older tag anyway.
+pub const ALLOW_ANNOTATION: &str = "ml.jedimindtricks.example/allow-downgrade";
+
+/// Whether moving from image `from` to `to` goes back to an older version. Only tags that are
+/// plain dotted numbers (`3`, `1.4`, `v2.0.1`) can be compared; anything else, including
+/// digests and pre-release suffixes, never counts as a downgrade.
+pub fn is_downgrade(from: &str, to: &str) -> bool {
+    match (version(from), version(to)) {
+        (Some(mut from), Some(mut to)) => {
+            // `2` and `2.0` are the same version
+            let len = from.len().max(to.len());
+            from.resize(len, 0);
+            to.resize(len, 0);
+            to.cmp(&from) == Ordering::Less
+        }
+        _ => false,
+    }
+}
+
+fn version(image: &str) -> Option<Vec<u64>> {
+    if image.contains('@') {
+        return None;
+    }
+    // the tag follows the last ':' unless that colon belongs to a registry port
+    let (_, tag) = image
+        .rsplit_once(':')
+        .filter(|(_, tag)| !tag.contains('/'))?;
+    let tag = tag.strip_prefix('v').unwrap_or(tag);
+    tag.split('.').map(|part| part.parse().ok()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_components_count_as_zero() {
+        assert!(!is_downgrade("model:2.0", "model:2"));
+        assert!(!is_downgrade("model:2", "model:2.0.0"));
+        assert!(!is_downgrade("model:v1.2", "model:1.2.0"));
+        assert!(is_downgrade("model:2.1", "model:2"));
+        assert!(!is_downgrade("model:2", "model:2.1"));
+    }
+
+    #[test]
+    fn older_tags_are_downgrades() {
+        assert!(is_downgrade("model:1.10.0", "model:1.9.3"));
+        assert!(!is_downgrade("model:1.9.3", "model:1.10.0"));
+        assert!(is_downgrade(
+            "registry:5000/model:2",
+            "registry:5000/model:1"
+        ));
+    }
+
+    #[test]
+    fn unparsable_versions_are_never_downgrades() {
+        assert!(!is_downgrade("model:latest", "model:1.0"));
+        assert!(!is_downgrade("model:2.0", "model@sha256:abc"));
+        assert!(!is_downgrade("registry:5000/model", "model:1"));
+    }
+}
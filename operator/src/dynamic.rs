@@ -0,0 +1,61 @@
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+    time::{Duration, Instant},
+};
+
+use kube::{
+    Api, Client,
+    api::{ApiResource, DynamicObject, GroupVersionKind, TypeMeta},
+    discovery::Scope,
+};
+
+use crate::error::Error;
+
+/// How long a discovered kind is trusted before it is looked up again, so CRDs installed or
+/// changed later are picked up without a restart.
+const DISCOVERY_TTL: Duration = Duration::from_secs(300);
+
+/// A discovered kind and when it was discovered.
+type Discovered = (ApiResource, Scope, Instant);
+
+/// Kinds discovered by [`dynamic_api`], so every reconcile doesn't cost a discovery request
+/// per extra resource.
+static DISCOVERED: LazyLock<Mutex<HashMap<GroupVersionKind, Discovered>>> =
+    LazyLock::new(Default::default);
+
+/// Discovers the API resource for `types` at runtime, for kinds without typed bindings in this
+/// crate. Only namespaced kinds can be owned by the ModelDeployment, so anything cluster-scoped
+/// is refused.
+pub async fn dynamic_api(
+    client: &Client,
+    ns: &str,
+    types: &TypeMeta,
+) -> Result<(Api<DynamicObject>, ApiResource), Error> {
+    let gvk = GroupVersionKind::try_from(types)
+        .map_err(|e| Error::Validation(format!("{}: {}", types.kind, e)))?;
+    let cached = DISCOVERED
+        .lock()
+        .unwrap()
+        .get(&gvk)
+        .filter(|(_, _, at)| at.elapsed() < DISCOVERY_TTL)
+        .map(|(ar, scope, _)| (ar.clone(), scope.clone()));
+    let (ar, scope) = match cached {
+        Some(found) => found,
+        None => {
+            let (ar, caps) = kube::discovery::pinned_kind(client, &gvk).await?;
+            DISCOVERED
+                .lock()
+                .unwrap()
+                .insert(gvk, (ar.clone(), caps.scope.clone(), Instant::now()));
+            (ar, caps.scope)
+        }
+    };
+    if scope != Scope::Namespaced {
+        return Err(Error::Validation(format!(
+            "{} is cluster-scoped; only namespaced kinds can be owned",
+            types.kind
+        )));
+    }
+    Ok((Api::namespaced_with(client.clone(), ns, &ar), ar))
+}
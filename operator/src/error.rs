@@ -5,4 +5,62 @@ use thiserror::Error;
 pub enum Error {
     #[error("Kubernetes API error: {0}")]
     Kube(#[from] KubeError),
+
+    /// A Kubernetes API call that failed on a particular child, so logs and Warning events
+    /// say which one.
+    #[error("failed to {action} {kind} {name}: {source}")]
+    Operation {
+        action: &'static str,
+        kind: String,
+        name: String,
+        #[source]
+        source: Box<KubeError>,
+    },
+
+    #[error("Invalid ModelDeployment spec: {0}")]
+    Validation(String),
+
+    #[error("{kind} {name} already exists and is not managed by this ModelDeployment")]
+    AdoptionConflict { kind: String, name: String },
+
+    #[error("{kind} {name} belongs to ModelDeployment {owner}")]
+    NameConflict {
+        kind: String,
+        name: String,
+        owner: String,
+    },
+
+    #[error("Failed to serialize desired object: {0}")]
+    Serialize(#[from] serde_json::Error),
+
+    #[error("Failed to write audit record: {0}")]
+    Audit(#[from] std::io::Error),
+
+    #[error("Reconcile did not finish within {0:?}")]
+    Timeout(std::time::Duration),
+}
+
+impl Error {
+    /// Wraps a failed API call on `kind` `name` with what was being done to it.
+    pub fn operation(
+        action: &'static str,
+        kind: impl Into<String>,
+        name: impl Into<String>,
+    ) -> impl FnOnce(KubeError) -> Self {
+        move |source| Error::Operation {
+            action,
+            kind: kind.into(),
+            name: name.into(),
+            source: Box::new(source),
+        }
+    }
+
+    /// The API error underneath, with or without operation context.
+    pub fn kube(&self) -> Option<&KubeError> {
+        match self {
+            Error::Kube(e) => Some(e),
+            Error::Operation { source, .. } => Some(source),
+            _ => None,
+        }
+    }
 }
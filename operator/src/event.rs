@@ -1,13 +1,57 @@
+use std::sync::Arc;
+
+use futures::StreamExt;
+use k8s_openapi::api::core::v1::ObjectReference;
+use k8s_openapi::apimachinery::pkg::version::Info;
 use kube::runtime::events::{Event, EventType};
-use kube::{Client, Resource};
+use kube::{Api, Client, Resource, ResourceExt};
 use kube_runtime::events::{Recorder, Reporter};
+use kube_runtime::{WatchStreamExt, metadata_watcher, watcher};
 
-use crate::error::Error;
+use crate::{
+    backoff::FailureTracker, config::Config, crd::ModelDeployment, defaults::SharedDefaults,
+    error::Error, history::ReconcileHistory, locks::ObjectLocks, metrics, resync::SyncTracker,
+};
 
 #[derive(Clone)]
 pub struct Ctx {
     pub client: Client,
     pub recorder: Recorder,
+    pub config: Config,
+    pub defaults: SharedDefaults,
+    pub failures: FailureTracker,
+    pub locks: ObjectLocks,
+    pub syncs: SyncTracker,
+    pub history: ReconcileHistory,
+    /// Version the API server reported at startup; `None` if it would not say.
+    pub server_version: Option<Info>,
+}
+
+impl Ctx {
+    /// Drops everything kept in memory about a deleted ModelDeployment.
+    pub fn forget<K: Resource>(&self, md: &K) {
+        let ns = md.namespace().unwrap_or_else(|| "default".into());
+        let name = md.name_any();
+        let key = format!("{}/{}", ns, name);
+        self.failures.reset(&key);
+        self.locks.forget(&md.uid().unwrap_or_default());
+        self.syncs.forget(&key);
+        self.history.forget(&key);
+        metrics::forget(&ns, &name);
+    }
+}
+
+/// Forgets ModelDeployments as they are deleted. The finalizer's last pass does the same, but
+/// an object can also go without one, e.g. when its finalizer is removed by hand.
+pub async fn forget_deleted(api: Api<ModelDeployment>, cfg: watcher::Config, ctx: Arc<Ctx>) {
+    let mut stream = metadata_watcher(api, cfg).default_backoff().boxed();
+    while let Some(event) = stream.next().await {
+        match event {
+            Ok(watcher::Event::Delete(md)) => ctx.forget(&md),
+            Ok(_) => {}
+            Err(e) => tracing::warn!("ModelDeployment deletion watch error: {}", e),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -17,10 +61,10 @@ pub enum Outcome {
     Updated,
 }
 
-pub fn make_reporter() -> Reporter {
+pub fn make_reporter(config: &Config) -> Reporter {
     Reporter {
         controller: "model-operator".into(),
-        instance: Some("dev".into()),
+        instance: Some(config.reporter_instance.clone()),
     }
 }
 
@@ -34,6 +78,18 @@ pub async fn emit_event<K>(
 where
     K: Resource<DynamicType = ()> + std::fmt::Debug,
 {
+    emit_event_for(ctx, &obj.object_ref(&()), reason, note, event_type).await
+}
+
+/// Like [`emit_event`], but about any object, e.g. a child Deployment, so the event shows up
+/// in `kubectl describe` of that object rather than only on the ModelDeployment.
+pub async fn emit_event_for(
+    ctx: &Ctx,
+    reference: &ObjectReference,
+    reason: &str,
+    note: &str,
+    event_type: EventType,
+) -> Result<(), Error> {
     ctx.recorder
         .publish(
             &Event {
@@ -43,7 +99,7 @@ where
                 action: reason.into(),
                 secondary: None,
             },
-            &obj.object_ref(&()),
+            reference,
         )
         .await?;
 
@@ -79,3 +135,272 @@ where
         }
     }
 }
+
+#[cfg(test)]
+pub mod testing {
+    use std::{
+        collections::BTreeMap,
+        convert::Infallible,
+        sync::{Arc, Mutex},
+    };
+
+    use http::{Method, Request, Response, StatusCode};
+    use http_body_util::BodyExt;
+    use kube::client::Body;
+    use serde_json::{Value, json};
+
+    use super::*;
+
+    /// An in-memory API server for whole-reconcile tests. Objects are kept by URL path; applies
+    /// and patches merge into them, lists honour label selectors but no field selectors, and
+    /// every write is recorded for the test to inspect.
+    #[derive(Clone, Default)]
+    pub struct FakeApi {
+        objects: Arc<Mutex<BTreeMap<String, Value>>>,
+        writes: Arc<Mutex<Vec<(Method, String, Value)>>>,
+    }
+
+    impl FakeApi {
+        pub fn insert(&self, path: &str, object: Value) {
+            self.objects.lock().unwrap().insert(path.into(), object);
+        }
+
+        /// The writes so far, as `(method, path, body)`.
+        pub fn writes(&self) -> Vec<(Method, String, Value)> {
+            self.writes.lock().unwrap().clone()
+        }
+
+        /// Reasons of the events published so far.
+        pub fn event_reasons(&self) -> Vec<String> {
+            self.writes()
+                .into_iter()
+                .filter(|(_, path, _)| path.ends_with("/events"))
+                .filter_map(|(_, _, body)| body["reason"].as_str().map(String::from))
+                .collect()
+        }
+
+        pub fn ctx(&self) -> Ctx {
+            let fake = self.clone();
+            let service = tower::service_fn(move |req: Request<Body>| {
+                let fake = fake.clone();
+                async move { Ok::<_, Infallible>(fake.handle(req).await) }
+            });
+            let client = Client::new(service, "default");
+            // the operator's own defaults, as no test sets the environment
+            let config = Config::from_env();
+            Ctx {
+                recorder: Recorder::new(client.clone(), make_reporter(&config)),
+                client,
+                config,
+                defaults: Default::default(),
+                failures: Default::default(),
+                locks: Default::default(),
+                syncs: Default::default(),
+                history: ReconcileHistory::new(1),
+                server_version: None,
+            }
+        }
+
+        async fn handle(&self, req: Request<Body>) -> Response<Body> {
+            let (parts, body) = req.into_parts();
+            let path = parts.uri.path().to_string();
+            let selector = parts
+                .uri
+                .query()
+                .into_iter()
+                .flat_map(|q| q.split('&'))
+                .find_map(|pair| pair.strip_prefix("labelSelector="))
+                .map(decode)
+                .unwrap_or_default();
+            let body = body.collect().await.unwrap().to_bytes();
+            let body: Value = serde_json::from_slice(&body).unwrap_or_default();
+            if parts.method != Method::GET {
+                self.writes.lock().unwrap().push((
+                    parts.method.clone(),
+                    path.clone(),
+                    body.clone(),
+                ));
+            }
+
+            let mut objects = self.objects.lock().unwrap();
+            let (path, subresource) = match path.strip_suffix("/status") {
+                Some(parent) => (parent.to_string(), true),
+                None => (path, false),
+            };
+            match parts.method {
+                Method::GET if collection(&path) => {
+                    let prefix = format!("{}/", path);
+                    let items: Vec<Value> = objects
+                        .iter()
+                        .filter(|(p, _)| p.strip_prefix(&prefix).is_some_and(|n| !n.contains('/')))
+                        .filter(|(_, o)| matches(&selector, &o["metadata"]["labels"]))
+                        .map(|(_, o)| o.clone())
+                        .collect();
+                    respond(
+                        StatusCode::OK,
+                        json!({"metadata": {"resourceVersion": "1"}, "items": items}),
+                    )
+                }
+                Method::GET => match objects.get(&path) {
+                    Some(object) => respond(StatusCode::OK, object.clone()),
+                    None => not_found(),
+                },
+                Method::POST => {
+                    let name = body["metadata"]["name"].as_str().unwrap_or("generated");
+                    let path = format!("{}/{}", path, name);
+                    let object = stamp(None, body.clone());
+                    objects.insert(path, object.clone());
+                    respond(StatusCode::CREATED, object)
+                }
+                Method::PATCH | Method::PUT => {
+                    let existing = objects.get(&path).cloned();
+                    let apply = parts
+                        .headers
+                        .get(http::header::CONTENT_TYPE)
+                        .is_some_and(|t| t.as_bytes().starts_with(b"application/apply-patch"));
+                    let object = match (existing.clone(), parts.method == Method::PUT) {
+                        (_, true) => body,
+                        (Some(mut object), false) => {
+                            if subresource {
+                                merge(&mut object["status"], &body["status"]);
+                            } else {
+                                merge(&mut object, &body);
+                            }
+                            object
+                        }
+                        (None, false) if apply => body,
+                        (None, false) => return not_found(),
+                    };
+                    let object = stamp(existing.as_ref(), object);
+                    objects.insert(path, object.clone());
+                    respond(StatusCode::OK, object)
+                }
+                Method::DELETE => match objects.remove(&path) {
+                    Some(object) => respond(StatusCode::OK, object),
+                    None => not_found(),
+                },
+                _ => not_found(),
+            }
+        }
+    }
+
+    /// Runs `test` to completion on a thread with room for a whole reconcile pass, whose
+    /// unoptimised frames overflow the default test thread.
+    pub fn run<F>(test: F) -> F::Output
+    where
+        F: std::future::Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        std::thread::Builder::new()
+            .stack_size(16 << 20)
+            .spawn(|| {
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .unwrap()
+                    .block_on(test)
+            })
+            .unwrap()
+            .join()
+            .unwrap()
+    }
+
+    /// Whether `path` names a collection rather than one object in it.
+    fn collection(path: &str) -> bool {
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let mut rest = match segments.first() {
+            Some(&"api") => &segments[2.min(segments.len())..],
+            _ => &segments[3.min(segments.len())..],
+        };
+        if rest.first() == Some(&"namespaces") && rest.len() >= 3 {
+            rest = &rest[2..];
+        }
+        rest.len() == 1
+    }
+
+    /// Whether `labels` satisfy every `k=v`, `k!=v`, `k` and `!k` term of `selector`.
+    fn matches(selector: &str, labels: &Value) -> bool {
+        selector.split(',').filter(|t| !t.is_empty()).all(|term| {
+            if let Some((key, value)) = term.split_once("!=") {
+                labels[key].as_str() != Some(value)
+            } else if let Some((key, value)) = term.split_once('=') {
+                labels[key.trim_end_matches('=')].as_str() == Some(value)
+            } else if let Some(key) = term.strip_prefix('!') {
+                labels[key].is_null()
+            } else {
+                !labels[term].is_null()
+            }
+        })
+    }
+
+    fn decode(query: &str) -> String {
+        let mut out = Vec::new();
+        let mut bytes = query.bytes();
+        while let Some(b) = bytes.next() {
+            match b {
+                b'%' => {
+                    let hex: String = bytes.by_ref().take(2).map(char::from).collect();
+                    out.push(u8::from_str_radix(&hex, 16).unwrap_or_default());
+                }
+                b'+' => out.push(b' '),
+                b => out.push(b),
+            }
+        }
+        String::from_utf8(out).unwrap_or_default()
+    }
+
+    /// A JSON merge patch, which server-side apply of a whole object comes down to here.
+    fn merge(target: &mut Value, patch: &Value) {
+        match (target, patch) {
+            (Value::Object(target), Value::Object(patch)) => {
+                for (key, value) in patch {
+                    if value.is_null() {
+                        target.remove(key);
+                    } else {
+                        merge(target.entry(key.clone()).or_insert(Value::Null), value);
+                    }
+                }
+            }
+            (target, patch) => *target = patch.clone(),
+        }
+    }
+
+    /// Bumps the resourceVersion only when the object changed, as the API server does.
+    fn stamp(existing: Option<&Value>, mut object: Value) -> Value {
+        let version = existing
+            .and_then(|e| e.pointer("/metadata/resourceVersion"))
+            .and_then(Value::as_str)
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or_default();
+        let unchanged = existing.is_some_and(|e| {
+            let mut e = e.clone();
+            e["metadata"]["resourceVersion"] = object["metadata"]["resourceVersion"].clone();
+            e == object
+        });
+        let version = if unchanged { version } else { version + 1 };
+        object["metadata"]["resourceVersion"] = json!(version.to_string());
+        object
+    }
+
+    fn respond(status: StatusCode, body: Value) -> Response<Body> {
+        Response::builder()
+            .status(status)
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap()
+    }
+
+    fn not_found() -> Response<Body> {
+        respond(
+            StatusCode::NOT_FOUND,
+            json!({
+                "kind": "Status",
+                "apiVersion": "v1",
+                "status": "Failure",
+                "message": "not found",
+                "reason": "NotFound",
+                "code": 404,
+            }),
+        )
+    }
+}
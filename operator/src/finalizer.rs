@@ -1,10 +1,23 @@
+use crate::dynamic::dynamic_api;
 use crate::error::Error;
+use crate::migrate::child_base_name;
+use crate::reconsile::{ADOPTED_ANN, FP_ANN, OWNER_LABEL, SERVICE_LABEL, delete_owned};
 use crate::{crd::ModelDeployment, event::Outcome};
+use k8s_openapi::NamespaceResourceScope;
+use k8s_openapi::api::{
+    apps::v1::Deployment,
+    autoscaling::v2::HorizontalPodAutoscaler,
+    core::v1::{ConfigMap, Service},
+    networking::v1::NetworkPolicy,
+    policy::v1::PodDisruptionBudget,
+};
+use kcr_traefik_io::v1alpha1::{ingressroutes::IngressRoute, traefikservices::TraefikService};
 use kube::{
     Api, Client, Resource, ResourceExt,
-    api::{Patch, PatchParams},
+    api::{DeleteParams, ListParams, Patch, PatchParams, TypeMeta},
 };
-use serde_json::json;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
 
 pub const FINALIZER: &str = "ml.jedimindtricks.example/finalizer";
 
@@ -65,3 +78,202 @@ pub async fn remove_finalizer(
         .await?;
     Ok(Outcome::Updated)
 }
+
+/// Adopted children existed before this ModelDeployment took them over. On deletion they are
+/// released (our owner reference and annotations removed) so garbage collection leaves them be;
+/// children we created ourselves are still collected through their owner reference. The
+/// children are taken from `status.managedResources`, or looked up by name for objects last
+/// reconciled before that was recorded.
+pub async fn disown_adopted(
+    client: &Client,
+    md: &ModelDeployment,
+    ns: &str,
+) -> Result<Outcome, Error> {
+    let base = child_base_name(md);
+    let uid = md.uid().unwrap_or_default();
+    let named = |suffix: &str| format!("{}{}", base, suffix);
+    let mut released = false;
+
+    let deployments: Api<Deployment> = Api::namespaced(client.clone(), ns);
+    let services: Api<Service> = Api::namespaced(client.clone(), ns);
+    let hpas: Api<HorizontalPodAutoscaler> = Api::namespaced(client.clone(), ns);
+    let policies: Api<NetworkPolicy> = Api::namespaced(client.clone(), ns);
+    let budgets: Api<PodDisruptionBudget> = Api::namespaced(client.clone(), ns);
+    let config_maps: Api<ConfigMap> = Api::namespaced(client.clone(), ns);
+    let traefik_services: Api<TraefikService> = Api::namespaced(client.clone(), ns);
+    let ingress_routes: Api<IngressRoute> = Api::namespaced(client.clone(), ns);
+
+    if let Some(recorded) = md
+        .status
+        .as_ref()
+        .and_then(|s| s.managed_resources.as_ref())
+    {
+        for child in recorded {
+            let name = &child.name;
+            // `extraResources` objects are collected along with the ModelDeployment regardless
+            released |= match child.kind.as_str() {
+                "Deployment" => disown(&deployments, name, &uid).await?,
+                "Service" => disown(&services, name, &uid).await?,
+                "HorizontalPodAutoscaler" => disown(&hpas, name, &uid).await?,
+                "NetworkPolicy" => disown(&policies, name, &uid).await?,
+                "PodDisruptionBudget" => disown(&budgets, name, &uid).await?,
+                "ConfigMap" => disown(&config_maps, name, &uid).await?,
+                "TraefikService" => disown(&traefik_services, name, &uid).await?,
+                "IngressRoute" => disown(&ingress_routes, name, &uid).await?,
+                _ => false,
+            };
+        }
+        return Ok(if released {
+            Outcome::Updated
+        } else {
+            Outcome::NoOp
+        });
+    }
+
+    let spec = &md.spec;
+    for role in ["live", "shadow"] {
+        released |= disown(&deployments, &spec.deployment_name(&base, role), &uid).await?;
+    }
+    for name in [
+        spec.service_name(&base, "live"),
+        spec.service_name(&base, "shadow"),
+    ] {
+        released |= disown(&services, &name, &uid).await?;
+    }
+    let lp = ListParams::default().labels(&format!("app={},{}", base, SERVICE_LABEL));
+    for svc in services.list(&lp).await? {
+        released |= disown(&services, &svc.name_any(), &uid).await?;
+    }
+    released |= disown(&hpas, &spec.deployment_name(&base, "live"), &uid).await?;
+    released |= disown(&policies, &named("-netpol"), &uid).await?;
+    released |= disown(&config_maps, &named("-config"), &uid).await?;
+    released |= disown(&traefik_services, &base, &uid).await?;
+
+    // missing Traefik CRDs must not block deletion
+    let lp = ListParams::default().labels(&format!("app={}", base));
+    match ingress_routes.list(&lp).await {
+        Ok(routes) => {
+            for route in routes {
+                released |= disown(&ingress_routes, &route.name_any(), &uid).await?;
+            }
+        }
+        Err(kube::Error::Api(resp)) if resp.code == 404 => {}
+        Err(e) => return Err(e.into()),
+    }
+
+    Ok(if released {
+        Outcome::Updated
+    } else {
+        Outcome::NoOp
+    })
+}
+
+/// Garbage collection never sees children in a `targetNamespace`, so the finalizer deletes
+/// them: everything carrying our `OWNER_LABEL` (adopted ones were already released by
+/// [`disown_adopted`]) and the recorded `extraResources`.
+pub async fn delete_cross_namespace(
+    client: &Client,
+    md: &ModelDeployment,
+    ns: &str,
+) -> Result<Outcome, Error> {
+    let lp =
+        ListParams::default().labels(&format!("{}={}", OWNER_LABEL, md.uid().unwrap_or_default()));
+    let mut deleted = false;
+
+    deleted |= delete_labelled::<Deployment>(client, ns, &lp).await?;
+    deleted |= delete_labelled::<Service>(client, ns, &lp).await?;
+    deleted |= delete_labelled::<HorizontalPodAutoscaler>(client, ns, &lp).await?;
+    deleted |= delete_labelled::<NetworkPolicy>(client, ns, &lp).await?;
+    deleted |= delete_labelled::<PodDisruptionBudget>(client, ns, &lp).await?;
+    deleted |= delete_labelled::<ConfigMap>(client, ns, &lp).await?;
+    deleted |= delete_labelled::<TraefikService>(client, ns, &lp).await?;
+    deleted |= delete_labelled::<IngressRoute>(client, ns, &lp).await?;
+
+    for extra in md
+        .status
+        .as_ref()
+        .and_then(|s| s.extra_resources.as_ref())
+        .into_iter()
+        .flatten()
+    {
+        let types = TypeMeta {
+            api_version: extra.api_version.clone(),
+            kind: extra.kind.clone(),
+        };
+        // a kind that can no longer be discovered took its objects with it
+        let api = match dynamic_api(client, ns, &types).await {
+            Ok((api, _)) => api,
+            Err(e) => {
+                tracing::warn!("Not deleting {} {}: {}", extra.kind, extra.name, e);
+                continue;
+            }
+        };
+        deleted |= delete_owned(&api, md, &extra.name).await? != Outcome::NoOp;
+    }
+
+    Ok(if deleted {
+        Outcome::Updated
+    } else {
+        Outcome::NoOp
+    })
+}
+
+async fn delete_labelled<K>(client: &Client, ns: &str, lp: &ListParams) -> Result<bool, Error>
+where
+    K: Resource<DynamicType = (), Scope = NamespaceResourceScope>
+        + Clone
+        + DeserializeOwned
+        + std::fmt::Debug,
+{
+    let api: Api<K> = Api::namespaced(client.clone(), ns);
+    // missing Traefik CRDs must not block deletion
+    let objs = match api.list_metadata(lp).await {
+        Ok(objs) => objs,
+        Err(kube::Error::Api(resp)) if resp.code == 404 => return Ok(false),
+        Err(e) => return Err(e.into()),
+    };
+    for obj in &objs {
+        match api
+            .delete(&obj.name_any(), &DeleteParams::background())
+            .await
+        {
+            Ok(_) => {}
+            Err(kube::Error::Api(resp)) if resp.code == 404 => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(!objs.items.is_empty())
+}
+
+async fn disown<K>(api: &Api<K>, name: &str, uid: &str) -> Result<bool, Error>
+where
+    K: Resource + Clone + DeserializeOwned + std::fmt::Debug,
+{
+    let obj = match api.get_opt(name).await {
+        Ok(Some(obj)) if obj.annotations().contains_key(ADOPTED_ANN) => obj,
+        Ok(_) => return Ok(false),
+        Err(kube::Error::Api(resp)) if resp.code == 404 => return Ok(false),
+        Err(e) => return Err(e.into()),
+    };
+
+    let owners: Vec<_> = obj
+        .owner_references()
+        .iter()
+        .filter(|o| o.uid != uid)
+        .cloned()
+        .collect();
+    let mut annotations = serde_json::Map::new();
+    annotations.insert(FP_ANN.into(), Value::Null);
+    annotations.insert(ADOPTED_ANN.into(), Value::Null);
+    let patch = json!({
+        "metadata": {
+            "ownerReferences": owners,
+            "annotations": annotations,
+            "labels": {OWNER_LABEL: null}
+        }
+    });
+
+    api.patch_metadata(name, &PatchParams::default(), &Patch::Merge(&patch))
+        .await?;
+    Ok(true)
+}
@@ -0,0 +1,93 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use k8s_openapi::chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::error::Error;
+
+/// One reconcile pass, as served on `/debug/reconciles/<namespace>/<name>`.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcileRecord {
+    pub started_at: String,
+    pub duration_ms: u128,
+    /// `Ok` or `Error`.
+    pub outcome: &'static str,
+    /// Some child was created or updated.
+    pub changed: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+struct Entries {
+    records: HashMap<String, VecDeque<ReconcileRecord>>,
+    changed: HashSet<String>,
+}
+
+/// The last `capacity` reconciles of each object, keyed by `namespace/name`, kept in memory for
+/// troubleshooting flapping objects without their logs. A capacity of 0 keeps nothing.
+#[derive(Clone, Default)]
+pub struct ReconcileHistory {
+    capacity: usize,
+    entries: Arc<Mutex<Entries>>,
+}
+
+impl ReconcileHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Default::default(),
+        }
+    }
+
+    /// Notes that the running reconcile of `key` changed a child, for its record.
+    pub fn mark_changed(&self, key: &str) {
+        if self.capacity > 0 {
+            self.entries.lock().unwrap().changed.insert(key.into());
+        }
+    }
+
+    pub fn record<T>(
+        &self,
+        key: &str,
+        started_at: DateTime<Utc>,
+        duration: Duration,
+        result: &Result<T, Error>,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        let changed = entries.changed.remove(key);
+        let records = entries.records.entry(key.into()).or_default();
+        if records.len() == self.capacity {
+            records.pop_front();
+        }
+        records.push_back(ReconcileRecord {
+            started_at: started_at.to_rfc3339(),
+            duration_ms: duration.as_millis(),
+            outcome: if result.is_ok() { "Ok" } else { "Error" },
+            changed,
+            error: result.as_ref().err().map(ToString::to_string),
+        });
+    }
+
+    /// Oldest first; `None` for an object with no reconciles recorded.
+    pub fn get(&self, key: &str) -> Option<Vec<ReconcileRecord>> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .records
+            .get(key)
+            .map(|records| records.iter().cloned().collect())
+    }
+
+    pub fn forget(&self, key: &str) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.records.remove(key);
+        entries.changed.remove(key);
+    }
+}
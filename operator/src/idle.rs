@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+use k8s_openapi::chrono::{DateTime, Utc};
+use kube::ResourceExt;
+
+use crate::{
+    analysis,
+    crd::{IdleStatus, ModelDeployment},
+};
+
+/// Set by an activator to the RFC3339 time of a request that arrived while live was at zero.
+pub const ACTIVATE_ANN: &str = "ml.jedimindtricks.example/activate";
+
+/// Where `scaleToZero` stands at `now`: live is active as of the latest of the last recorded
+/// activity, the activation annotation and (when the query sees traffic) now, and goes to zero
+/// once that is `idleSeconds` behind. The first observation starts the cooldown.
+pub async fn observe(md: &ModelDeployment, now: DateTime<Utc>) -> Option<IdleStatus> {
+    let config = md.spec.scale_to_zero.as_ref()?;
+    let previous = md
+        .status
+        .as_ref()
+        .and_then(|s| s.idle.as_ref())
+        .and_then(|idle| parse(&idle.last_active));
+    let activated = md.annotations().get(ACTIVATE_ANN).and_then(|t| {
+        let parsed = parse(t);
+        if parsed.is_none() {
+            tracing::warn!("Ignoring {} {:?} on {}", ACTIVATE_ANN, t, md.name_any());
+        }
+        parsed
+    });
+    let traffic = match analysis::sample(&config.prometheus_url, &config.query).await {
+        Ok(rate) => rate > 0.0,
+        Err(reason) => {
+            tracing::debug!("No request rate for {}: {}", md.name_any(), reason);
+            true
+        }
+    };
+
+    let last_active = [previous, activated, traffic.then_some(now)]
+        .into_iter()
+        .flatten()
+        .max()
+        .unwrap_or(now)
+        .min(now);
+    let idle_for = (now - last_active).to_std().unwrap_or_default();
+    Some(IdleStatus {
+        last_active: last_active.to_rfc3339(),
+        scaled_to_zero: idle_for >= Duration::from_secs(config.idle_seconds.into()),
+    })
+}
+
+/// How long until an active live is scaled to zero, to requeue in time for it.
+pub fn remaining(md: &ModelDeployment, idle: &IdleStatus, now: DateTime<Utc>) -> Option<Duration> {
+    let config = md.spec.scale_to_zero.as_ref()?;
+    if idle.scaled_to_zero {
+        return None;
+    }
+    let last_active = parse(&idle.last_active)?;
+    let idle_for = (now - last_active).to_std().unwrap_or_default();
+    Some(Duration::from_secs(config.idle_seconds.into()).saturating_sub(idle_for))
+}
+
+/// The Service routing points at instead of live while it is scaled to zero.
+pub fn activator(md: &ModelDeployment) -> Option<&str> {
+    let scaled_to_zero = md
+        .status
+        .as_ref()
+        .and_then(|s| s.idle.as_ref())
+        .is_some_and(|idle| idle.scaled_to_zero);
+    md.spec
+        .scale_to_zero
+        .as_ref()
+        .and_then(|c| c.activator_service.as_deref())
+        .filter(|_| scaled_to_zero)
+}
+
+fn parse(time: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(time)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
@@ -0,0 +1,337 @@
+use std::time::Duration;
+
+use serde_json::Value;
+
+/// Docker Hub, which unqualified names such as `nginx` or `org/model` resolve to.
+const DEFAULT_REGISTRY: &str = "docker.io";
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(10);
+const MANIFEST_TYPES: &str = "application/vnd.oci.image.index.v1+json, \
+    application/vnd.oci.image.manifest.v1+json, \
+    application/vnd.docker.distribution.manifest.list.v2+json, \
+    application/vnd.docker.distribution.manifest.v2+json";
+
+/// An image reference split the way a container runtime resolves it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageRef {
+    pub registry: String,
+    pub repository: String,
+    /// The digest if there is one, else the tag (`latest` when neither is given).
+    pub reference: String,
+}
+
+/// Parses `[registry[:port]/]repository[:tag][@digest]` following the distribution reference
+/// grammar, so a typo fails here instead of as an `ImagePullBackOff`.
+pub fn parse(image: &str) -> Result<ImageRef, String> {
+    if image.is_empty() {
+        return Err("is empty".into());
+    }
+    if image.chars().any(char::is_whitespace) {
+        return Err("contains whitespace".into());
+    }
+
+    let (rest, digest) = match image.split_once('@') {
+        Some((rest, digest)) => (rest, Some(digest)),
+        None => (image, None),
+    };
+    if let Some(digest) = digest {
+        check_digest(digest)?;
+    }
+    // the tag follows the last ':' unless that colon belongs to a registry port
+    let (name, tag) = match rest.rsplit_once(':') {
+        Some((name, tag)) if !tag.contains('/') => (name, Some(tag)),
+        _ => (rest, None),
+    };
+    if let Some(tag) = tag {
+        check_tag(tag)?;
+    }
+    if name.len() > 255 {
+        return Err(format!("name is {} characters (limit 255)", name.len()));
+    }
+
+    let mut components: Vec<&str> = name.split('/').collect();
+    let registry = match components.first() {
+        Some(first)
+            if components.len() > 1
+                && (first.contains('.') || first.contains(':') || *first == "localhost") =>
+        {
+            check_registry(first)?;
+            let registry = first.to_string();
+            components.remove(0);
+            registry
+        }
+        _ => DEFAULT_REGISTRY.to_string(),
+    };
+    for component in &components {
+        if !path_component(component) {
+            return Err(format!(
+                "path component {:?} must be lowercase alphanumerics separated by '.', '_', '__' or '-'",
+                component
+            ));
+        }
+    }
+    let mut repository = components.join("/");
+    if registry == DEFAULT_REGISTRY && components.len() == 1 {
+        repository = format!("library/{}", repository);
+    }
+
+    Ok(ImageRef {
+        registry,
+        repository,
+        reference: digest.or(tag).unwrap_or("latest").to_string(),
+    })
+}
+
+fn check_digest(digest: &str) -> Result<(), String> {
+    let (algorithm, hex) = digest
+        .split_once(':')
+        .ok_or_else(|| format!("digest {:?} must be <algorithm>:<hex>", digest))?;
+    let algorithm_ok = !algorithm.is_empty()
+        && algorithm.split(['+', '.', '_', '-']).all(|part| {
+            !part.is_empty()
+                && part
+                    .bytes()
+                    .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+        });
+    let hex_ok = hex.len() >= 32 && hex.bytes().all(|b| b.is_ascii_hexdigit());
+    if !algorithm_ok || !hex_ok || (algorithm == "sha256" && hex.len() != 64) {
+        return Err(format!("digest {:?} is malformed", digest));
+    }
+    Ok(())
+}
+
+fn check_tag(tag: &str) -> Result<(), String> {
+    let valid = !tag.is_empty()
+        && tag.len() <= 128
+        && tag.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_')
+        && tag
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'));
+    if !valid {
+        return Err(format!(
+            "tag {:?} must be up to 128 alphanumerics, '_', '.' or '-', not starting with '.' or '-'",
+            tag
+        ));
+    }
+    Ok(())
+}
+
+fn check_registry(registry: &str) -> Result<(), String> {
+    let (host, port) = match registry.rsplit_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (registry, None),
+    };
+    let host_ok = host.split('.').all(|label| {
+        !label.is_empty()
+            && label
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+    });
+    let port_ok = port.is_none_or(|p| p.parse::<u16>().is_ok_and(|p| p > 0));
+    if !host_ok || !port_ok {
+        return Err(format!("registry {:?} is not a host[:port]", registry));
+    }
+    Ok(())
+}
+
+fn path_component(component: &str) -> bool {
+    let bytes = component.as_bytes();
+    let alnum = |b: &u8| b.is_ascii_lowercase() || b.is_ascii_digit();
+    if bytes.is_empty() || !alnum(&bytes[0]) || !alnum(&bytes[bytes.len() - 1]) {
+        return false;
+    }
+    // separators are a single '.' or '_', '__', or any run of '-'
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'.' | b'_' => {
+                let run = bytes[i..]
+                    .iter()
+                    .take_while(|b| matches!(b, b'.' | b'_'))
+                    .count();
+                if !(run == 1 || (run == 2 && &bytes[i..i + 2] == b"__")) {
+                    return false;
+                }
+                i += run;
+            }
+            b'-' => i += 1,
+            b if alnum(&b) => i += 1,
+            _ => return false,
+        }
+    }
+    true
+}
+
+/// Whether `image` matches one of `patterns`: a pattern without a `/` is matched against the
+/// registry host, one with a `/` against `registry/repository`, so `docker.io` also covers the
+/// implicit Docker Hub of `nginx`. `*` matches any run of characters.
+pub fn allowed(image: &ImageRef, patterns: &[String]) -> bool {
+    let full = format!("{}/{}", image.registry, image.repository);
+    patterns.iter().any(|pattern| {
+        let subject = if pattern.contains('/') {
+            &full
+        } else {
+            &image.registry
+        };
+        glob(pattern, subject)
+    })
+}
+
+fn glob(pattern: &str, subject: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = subject.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+/// Asks the registry for the manifest with a HEAD request. `Ok(false)` only when the registry
+/// says it does not exist; credentials are limited to the anonymous pull token registries hand
+/// out for public images, so a private one answers with an error rather than a verdict.
+pub async fn exists(image: &ImageRef) -> Result<bool, String> {
+    let client = reqwest::Client::builder()
+        .timeout(LOOKUP_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let host = match image.registry.as_str() {
+        DEFAULT_REGISTRY => "registry-1.docker.io",
+        registry => registry,
+    };
+    let url = format!(
+        "https://{}/v2/{}/manifests/{}",
+        host, image.repository, image.reference
+    );
+    let head = |token: Option<&str>| {
+        let mut request = client.head(&url).header("Accept", MANIFEST_TYPES);
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        request.send()
+    };
+
+    let mut response = head(None).await.map_err(|e| e.to_string())?;
+    if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+        let challenge = response
+            .headers()
+            .get("www-authenticate")
+            .and_then(|h| h.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let token = anonymous_token(&client, &challenge).await?;
+        response = head(Some(&token)).await.map_err(|e| e.to_string())?;
+    }
+    match response.status().as_u16() {
+        200 => Ok(true),
+        404 => Ok(false),
+        status => Err(format!("{} answered {}", host, status)),
+    }
+}
+
+/// Follows a `Bearer realm=...,service=...,scope=...` challenge without credentials.
+async fn anonymous_token(client: &reqwest::Client, challenge: &str) -> Result<String, String> {
+    let params = challenge
+        .strip_prefix("Bearer ")
+        .ok_or_else(|| format!("unsupported authentication {:?}", challenge))?;
+    let mut realm = None;
+    let mut query = Vec::new();
+    for param in params.split(',') {
+        let Some((key, value)) = param.trim().split_once('=') else {
+            continue;
+        };
+        let value = value.trim_matches('"');
+        match key {
+            "realm" => realm = Some(value),
+            "service" | "scope" => query.push((key, value)),
+            _ => {}
+        }
+    }
+    let realm = realm.ok_or("authentication challenge has no realm")?;
+
+    let response = client
+        .get(realm)
+        .query(&query)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("token endpoint answered {}", response.status()));
+    }
+    let body: Value = serde_json::from_str(&response.text().await.map_err(|e| e.to_string())?)
+        .map_err(|e| format!("bad token JSON: {}", e))?;
+    body.get("token")
+        .or_else(|| body.get("access_token"))
+        .and_then(Value::as_str)
+        .map(String::from)
+        .ok_or_else(|| "token endpoint returned no token".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allowed_by(image: &str, patterns: &[&str]) -> bool {
+        let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+        allowed(&parse(image).unwrap(), &patterns)
+    }
+
+    #[test]
+    fn registries_are_allowed_by_host_or_repository() {
+        assert!(allowed_by(
+            "registry.corp.example/model:1",
+            &["registry.corp.example"]
+        ));
+        assert!(!allowed_by(
+            "evil.example/model:1",
+            &["registry.corp.example"]
+        ));
+        // a host pattern does not match a repository of the same name elsewhere
+        assert!(!allowed_by(
+            "evil.example/registry.corp.example:1",
+            &["registry.corp.example"]
+        ));
+
+        assert!(allowed_by("ghcr.io/org/model:1", &["ghcr.io/org/*"]));
+        assert!(!allowed_by("ghcr.io/other/model:1", &["ghcr.io/org/*"]));
+        assert!(allowed_by("eu.gcr.io/model:1", &["*.gcr.io"]));
+        assert!(!allowed_by("gcr.io/model:1", &["*.gcr.io"]));
+    }
+
+    #[test]
+    fn unqualified_names_resolve_to_docker_hub() {
+        assert!(allowed_by("nginx", &["docker.io"]));
+        assert!(allowed_by("nginx:1.27", &["docker.io/library/*"]));
+        assert!(allowed_by("org/model", &["docker.io/org/*"]));
+        assert!(!allowed_by("nginx", &["registry.corp.example"]));
+        assert!(!allowed_by("nginx", &[]));
+    }
+
+    #[test]
+    fn glob_edge_cases() {
+        assert!(glob("exact", "exact"));
+        assert!(!glob("exact", "exactly"));
+        assert!(glob("*", ""));
+        assert!(glob("*", "anything"));
+        assert!(glob("*.example", "a.example"));
+        assert!(!glob("*.example", "a.example.org"));
+        assert!(glob("registry.*", "registry.corp"));
+        assert!(glob("a*b*c", "aXbYc"));
+        assert!(glob("a*b*c", "abc"));
+        assert!(!glob("a*b*c", "acb"));
+        assert!(glob("a**b", "ab"));
+        // the prefix and suffix may not share characters
+        assert!(!glob("a*a", "a"));
+        assert!(glob("a*a", "aa"));
+    }
+}
@@ -0,0 +1,51 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+/// Per-object async locks, keyed by UID, serialising everything that mutates an object's
+/// in-memory state (failure streaks, metrics) or writes its status.
+///
+/// The controller already never runs two reconciles of one object at once, but the status
+/// write spawned by `error_policy` outlives its reconcile and would otherwise race the next one.
+/// A reconcile that finds the lock taken does not wait: it requeues shortly, so events arriving
+/// meanwhile coalesce into a single follow-up pass. Guards are only ever held by a reconcile
+/// body or a single status patch, neither of which waits on another lock, so the status
+/// write's own watch event can't deadlock against the guard that issued it.
+#[derive(Clone, Default)]
+pub struct ObjectLocks(Arc<Mutex<HashMap<String, Arc<AsyncMutex<()>>>>>);
+
+impl ObjectLocks {
+    fn entry(&self, uid: &str) -> Arc<AsyncMutex<()>> {
+        self.0
+            .lock()
+            .unwrap()
+            .entry(uid.into())
+            .or_default()
+            .clone()
+    }
+
+    /// Takes the object's lock if nobody holds it.
+    pub fn try_acquire(&self, uid: &str) -> Option<OwnedMutexGuard<()>> {
+        self.entry(uid).try_lock_owned().ok()
+    }
+
+    /// Waits for the object's lock; for short writes that must not be dropped.
+    pub async fn acquire(&self, uid: &str) -> OwnedMutexGuard<()> {
+        self.entry(uid).lock_owned().await
+    }
+
+    /// Drops the lock of a deleted object, unless a guard is still out for it: removing a
+    /// held lock would let the next caller create a second one and run alongside its holder.
+    pub fn forget(&self, uid: &str) {
+        let mut locks = self.0.lock().unwrap();
+        if locks
+            .get(uid)
+            .is_some_and(|lock| Arc::strong_count(lock) == 1)
+        {
+            locks.remove(uid);
+        }
+    }
+}
@@ -1,31 +1,147 @@
+mod analysis;
+mod audit;
+mod backoff;
+mod canary;
+mod config;
 mod crd;
+mod defaults;
+mod downgrade;
+mod dynamic;
 mod error;
 mod event;
 mod finalizer;
+mod history;
+mod idle;
+mod image;
+mod locks;
+mod maintenance;
+mod metrics;
+mod migrate;
+mod oneshot;
+mod plan;
+mod preflight;
 mod reconsile;
+mod resync;
+mod retry;
+mod rightsize;
+mod telemetry;
+mod validation;
 
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
-use crd::ModelDeployment;
+use config::Config;
+use defaults::{OperatorDefaults, watch_defaults};
 use event::{Ctx, make_reporter};
 use futures::stream::StreamExt;
-use kube::{Api, Client};
-use kube_runtime::{Controller, watcher};
+use kube::client::ClientBuilder;
+use kube_runtime::{Controller, controller, watcher};
 use reconsile::{error_policy, reconsile};
-use tracing_subscriber::{EnvFilter, fmt};
+use tower::util::AndThenLayer;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    fmt().with_env_filter(EnvFilter::from_default_env()).init();
+    let tracer_provider = telemetry::init()?;
 
-    let client = Client::try_default().await?;
-    let api = Api::<ModelDeployment>::all(client.clone());
+    let mut args = std::env::args().skip(1);
+    let command = match args.next() {
+        None => None,
+        Some(cmd) if cmd == "reconcile" || cmd == "plan" => {
+            Some((cmd, args.next().ok_or(oneshot::USAGE)?))
+        }
+        Some(cmd) if cmd == "validate" => {
+            let path = match (args.next().as_deref(), args.next()) {
+                (Some("-f"), Some(path)) => path,
+                _ => return Err(oneshot::USAGE.into()),
+            };
+            let result = oneshot::validate_file(&path);
+            if let Some(provider) = tracer_provider {
+                provider.shutdown()?;
+            }
+            return result;
+        }
+        Some(_) => return Err(oneshot::USAGE.into()),
+    };
 
-    let reporter = make_reporter();
+    let client = ClientBuilder::try_from(kube::Config::infer().await?)?
+        .with_layer(&AndThenLayer::new(retry::surface_retry_after))
+        .build();
+    let config = Config::from_env();
+    let api = preflight::watched(&client, &config);
+    let reporter = make_reporter(&config);
     let recorder = kube_runtime::events::Recorder::new(client.clone(), reporter);
-    let ctx = Arc::new(Ctx { client, recorder });
+    let defaults = Arc::new(RwLock::new(OperatorDefaults::default()));
+    let server_version = match client.apiserver_version().await {
+        Ok(info) => Some(info),
+        Err(e) => {
+            tracing::warn!("Cannot read the API server version: {}", e);
+            None
+        }
+    };
+
+    let ctx = Arc::new(Ctx {
+        client: client.clone(),
+        recorder,
+        config: config.clone(),
+        defaults: defaults.clone(),
+        failures: Default::default(),
+        locks: Default::default(),
+        syncs: Default::default(),
+        history: history::ReconcileHistory::new(config.history_size),
+        server_version,
+    });
+
+    if let Some((cmd, target)) = command {
+        let result = match cmd.as_str() {
+            "plan" => plan::run(ctx, &target).await,
+            _ => oneshot::run(ctx, &target).await,
+        };
+        if let Some(provider) = tracer_provider {
+            provider.shutdown()?;
+        }
+        return result;
+    }
+
+    if let Err(e) = preflight::check(&client, &config).await {
+        tracing::error!("Preflight failed: {}", e);
+        return Err(e.into());
+    }
+    tracing::info!("Preflight passed");
+
+    let (defaults_changed, reconcile_all) = futures::channel::mpsc::channel(1);
+    if let Some(name) = &config.defaults_configmap {
+        tokio::spawn(watch_defaults(
+            client,
+            config.defaults_namespace.clone(),
+            name.clone(),
+            defaults,
+            defaults_changed,
+        ));
+    }
 
-    Controller::new(api, watcher::Config::default())
+    tokio::spawn(metrics::serve(
+        config.metrics_addr.clone(),
+        ctx.history.clone(),
+    ));
+
+    if let Some(ns) = &config.watch_namespace {
+        tracing::info!("Watching ModelDeployments in namespace {}", ns);
+    }
+    let mut watcher_config = watcher::Config::default();
+    if let Some(selector) = &config.label_selector {
+        config::check_label_selector(selector)?;
+        tracing::info!("Watching ModelDeployments matching {}", selector);
+        watcher_config = watcher_config.labels(selector);
+    }
+
+    tokio::spawn(event::forget_deleted(
+        api.clone(),
+        watcher_config.clone(),
+        ctx.clone(),
+    ));
+
+    Controller::new(api, watcher_config)
+        .with_config(controller::Config::default().concurrency(config.reconcile_concurrency))
+        .reconcile_all_on(reconcile_all)
         .run(reconsile, error_policy, ctx)
         .for_each(|res| async move {
             match res {
@@ -40,5 +156,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
         })
         .await;
+
+    if let Some(provider) = tracer_provider {
+        provider.shutdown()?;
+    }
     Ok(())
 }
@@ -0,0 +1,37 @@
+use std::time::Duration;
+
+use k8s_openapi::chrono::{DateTime, Utc};
+
+use crate::crd::TimeWindow;
+
+/// Parses both RFC3339 bounds of the window at spec field `field`.
+pub fn bounds(field: &str, window: &TimeWindow) -> Result<(DateTime<Utc>, DateTime<Utc>), String> {
+    let parse = |bound: &str, value: &str| {
+        DateTime::parse_from_rfc3339(value)
+            .map(|t| t.with_timezone(&Utc))
+            .map_err(|e| format!("{}.{} {:?} is not RFC3339: {}", field, bound, value, e))
+    };
+    let start = parse("start", &window.start)?;
+    let end = parse("end", &window.end)?;
+    if start >= end {
+        return Err(format!("{0}.start must be before {0}.end", field));
+    }
+    Ok((start, end))
+}
+
+/// How long until the window closes, if `now` falls inside it.
+pub fn remaining(window: &TimeWindow, now: DateTime<Utc>) -> Option<Duration> {
+    let (start, end) = bounds("", window).ok()?;
+    (start <= now && now < end)
+        .then(|| (end - now).to_std().ok())
+        .flatten()
+}
+
+/// How long until the window next opens or closes, if it still will.
+pub fn next_change(window: &TimeWindow, now: DateTime<Utc>) -> Option<Duration> {
+    let (start, end) = bounds("", window).ok()?;
+    [start, end]
+        .into_iter()
+        .find(|&t| t > now)
+        .and_then(|t| (t - now).to_std().ok())
+}
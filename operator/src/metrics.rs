@@ -0,0 +1,163 @@
+use std::sync::LazyLock;
+
+use prometheus::{
+    Encoder, HistogramVec, IntGaugeVec, TextEncoder, register_histogram_vec, register_int_gauge_vec,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+use crate::{
+    crd::{ChildStatus, ModelDeploymentStatus},
+    history::ReconcileHistory,
+};
+
+pub static CONSECUTIVE_FAILURES: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "model_deployment_consecutive_failures",
+        "Consecutive failed reconciles per ModelDeployment",
+        &["namespace", "name"]
+    )
+    .unwrap()
+});
+
+pub static LIVE_AVAILABLE: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "model_deployment_live_available_replicas",
+        "Available replicas of the live Deployment",
+        &["namespace", "name"]
+    )
+    .unwrap()
+});
+
+pub static SHADOW_AVAILABLE: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "model_deployment_shadow_available_replicas",
+        "Available replicas of the shadow Deployment",
+        &["namespace", "name"]
+    )
+    .unwrap()
+});
+
+/// Time from a `live.image` change until the live Deployment is fully available again.
+pub static ROLLOUT_SECONDS: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "model_rollout_seconds",
+        "Seconds from a live image change to full availability",
+        &["namespace", "name"],
+        vec![
+            10.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1200.0, 1800.0, 3600.0
+        ]
+    )
+    .unwrap()
+});
+
+/// 1 for the phase a ModelDeployment is in, 0 for the others.
+pub static PHASE: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "model_deployment_phase",
+        "Current phase per ModelDeployment",
+        &["namespace", "name", "phase"]
+    )
+    .unwrap()
+});
+
+const PHASES: [&str; 4] = ["Available", "Progressing", "Degraded", "Suspended"];
+
+/// Mirrors a freshly written status into the per-ModelDeployment gauges.
+pub fn record_status(ns: &str, name: &str, status: &ModelDeploymentStatus) {
+    let available = |child: &Option<ChildStatus>| {
+        child
+            .as_ref()
+            .and_then(|c| c.available_replicas)
+            .unwrap_or(0)
+    };
+    LIVE_AVAILABLE
+        .with_label_values(&[ns, name])
+        .set(available(&status.live_status).into());
+    match &status.shadow_status {
+        Some(_) => SHADOW_AVAILABLE
+            .with_label_values(&[ns, name])
+            .set(available(&status.shadow_status).into()),
+        None => {
+            let _ = SHADOW_AVAILABLE.remove_label_values(&[ns, name]);
+        }
+    }
+    for phase in PHASES {
+        PHASE
+            .with_label_values(&[ns, name, phase])
+            .set((status.phase.as_deref() == Some(phase)).into());
+    }
+}
+
+/// Drops every series labelled with a deleted ModelDeployment.
+pub fn forget(ns: &str, name: &str) {
+    let _ = CONSECUTIVE_FAILURES.remove_label_values(&[ns, name]);
+    let _ = LIVE_AVAILABLE.remove_label_values(&[ns, name]);
+    let _ = SHADOW_AVAILABLE.remove_label_values(&[ns, name]);
+    let _ = ROLLOUT_SECONDS.remove_label_values(&[ns, name]);
+    for phase in PHASES {
+        let _ = PHASE.remove_label_values(&[ns, name, phase]);
+    }
+}
+
+/// Serves the default registry on `GET /metrics`, and the recent reconciles of an object as
+/// JSON on `GET /debug/reconciles/<namespace>/<name>`.
+pub async fn serve(addr: String, history: ReconcileHistory) {
+    let listener = match TcpListener::bind(&addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            tracing::error!("Failed to bind metrics server on {}: {}", addr, e);
+            return;
+        }
+    };
+    tracing::info!("Serving metrics on {}", addr);
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tokio::spawn(handle(stream, history.clone()));
+            }
+            Err(e) => tracing::warn!("Metrics connection failed: {}", e),
+        }
+    }
+}
+
+async fn handle(mut stream: TcpStream, history: ReconcileHistory) {
+    let mut buf = [0u8; 1024];
+    let n = stream.read(&mut buf).await.unwrap_or(0);
+    let request = String::from_utf8_lossy(&buf[..n]);
+    let path = request.split_whitespace().nth(1).unwrap_or("/");
+
+    let reconciles = path
+        .strip_prefix("/debug/reconciles/")
+        .and_then(|key| history.get(key));
+    let (status, content_type, body) = match path {
+        "/metrics" => {
+            let encoder = TextEncoder::new();
+            let mut body = Vec::new();
+            let _ = encoder.encode(&prometheus::gather(), &mut body);
+            ("200 OK", encoder.format_type().to_string(), body)
+        }
+        _ if reconciles.is_some() => (
+            "200 OK",
+            "application/json".into(),
+            serde_json::to_vec_pretty(&reconciles).unwrap_or_default(),
+        ),
+        _ => (
+            "404 Not Found",
+            "text/plain".into(),
+            b"not found\n".to_vec(),
+        ),
+    };
+
+    let header = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    );
+    let _ = stream.write_all(header.as_bytes()).await;
+    let _ = stream.write_all(&body).await;
+}
@@ -0,0 +1,176 @@
+use k8s_openapi::api::{
+    apps::v1::Deployment,
+    autoscaling::v2::HorizontalPodAutoscaler,
+    core::v1::{ConfigMap, Service},
+    networking::v1::NetworkPolicy,
+};
+use kcr_traefik_io::v1alpha1::{ingressroutes::IngressRoute, traefikservices::TraefikService};
+use kube::{
+    Api, Client, Resource, ResourceExt,
+    api::{ListParams, Patch, PatchParams},
+};
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+
+use crate::{
+    crd::ModelDeployment,
+    error::Error,
+    reconsile::{OWNER_LABEL, SERVICE_LABEL, cross_namespace, owned_by},
+};
+
+/// Names a deleted (orphaned) ModelDeployment whose children this one takes over. They keep
+/// their names and labels, so nothing is recreated; once they are ours the annotation is
+/// replaced by [`CHILD_BASE_ANN`].
+pub const ADOPT_FROM_ANN: &str = "ml.jedimindtricks.example/adopt-from";
+
+/// Name prefix of the children after an `adopt-from` migration. Kept on the object rather
+/// than in its status, so the children are still found after the status is lost, e.g. on a
+/// restore from backup.
+pub const CHILD_BASE_ANN: &str = "ml.jedimindtricks.example/child-base-name";
+
+/// Name prefix of the children: the pending `adopt-from`, then a completed one, then our name.
+pub fn child_base_name(md: &ModelDeployment) -> String {
+    let annotations = md.annotations();
+    annotations
+        .get(ADOPT_FROM_ANN)
+        .or_else(|| annotations.get(CHILD_BASE_ANN))
+        .cloned()
+        .unwrap_or_else(|| md.name_any())
+}
+
+/// Namespace of the children: `targetNamespace`, or the ModelDeployment's own.
+pub fn child_namespace(md: &ModelDeployment) -> String {
+    md.spec
+        .target_namespace
+        .clone()
+        .or_else(|| md.namespace())
+        .unwrap_or_else(|| "default".into())
+}
+
+/// Points the owner reference (or, in a `targetNamespace`, the owner label) of every child of
+/// `old` that still exists at `md`, returning how many moved. Children already ours are skipped and missing ones are left for the reconcile
+/// to create, so an interrupted migration is simply run again.
+pub async fn adopt_children(
+    client: &Client,
+    md: &ModelDeployment,
+    ns: &str,
+    old: &str,
+) -> Result<usize, Error> {
+    let mds: Api<ModelDeployment> = Api::namespaced(client.clone(), ns);
+    if old != md.name_any() && mds.get_metadata_opt(old).await?.is_some() {
+        return Err(Error::Validation(format!(
+            "{} {} still exists; delete it with --cascade=orphan before adopting its children",
+            ADOPT_FROM_ANN, old
+        )));
+    }
+
+    let named = |suffix: &str| format!("{}{}", old, suffix);
+    let mut moved = 0;
+    let ns = &child_namespace(md);
+
+    let deployments: Api<Deployment> = Api::namespaced(client.clone(), ns);
+    let services: Api<Service> = Api::namespaced(client.clone(), ns);
+    let hpas: Api<HorizontalPodAutoscaler> = Api::namespaced(client.clone(), ns);
+    let policies: Api<NetworkPolicy> = Api::namespaced(client.clone(), ns);
+    let config_maps: Api<ConfigMap> = Api::namespaced(client.clone(), ns);
+    let traefik_services: Api<TraefikService> = Api::namespaced(client.clone(), ns);
+    let ingress_routes: Api<IngressRoute> = Api::namespaced(client.clone(), ns);
+
+    let spec = &md.spec;
+    for role in ["live", "shadow"] {
+        moved += usize::from(reown(&deployments, &spec.deployment_name(old, role), md).await?);
+    }
+    let lp = ListParams::default().labels(&format!("app={},{}", old, SERVICE_LABEL));
+    let extra: Vec<String> = services
+        .list(&lp)
+        .await?
+        .iter()
+        .map(|s| s.name_any())
+        .collect();
+    for name in [
+        spec.service_name(old, "live"),
+        spec.service_name(old, "shadow"),
+    ]
+    .into_iter()
+    .chain(extra)
+    {
+        moved += usize::from(reown(&services, &name, md).await?);
+    }
+    moved += usize::from(reown(&hpas, &spec.deployment_name(old, "live"), md).await?);
+    moved += usize::from(reown(&policies, &named("-netpol"), md).await?);
+    moved += usize::from(reown(&config_maps, &named("-config"), md).await?);
+
+    moved += usize::from(reown(&traefik_services, old, md).await?);
+
+    // without the Traefik CRDs there is no routing to take over
+    let lp = ListParams::default().labels(&format!("app={}", old));
+    let routes = match ingress_routes.list(&lp).await {
+        Ok(routes) => routes.items,
+        Err(kube::Error::Api(resp)) if resp.code == 404 => Vec::new(),
+        Err(e) => return Err(e.into()),
+    };
+    for route in routes {
+        moved += usize::from(reown(&ingress_routes, &route.name_any(), md).await?);
+    }
+
+    Ok(moved)
+}
+
+async fn reown<K>(api: &Api<K>, name: &str, md: &ModelDeployment) -> Result<bool, Error>
+where
+    K: Resource + Clone + DeserializeOwned + std::fmt::Debug,
+{
+    let Some(obj) = api.get_metadata_opt(name).await? else {
+        return Ok(false);
+    };
+    if owned_by(md, &obj) {
+        return Ok(false);
+    }
+
+    // drop the old ModelDeployment's reference, keep any others
+    let owner = md.controller_owner_ref(&()).unwrap();
+    let mut owners: Vec<Value> = obj
+        .owner_references()
+        .iter()
+        .filter(|o| o.kind != owner.kind || o.api_version != owner.api_version)
+        .map(|o| json!(o))
+        .collect();
+    // across namespaces the owner label is all there is
+    let label = if cross_namespace(md) {
+        json!(owner.uid)
+    } else {
+        owners.push(json!(owner));
+        Value::Null
+    };
+    let patch = json!({
+        "metadata": {"ownerReferences": owners, "labels": {OWNER_LABEL: label}}
+    });
+    api.patch_metadata(name, &PatchParams::default(), &Patch::Merge(&patch))
+        .await?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crd::testing::model_deployment;
+    use serde_json::json;
+
+    #[test]
+    fn child_base_name_survives_a_lost_status() {
+        let mut md = model_deployment(json!({"live": {"image": "m:1"}}));
+        md.metadata.name = Some("new".into());
+        assert_eq!(child_base_name(&md), "new");
+
+        md.annotations_mut()
+            .insert(ADOPT_FROM_ANN.into(), "old".into());
+        assert_eq!(child_base_name(&md), "old");
+
+        // what a finished migration leaves behind, with no status at all
+        md.annotations_mut().remove(ADOPT_FROM_ANN);
+        md.annotations_mut()
+            .insert(CHILD_BASE_ANN.into(), "old".into());
+        md.status = None;
+        assert_eq!(child_base_name(&md), "old");
+    }
+}
@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::Api;
+use serde::Deserialize;
+
+use crate::{
+    config::Config,
+    crd::ModelDeployment,
+    defaults::OperatorDefaults,
+    event::Ctx,
+    reconsile::reconsile,
+    validation::{check_registries, validate},
+};
+
+pub const USAGE: &str =
+    "usage: operator [reconcile|plan <namespace>/<name> | validate -f <file.yaml>]";
+
+pub fn parse_target(target: &str) -> Result<(&str, &str), &'static str> {
+    target.split_once('/').ok_or(USAGE)
+}
+
+/// The controller keeps defaults fresh with a watch; a single pass only needs one read.
+pub async fn load_defaults(ctx: &Ctx) -> Result<(), kube::Error> {
+    if let Some(cm_name) = &ctx.config.defaults_configmap {
+        let cms: Api<ConfigMap> =
+            Api::namespaced(ctx.client.clone(), &ctx.config.defaults_namespace);
+        if let Some(cm) = cms.get_opt(cm_name).await? {
+            *ctx.defaults.write().unwrap() = OperatorDefaults::from_config_map(&cm);
+        }
+    }
+    Ok(())
+}
+
+/// Checks every ModelDeployment in a (multi-document) manifest against the rules `reconsile`
+/// enforces, without a cluster, so CI can reject a manifest before it is applied. Other kinds
+/// are skipped; the checks against the cluster itself (like `targetNamespace` access) are not run.
+pub fn validate_file(path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let manifest = std::fs::read_to_string(path)?;
+    let mut failed = 0;
+    let config = Config::from_env();
+    for (i, document) in serde_yaml::Deserializer::from_str(&manifest).enumerate() {
+        let value = serde_yaml::Value::deserialize(document)?;
+        if value.is_null() || value["kind"].as_str() != Some("ModelDeployment") {
+            continue;
+        }
+        let name = value["metadata"]["name"]
+            .as_str()
+            .map_or_else(|| format!("document {}", i + 1), str::to_string);
+        let result = serde_yaml::from_value::<ModelDeployment>(value)
+            .map_err(|e| e.to_string())
+            .and_then(|md| {
+                validate(&md, &config)
+                    .and_then(|()| check_registries(&md, &config.registry_allowlist))
+                    .map_err(|e| e.to_string())
+            });
+        match result {
+            Ok(()) => println!("{}: ok", name),
+            Err(e) => {
+                println!("{}: {}", name, e);
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(format!(
+            "{} ModelDeployment(s) in {} failed validation",
+            failed, path
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Runs one `reconsile` pass for `<namespace>/<name>` and prints the resulting action and
+/// status, without starting the watch loop. Handy against a kind cluster while iterating.
+pub async fn run(ctx: Arc<Ctx>, target: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let (ns, name) = parse_target(target)?;
+    load_defaults(&ctx).await?;
+
+    let api: Api<ModelDeployment> = Api::namespaced(ctx.client.clone(), ns);
+    let before = api.get(name).await?;
+    let action = reconsile(Arc::new(before.clone()), ctx).await?;
+    println!("action: {:?}", action);
+
+    let after = api.get(name).await?;
+    let before = serde_yaml::to_string(&before.status)?;
+    let after = serde_yaml::to_string(&after.status)?;
+    if before == after {
+        println!("status unchanged");
+    } else {
+        println!("status before:\n{}", before);
+        println!("status after:\n{}", after);
+    }
+    Ok(())
+}
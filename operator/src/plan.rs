@@ -0,0 +1,486 @@
+use std::sync::Arc;
+
+use k8s_openapi::api::{
+    apps::v1::Deployment,
+    autoscaling::v2::HorizontalPodAutoscaler,
+    core::v1::{ConfigMap, Service},
+    networking::v1::NetworkPolicy,
+    policy::v1::PodDisruptionBudget,
+};
+use k8s_openapi::chrono::Utc;
+use kcr_traefik_io::v1alpha1::{ingressroutes::IngressRoute, traefikservices::TraefikService};
+use kube::{
+    Api, Resource, ResourceExt,
+    api::{DynamicObject, ListParams, TypeMeta},
+};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::{
+    crd::ModelDeployment,
+    dynamic::dynamic_api,
+    event::Ctx,
+    idle, maintenance,
+    migrate::{child_base_name, child_namespace},
+    oneshot::{load_defaults, parse_target},
+    reconsile::{
+        DeploymentType, FREEZE_ANN, SERVICE_LABEL, add_cost_labels, clamp_replicas,
+        desired_alias_service, desired_canary_service, desired_config_map, desired_deployment,
+        desired_extra_service, desired_hpa, desired_ingress_route, desired_network_policy,
+        desired_pdb, desired_service, desired_traefik_service, effective_replicas, external_hpa,
+        extra_service_name, hpa_enabled, ingress_route_name, inline_config_name, json_contains,
+        live_service_managed, owned_by, pdb_name, relabel_owner, scaler_replicas, shadow_replicas,
+        stamp_config_hash, strip_traffic_distribution,
+    },
+    validation::{check_registries, validate},
+};
+
+/// Prints what a reconcile of `<namespace>/<name>` would create, update or delete, with
+/// field-level diffs, without applying anything. Objects are built by the same `desired_*`
+/// functions the reconciler applies and compared with the same "every field we set matches"
+/// rule it uses for drift; status, events and the finalizer are out of scope.
+pub async fn run(ctx: Arc<Ctx>, target: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let (ns, name) = parse_target(target)?;
+    load_defaults(&ctx).await?;
+
+    let mds: Api<ModelDeployment> = Api::namespaced(ctx.client.clone(), ns);
+    let md = mds.get(name).await?;
+    let mut md = ctx.defaults.read().unwrap().apply(&md);
+    if let Some(replicas) = scaler_replicas(&md) {
+        md.spec.live.manage_replicas = true;
+        md.spec.replicas = Some(replicas);
+    }
+    if let Some(cap) = ctx.config.max_replicas_cap {
+        clamp_replicas(&mut md.spec, cap);
+    }
+    if let Some(version) = strip_traffic_distribution(ctx.server_version.as_ref(), &mut md.spec) {
+        eprintln!(
+            "warning: API server {} predates trafficDistribution; planning without it",
+            version
+        );
+    }
+    if let Some(hpa) = external_hpa(&ctx.client, &md).await? {
+        eprintln!(
+            "note: HorizontalPodAutoscaler {} scales the live Deployment; planning without replicas",
+            hpa
+        );
+        md.spec.live.manage_replicas = false;
+    }
+    if let Some(state) = idle::observe(&md, Utc::now()).await {
+        if state.scaled_to_zero {
+            eprintln!(
+                "note: live idle since {}; planning it at zero replicas",
+                state.last_active
+            );
+            md.spec.replicas = Some(0);
+        }
+        md.status.get_or_insert_with(Default::default).idle = Some(state);
+    }
+    validate(&md, &ctx.config)?;
+    check_registries(&md, &ctx.config.registry_allowlist)?;
+
+    let spec = &md.spec;
+    let base = child_base_name(&md);
+    let ns = &child_namespace(&md);
+    let client = &ctx.client;
+    let mut plan = Vec::new();
+
+    let services: Api<Service> = Api::namespaced(client.clone(), ns);
+    let deployments: Api<Deployment> = Api::namespaced(client.clone(), ns);
+    let hpas: Api<HorizontalPodAutoscaler> = Api::namespaced(client.clone(), ns);
+    let policies: Api<NetworkPolicy> = Api::namespaced(client.clone(), ns);
+    let budgets: Api<PodDisruptionBudget> = Api::namespaced(client.clone(), ns);
+    let config_maps: Api<ConfigMap> = Api::namespaced(client.clone(), ns);
+    let traefik_services: Api<TraefikService> = Api::namespaced(client.clone(), ns);
+    let ingress_routes: Api<IngressRoute> = Api::namespaced(client.clone(), ns);
+
+    if live_service_managed(spec) {
+        plan.push(
+            compare(
+                &md,
+                &services,
+                desired_service(&md, &base, DeploymentType::Live),
+            )
+            .await?,
+        );
+    }
+    if spec.shadow.is_some() {
+        plan.push(
+            compare(
+                &md,
+                &services,
+                desired_service(&md, &base, DeploymentType::Shadow),
+            )
+            .await?,
+        );
+    }
+    let extra = spec.services.as_deref().unwrap_or_default();
+    for config in extra {
+        plan.push(compare(&md, &services, desired_extra_service(&md, &base, config)).await?);
+    }
+    let keep: Vec<String> = extra
+        .iter()
+        .map(|c| extra_service_name(spec, &base, c))
+        .collect();
+    let lp = ListParams::default().labels(&format!("app={},{}", base, SERVICE_LABEL));
+    for svc in services.list(&lp).await? {
+        if owned_by(&md, &svc) && !keep.contains(&svc.name_any()) {
+            plan.push(Step::Delete(describe::<Service>(&svc.name_any())));
+        }
+    }
+
+    if spec.alias_service {
+        let alias = desired_alias_service(&md, &base, ns, &ctx.config.cluster_domain);
+        plan.push(compare(&md, &services, alias).await?);
+    } else {
+        plan.extend(removal(&services, &base).await?);
+    }
+
+    let cm_name = inline_config_name(&base);
+    match &spec.inline_config {
+        Some(data) => {
+            plan.push(compare(&md, &config_maps, desired_config_map(&md, &base, data)).await?)
+        }
+        None => plan.extend(removal(&config_maps, &cm_name).await?),
+    }
+
+    let mut live = desired_deployment(
+        &md,
+        &spec.deployment_name(&base, "live"),
+        &base,
+        &spec.live,
+        effective_replicas(spec, &DeploymentType::Live),
+        DeploymentType::Live,
+    );
+    stamp_config_hash(client, &md, &mut live).await?;
+    plan.push(compare(&md, &deployments, live).await?);
+    if let Some(shadow) = &spec.shadow {
+        let mut shadow = desired_deployment(
+            &md,
+            &spec.deployment_name(&base, "shadow"),
+            &base,
+            shadow,
+            shadow_replicas(&md),
+            DeploymentType::Shadow,
+        );
+        stamp_config_hash(client, &md, &mut shadow).await?;
+        plan.push(compare(&md, &deployments, shadow).await?);
+    }
+
+    let hpa_name = spec.deployment_name(&base, "live");
+    match &spec.autoscaling {
+        Some(autoscaling) if hpa_enabled(spec) => {
+            plan.push(compare(&md, &hpas, desired_hpa(&md, &hpa_name, autoscaling)).await?)
+        }
+        _ => plan.extend(removal(&hpas, &hpa_name).await?),
+    }
+
+    let np_name = format!("{}-netpol", base);
+    match &spec.network_policy {
+        Some(policy) => plan.push(
+            compare(
+                &md,
+                &policies,
+                desired_network_policy(&md, &np_name, &base, policy),
+            )
+            .await?,
+        ),
+        None => plan.extend(removal(&policies, &np_name).await?),
+    }
+
+    let pdb_name = pdb_name(&base);
+    match &spec.disruption_budget {
+        Some(budget) => {
+            let in_maintenance = spec
+                .maintenance_window
+                .as_ref()
+                .is_some_and(|window| maintenance::remaining(window, Utc::now()).is_some());
+            let pdb = desired_pdb(
+                &md,
+                &pdb_name,
+                &base,
+                budget.max_unavailable(in_maintenance),
+            );
+            plan.push(compare(&md, &budgets, pdb).await?)
+        }
+        None => plan.extend(removal(&budgets, &pdb_name).await?),
+    }
+
+    let canary = spec.is_canary();
+    if spec.traffic_mirror || canary {
+        // the weight the canary currently sits at; advancing it is the reconciler's call
+        let ts = if canary {
+            let weight = md
+                .status
+                .as_ref()
+                .and_then(|s| s.canary.as_ref())
+                .map_or(0, |c| c.weight);
+            desired_canary_service(&md, &base, ns, weight)
+        } else {
+            // shown with the mirror enabled, even if it is still held for the shadow
+            desired_traefik_service(&md, &base, ns, true)
+        };
+        plan.push(compare(&md, &traefik_services, ts).await?);
+
+        let routes = spec.effective_routes();
+        for route in &routes {
+            plan.push(
+                compare(
+                    &md,
+                    &ingress_routes,
+                    desired_ingress_route(&md, &base, ns, route),
+                )
+                .await?,
+            );
+        }
+
+        let keep: Vec<String> = routes
+            .iter()
+            .map(|r| ingress_route_name(&base, &r.entry_point))
+            .collect();
+        let lp = ListParams::default().labels(&format!("app={}", base));
+        // without the Traefik CRDs there is nothing to prune
+        let existing = match ingress_routes.list(&lp).await {
+            Ok(list) => list.items,
+            Err(kube::Error::Api(resp)) if resp.code == 404 => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+        for ir in existing {
+            if owned_by(&md, &ir) && !keep.contains(&ir.name_any()) {
+                plan.push(Step::Delete(describe::<IngressRoute>(&ir.name_any())));
+            }
+        }
+    }
+
+    // applied untyped, so read back through discovery like the reconciler does
+    let mut listed = Vec::new();
+    for raw in spec.extra_resources.iter().flatten() {
+        let mut obj: DynamicObject = serde_json::from_value(raw.clone())?;
+        let types = obj.types.clone().unwrap_or_default();
+        let (api, _) = dynamic_api(client, ns, &types).await?;
+        obj.metadata.namespace = Some(ns.clone());
+        obj.metadata.owner_references = md.controller_owner_ref(&()).map(|owner| vec![owner]);
+        relabel_owner(&md, &mut obj);
+        add_cost_labels(&md, &mut obj);
+        let name = obj.name_any();
+        let live = api.get_opt(&name).await?;
+        plan.push(step(
+            format!("{} {}", types.kind, name),
+            live.as_ref(),
+            &obj,
+        ));
+        listed.push((types.api_version, types.kind, name));
+    }
+    let applied = md.status.as_ref().and_then(|s| s.extra_resources.as_ref());
+    for stale in applied.into_iter().flatten().filter(|r| {
+        !listed
+            .iter()
+            .any(|(v, k, n)| (v, k, n) == (&r.api_version, &r.kind, &r.name))
+    }) {
+        let types = TypeMeta {
+            api_version: stale.api_version.clone(),
+            kind: stale.kind.clone(),
+        };
+        let what = format!("{} {}", stale.kind, stale.name);
+        let api = match dynamic_api(client, ns, &types).await {
+            Ok((api, _)) => api,
+            Err(e) => {
+                eprintln!("note: skipping {}, which cannot be discovered: {}", what, e);
+                continue;
+            }
+        };
+        let live = api.get_opt(&stale.name).await?;
+        if live.is_some_and(|live| owned_by(&md, &live)) {
+            plan.push(Step::Delete(what));
+        }
+    }
+
+    let mut changes = 0;
+    for step in &plan {
+        match step {
+            Step::Create(what) => println!("+ {} (create)", what),
+            Step::Update(what, fields) => {
+                println!("~ {} (update)", what);
+                for field in fields {
+                    println!("    {}", field);
+                }
+            }
+            Step::Delete(what) => println!("- {} (delete)", what),
+            Step::Unchanged(what) => println!("  {} (no changes)", what),
+        }
+        changes += usize::from(!matches!(step, Step::Unchanged(_)));
+    }
+    println!(
+        "\nPlan: {} of {} objects would change.",
+        changes,
+        plan.len()
+    );
+    Ok(())
+}
+
+enum Step {
+    Create(String),
+    Update(String, Vec<String>),
+    Delete(String),
+    Unchanged(String),
+}
+
+fn describe<K: Resource<DynamicType = ()>>(name: &str) -> String {
+    format!("{} {}", K::kind(&()), name)
+}
+
+async fn compare<K>(md: &ModelDeployment, api: &Api<K>, mut desired: K) -> Result<Step, kube::Error>
+where
+    K: Resource<DynamicType = ()> + Clone + DeserializeOwned + Serialize + std::fmt::Debug,
+{
+    relabel_owner(md, &mut desired);
+    add_cost_labels(md, &mut desired);
+    let name = desired.name_any();
+    let live = api.get_opt(&name).await?;
+    Ok(step(describe::<K>(&name), live.as_ref(), &desired))
+}
+
+/// What applying `desired` over `live` would do.
+fn step<K: Resource + Serialize>(what: String, live: Option<&K>, desired: &K) -> Step {
+    let Some(live) = live else {
+        return Step::Create(what);
+    };
+    // the reconciler leaves frozen children alone, whatever they hold
+    if live.annotations().get(FREEZE_ANN).map(String::as_str) == Some("true") {
+        return Step::Unchanged(format!("{} (frozen)", what));
+    }
+
+    let live = serde_json::to_value(live).unwrap_or_default();
+    let desired = serde_json::to_value(desired).unwrap_or_default();
+    let mut fields = Vec::new();
+    diff("", &live, &desired, &mut fields);
+    if fields.is_empty() {
+        Step::Unchanged(what)
+    } else {
+        Step::Update(what, fields)
+    }
+}
+
+/// Objects the reconciler deletes when their feature is switched off.
+async fn removal<K>(api: &Api<K>, name: &str) -> Result<Option<Step>, kube::Error>
+where
+    K: Resource<DynamicType = ()> + Clone + DeserializeOwned + std::fmt::Debug,
+{
+    Ok(api
+        .get_opt(name)
+        .await?
+        .map(|_| Step::Delete(describe::<K>(name))))
+}
+
+/// Collects `path: live -> desired` for every field we set that the live object disagrees on.
+fn diff(path: &str, live: &Value, desired: &Value, out: &mut Vec<String>) {
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+    match (live, desired) {
+        (Value::Object(live), Value::Object(desired)) => {
+            for (key, value) in desired {
+                diff(
+                    &join(key),
+                    live.get(key).unwrap_or(&Value::Null),
+                    value,
+                    out,
+                );
+            }
+        }
+        (Value::Array(live), Value::Array(desired)) if live.len() == desired.len() => {
+            for (i, (l, d)) in live.iter().zip(desired).enumerate() {
+                diff(&format!("{}[{}]", path, i), l, d, out);
+            }
+        }
+        (live, desired) if json_contains(live, desired) => {}
+        (live, desired) => out.push(format!("{}: {} -> {}", path, live, desired)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config_map(value: serde_json::Value) -> ConfigMap {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn steps_follow_the_live_object() {
+        let desired = config_map(json!({
+            "metadata": {"name": "model-inline-config"},
+            "data": {"a": "1", "b": "2"},
+        }));
+        let what = || "ConfigMap model-inline-config".to_string();
+
+        assert!(matches!(step(what(), None, &desired), Step::Create(_)));
+
+        // fields the server fills in are no change
+        let live = config_map(json!({
+            "metadata": {"name": "model-inline-config", "resourceVersion": "3"},
+            "data": {"a": "1", "b": "2"},
+        }));
+        assert!(matches!(
+            step(what(), Some(&live), &desired),
+            Step::Unchanged(_)
+        ));
+
+        let live = config_map(json!({
+            "metadata": {"name": "model-inline-config"},
+            "data": {"a": "1", "b": "3"},
+        }));
+        match step(what(), Some(&live), &desired) {
+            Step::Update(_, fields) => assert_eq!(fields, [r#"data.b: "3" -> "2""#]),
+            _ => panic!("expected an update"),
+        }
+
+        let mut frozen = live.clone();
+        frozen
+            .annotations_mut()
+            .insert(FREEZE_ANN.into(), "true".into());
+        match step(what(), Some(&frozen), &desired) {
+            Step::Unchanged(what) => assert!(what.ends_with("(frozen)")),
+            _ => panic!("a frozen object is left alone"),
+        }
+    }
+
+    #[test]
+    fn lists_of_another_length_are_replaced_whole() {
+        let mut fields = Vec::new();
+        diff(
+            "",
+            &json!({"args": ["--a"]}),
+            &json!({"args": ["--a", "--b"]}),
+            &mut fields,
+        );
+        assert_eq!(fields, [r#"args: ["--a"] -> ["--a","--b"]"#]);
+    }
+
+    #[test]
+    fn extra_resources_are_planned_untyped() {
+        let desired: DynamicObject = serde_json::from_value(json!({
+            "apiVersion": "monitoring.coreos.com/v1",
+            "kind": "ServiceMonitor",
+            "metadata": {"name": "model"},
+            "spec": {"endpoints": [{"port": "http"}]},
+        }))
+        .unwrap();
+        let what = || "ServiceMonitor model".to_string();
+        assert!(matches!(step(what(), None, &desired), Step::Create(_)));
+
+        let mut live = desired.clone();
+        live.data["spec"]["endpoints"][0]["port"] = json!("metrics");
+        match step(what(), Some(&live), &desired) {
+            Step::Update(_, fields) => {
+                assert_eq!(fields, [r#"spec.endpoints[0].port: "metrics" -> "http""#])
+            }
+            _ => panic!("expected an update"),
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use k8s_openapi::{
+    api::authorization::v1::{
+        ResourceAttributes, SelfSubjectAccessReview, SelfSubjectAccessReviewSpec,
+    },
+    apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition,
+};
+use kube::{
+    Api, Client, CustomResourceExt, Resource,
+    api::{ListParams, Patch, PatchParams, PostParams},
+};
+
+use crate::{config::Config, crd::ModelDeployment};
+
+/// (API group, resource, verb) the reconciler cannot work without.
+const REQUIRED: &[(&str, &str, &str)] = &[
+    (
+        "ml.jedimindtricks.example",
+        "modeldeployments/status",
+        "patch",
+    ),
+    ("apps", "deployments", "patch"),
+    ("", "services", "patch"),
+];
+
+/// What a `targetNamespace` needs on top: its children are deleted by the finalizer, not by
+/// garbage collection.
+const TARGET_REQUIRED: &[(&str, &str, &str)] = &[
+    ("apps", "deployments", "patch"),
+    ("apps", "deployments", "delete"),
+    ("", "services", "patch"),
+    ("", "services", "delete"),
+];
+
+/// Checks up front that the ModelDeployment CRD is usable and that the operator may list the
+/// ModelDeployments it watches and patch what it manages, so a missing CRD or RBAC shows up as one clear
+/// startup failure instead of a cryptic error on every reconcile.
+pub async fn check(client: &Client, config: &Config) -> Result<(), String> {
+    check_crd(client, config).await?;
+
+    let mds: Api<ModelDeployment> = watched(client, config);
+    mds.list_metadata(&ListParams::default().limit(1))
+        .await
+        .map_err(|e| format!("cannot list ModelDeployments: {}", e))?;
+
+    check_access(client, config.watch_namespace.as_deref(), REQUIRED).await
+}
+
+/// The ModelDeployments this operator watches: those in `WATCH_NAMESPACE`, or all of them.
+pub fn watched(client: &Client, config: &Config) -> Api<ModelDeployment> {
+    match &config.watch_namespace {
+        Some(ns) => Api::namespaced(client.clone(), ns),
+        None => Api::all(client.clone()),
+    }
+}
+
+/// Checks that the operator may manage children in `ns`, another ModelDeployment's
+/// `targetNamespace`.
+pub async fn check_namespace(client: &Client, ns: &str) -> Result<(), String> {
+    check_access(client, Some(ns), TARGET_REQUIRED).await
+}
+
+/// Asks the API server whether each of `required` is allowed, in `ns` or cluster-wide.
+async fn check_access(
+    client: &Client,
+    ns: Option<&str>,
+    required: &[(&str, &str, &str)],
+) -> Result<(), String> {
+    let reviews: Api<SelfSubjectAccessReview> = Api::all(client.clone());
+    let mut denied = Vec::new();
+    for &(group, resource, verb) in required {
+        let (resource, subresource) = match resource.split_once('/') {
+            Some((resource, sub)) => (resource, Some(sub.to_string())),
+            None => (resource, None),
+        };
+        let review = SelfSubjectAccessReview {
+            spec: SelfSubjectAccessReviewSpec {
+                resource_attributes: Some(ResourceAttributes {
+                    group: Some(group.into()),
+                    namespace: ns.map(Into::into),
+                    resource: Some(resource.into()),
+                    subresource,
+                    verb: Some(verb.into()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let allowed = reviews
+            .create(&PostParams::default(), &review)
+            .await
+            .map_err(|e| format!("access review failed: {}", e))?
+            .status
+            .is_some_and(|s| s.allowed);
+        if !allowed {
+            let group = if group.is_empty() { "core" } else { group };
+            denied.push(format!("{} {}.{}", verb, resource, group));
+        }
+    }
+
+    if denied.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "missing RBAC permissions: {}; see deploy/rbac.yaml",
+            denied.join(", ")
+        ))
+    }
+}
+
+/// The deprecation warning of the CRD version we read ModelDeployments as, if it is
+/// deprecated. An unreadable CRD counts as not deprecated.
+pub async fn version_deprecation(client: &Client) -> Option<String> {
+    let crds: Api<CustomResourceDefinition> = Api::all(client.clone());
+    let crd = crds.get_opt(ModelDeployment::crd_name()).await.ok()??;
+    let version = ModelDeployment::version(&());
+    let served = crd.spec.versions.into_iter().find(|v| v.name == version)?;
+    served.deprecated.filter(|&d| d).map(|_| {
+        served
+            .deprecation_warning
+            .unwrap_or_else(|| format!("{} is deprecated", version))
+    })
+}
+
+/// The CRD must exist and serve our version with the status subresource; without it every
+/// status patch fails with a 404 that doesn't say why. With `INSTALL_CRD` it is applied first.
+async fn check_crd(client: &Client, config: &Config) -> Result<(), String> {
+    let crds: Api<CustomResourceDefinition> = Api::all(client.clone());
+    let name = ModelDeployment::crd_name();
+    if config.install_crd {
+        let pp = PatchParams::apply(&config.field_manager).force();
+        crds.patch(name, &pp, &Patch::Apply(ModelDeployment::crd()))
+            .await
+            .map_err(|e| format!("cannot install CRD {}: {}", name, e))?;
+        tracing::info!("Applied CRD {}", name);
+    }
+
+    let fix = "apply crds/modeldeployment.yaml or set INSTALL_CRD=true";
+    let crd = crds
+        .get_opt(name)
+        .await
+        .map_err(|e| format!("cannot read CRD {}: {}", name, e))?
+        .ok_or_else(|| format!("CRD {} is not installed; {}", name, fix))?;
+    let version = ModelDeployment::version(&());
+    let served = crd
+        .spec
+        .versions
+        .iter()
+        .find(|v| v.name == version && v.served)
+        .ok_or_else(|| format!("CRD {} does not serve {}; {}", name, version, fix))?;
+    if served
+        .subresources
+        .as_ref()
+        .is_none_or(|s| s.status.is_none())
+    {
+        return Err(format!(
+            "CRD {} {} has no status subresource; {}",
+            name, version, fix
+        ));
+    }
+    if served.deprecated == Some(true) {
+        tracing::warn!(
+            "CRD {} marks {} deprecated: {}",
+            name,
+            version,
+            served
+                .deprecation_warning
+                .as_deref()
+                .unwrap_or("no warning given")
+        );
+    }
+    Ok(())
+}
@@ -1,48 +1,104 @@
-use std::{collections::BTreeMap, fmt::Display, sync::Arc, time::Duration};
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use crate::{
-    crd::{ChildStatus, Condition, ModelDeployment, ModelDeploymentSpec, ModelDeploymentStatus},
+    analysis::{self, Verdict},
+    audit,
+    backoff::RETRY_DELAY,
+    canary,
+    config::DEFAULT_INSTANCE,
+    crd::{
+        AdoptionPolicy, AutoScalingSpec, ChildStatus, Condition, ExtraResourceRef, ImageChange,
+        ManagedResource, ModelChecksum, ModelDeployment, ModelDeploymentSpec,
+        ModelDeploymentStatus, ModelReadiness, ModelVariant, NetworkPolicyConfig, PendingChange,
+        ProbeSpec, ResourceLimits, ResourceSpec, RouteSpec, ScalingBehavior, ScalingMetric,
+        SchedulingSpec, ServiceConfig, VolumeConfig, release,
+    },
+    downgrade,
+    dynamic::dynamic_api,
     error::Error,
-    event::{Ctx, Outcome, emit_event, with_event},
+    event::{Ctx, Outcome, emit_event, emit_event_for, with_event},
     finalizer::{
-        FINALIZER, ensure_finalizer_present, has_finalizer, is_deleting, remove_finalizer,
+        FINALIZER, delete_cross_namespace, disown_adopted, ensure_finalizer_present, has_finalizer,
+        is_deleting, remove_finalizer,
     },
+    idle, image, maintenance, metrics,
+    migrate::{self, ADOPT_FROM_ANN, CHILD_BASE_ANN, child_base_name, child_namespace},
+    preflight,
+    retry::{self, retry_read, retry_throttled},
+    rightsize,
+    validation::{check_registries, validate},
 };
+use futures::StreamExt;
+use k8s_openapi::chrono::{DateTime, Utc};
 use k8s_openapi::{
+    NamespaceResourceScope,
+    api::networking::v1::{
+        NetworkPolicy, NetworkPolicyIngressRule, NetworkPolicyPeer, NetworkPolicyPort,
+        NetworkPolicySpec,
+    },
+    api::policy::v1::{PodDisruptionBudget, PodDisruptionBudgetSpec},
     api::{
-        apps::v1::{Deployment, DeploymentSpec, DeploymentStrategy, RollingUpdateDeployment},
+        apps::v1::{
+            Deployment, DeploymentSpec, DeploymentStrategy, ReplicaSet, RollingUpdateDeployment,
+        },
+        autoscaling::v2::{
+            CrossVersionObjectReference, ExternalMetricSource, HPAScalingPolicy, HPAScalingRules,
+            HorizontalPodAutoscaler, HorizontalPodAutoscalerBehavior, HorizontalPodAutoscalerSpec,
+            MetricIdentifier, MetricSpec, MetricTarget, PodsMetricSource, ResourceMetricSource,
+        },
         core::v1::{
-            Container, ContainerPort, PodSpec, PodTemplateSpec, Service, ServicePort, ServiceSpec,
+            Affinity, CSIVolumeSource, ClientIPConfig, ConfigMap, ConfigMapVolumeSource, Container,
+            ContainerPort, EmptyDirVolumeSource, EnvFromSource, EnvVar, EnvVarSource,
+            HTTPGetAction, HostPathVolumeSource, Lifecycle, LifecycleHandler, LocalObjectReference,
+            NodeAffinity, NodeSelector, NodeSelectorRequirement, NodeSelectorTerm,
+            ObjectFieldSelector, PersistentVolumeClaimVolumeSource, Pod, PodReadinessGate, PodSpec,
+            PodTemplateSpec, Probe, ResourceRequirements, Secret, SecretEnvSource,
+            SecretVolumeSource, Service, ServicePort, ServiceSpec, SessionAffinityConfig,
+            SleepAction, TopologySpreadConstraint, Volume, VolumeMount,
         },
     },
     apimachinery::pkg::{
+        api::resource::Quantity,
         apis::meta::v1::{LabelSelector, OwnerReference},
         util::intstr::IntOrString,
+        version::Info,
     },
 };
 use kcr_traefik_io::v1alpha1::{
     ingressroutes::{
         IngressRoute, IngressRouteRoutes, IngressRouteRoutesKind, IngressRouteRoutesServices,
-        IngressRouteRoutesServicesKind, IngressRouteSpec,
+        IngressRouteRoutesServicesKind, IngressRouteRoutesServicesSticky,
+        IngressRouteRoutesServicesStickyCookie, IngressRouteRoutesServicesStickyCookieSameSite,
+        IngressRouteSpec, IngressRouteTls,
     },
     traefikservices::{
         TraefikService, TraefikServiceMirroring, TraefikServiceMirroringKind,
         TraefikServiceMirroringMirrors, TraefikServiceMirroringMirrorsKind, TraefikServiceSpec,
+        TraefikServiceWeighted, TraefikServiceWeightedServices, TraefikServiceWeightedServicesKind,
     },
 };
 use kube::{
     Api, Client,
-    api::{ObjectMeta, Patch, PatchParams},
+    api::{
+        DeleteParams, DynamicObject, ListParams, ObjectMeta, Patch, PatchParams, PostParams,
+        Preconditions, TypeMeta,
+    },
     core::object::HasSpec,
 };
 use kube::{Resource, ResourceExt};
 use kube_runtime::{controller::Action, events::EventType};
+use rand::Rng;
 use serde::{Serialize, de::DeserializeOwned};
 use serde_json::json;
 use sha2::{Digest, Sha256};
 
 #[derive(Debug, PartialEq)]
-enum DeploymentType {
+pub enum DeploymentType {
     Live,
     Shadow,
 }
@@ -60,23 +116,436 @@ impl AsRef<str> for DeploymentType {
     fn as_ref(&self) -> &str {
         match self {
             DeploymentType::Live => "live",
-            DeploymentType::Shadow => "Shadow",
+            DeploymentType::Shadow => "shadow",
         }
     }
 }
 
+const SERVING_PORT: i32 = 8000;
+const PROPAGATE_PREFIX: &str = "propagate.ml.jedimindtricks.example/";
+pub const OPERATOR_DOMAIN: &str = "ml.jedimindtricks.example/";
+
+/// `lastReconcileTime` is only moved forward once it is this old. Writing a fresh timestamp
+/// on every pass would make each status patch trigger the next reconcile.
+const HEARTBEAT: Duration = Duration::from_secs(30);
+/// How soon a phase held over stale child reads is re-evaluated.
+const STALE_READ_RECHECK: Duration = Duration::from_secs(10);
+/// How often a held-back mirror re-checks shadow readiness.
+const MIRROR_RECHECK: Duration = Duration::from_secs(15);
+/// How often a Deployment held back by `rolloutOrder` re-checks the one it waits for.
+const ORDER_RECHECK: Duration = Duration::from_secs(15);
+/// How soon a pass that left children missing is driven again.
+const INCOMPLETE_RECHECK: Duration = Duration::from_secs(5);
+/// How soon a reconcile that found its object locked is retried.
+const LOCK_RETRY: Duration = Duration::from_secs(1);
+/// The periodic requeue for `phase` moved by up to `REQUEUE_JITTER` of itself either way, so
+/// objects reconciled together (e.g. after an operator restart) drift apart instead of all
+/// requeuing at once.
+fn resync_interval(ctx: &Ctx, phase: Option<&str>) -> Duration {
+    let resync = ctx.config.resync.for_phase(phase);
+    let jitter = ctx.config.requeue_jitter;
+    if jitter <= 0.0 {
+        return resync;
+    }
+    resync.mul_f64(1.0 + rand::rng().random_range(-jitter..=jitter))
+}
+
+fn last_phase(md: &ModelDeployment) -> Option<&str> {
+    md.status.as_ref().and_then(|s| s.phase.as_deref())
+}
+
+fn last_reconcile_time(md: &ModelDeployment) -> Option<String> {
+    let previous = md
+        .status
+        .as_ref()
+        .and_then(|s| s.last_reconcile_time.clone());
+    let now = Utc::now();
+    let fresh = previous
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .and_then(|t| (now - t.with_timezone(&Utc)).to_std().ok())
+        .is_some_and(|age| age < HEARTBEAT);
+    if fresh {
+        previous
+    } else {
+        Some(now.to_rfc3339())
+    }
+}
+
+/// Replica count for a role's Deployment, or `None` when something else owns it. Highest
+/// precedence first: `manageReplicas: false` (None), `suspend` (0), autoscaling (live only),
+/// `spec.replicas` (live only), then the variant's own `replicas`, which defaults to 1.
+///
+/// Leaving `replicas` out of the apply releases our server-side-apply ownership of the field,
+/// so an HPA or GitOps controller can write it without conflicts. That also means `suspend`
+/// cannot scale an unmanaged variant down.
+pub fn effective_replicas(spec: &ModelDeploymentSpec, role: &DeploymentType) -> Option<i32> {
+    let variant = match role {
+        DeploymentType::Live => Some(&spec.live),
+        DeploymentType::Shadow => spec.shadow.as_ref(),
+    };
+    if variant.is_some_and(|v| !v.manage_replicas) {
+        return None;
+    }
+    if spec.suspend {
+        return Some(0);
+    }
+
+    match role {
+        DeploymentType::Live if hpa_enabled(spec) => None,
+        DeploymentType::Live => Some(spec.replicas.unwrap_or(spec.live.replicas)),
+        DeploymentType::Shadow => Some(spec.shadow.as_ref().map_or(0, |s| s.replicas)),
+    }
+}
+
+/// Shadow replicas to apply: the spec count, or with `scaleCanary` its share at the weight the
+/// canary last reached. A step that just advanced is therefore scaled up on the next pass,
+/// which the status write of the advance triggers straight away.
+pub fn shadow_replicas(md: &ModelDeployment) -> Option<i32> {
+    let replicas = effective_replicas(&md.spec, &DeploymentType::Shadow)?;
+    if !(md.spec.scale_canary && md.spec.is_canary()) {
+        return Some(replicas);
+    }
+    let weight = md
+        .status
+        .as_ref()
+        .and_then(|s| s.canary.as_ref())
+        .map_or(0, |c| c.weight);
+    Some(canary::scaled_replicas(replicas, weight))
+}
+
+pub fn hpa_enabled(spec: &ModelDeploymentSpec) -> bool {
+    !spec.suspend && spec.autoscaling.as_ref().is_some_and(|a| a.enabled)
+}
+
+/// Set by an external scaler to the live replica count it wants. Honoured while
+/// `live.manageReplicas` is false and autoscaling is off, so event-driven scalers can drive the
+/// live Deployment without an HPA.
+pub const SCALER_REPLICAS_ANN: &str = "ml.jedimindtricks.example/desired-replicas";
+
+/// The live replicas asked for through `SCALER_REPLICAS_ANN`, when the spec leaves them free.
+pub fn scaler_replicas(md: &ModelDeployment) -> Option<i32> {
+    let spec = &md.spec;
+    if spec.live.manage_replicas || spec.autoscaling.as_ref().is_some_and(|a| a.enabled) {
+        return None;
+    }
+    let value = md.annotations().get(SCALER_REPLICAS_ANN)?;
+    let parsed = value.trim().parse::<i32>().ok().filter(|r| *r >= 0);
+    if parsed.is_none() {
+        tracing::warn!(
+            "Ignoring {} {:?} on {}: not a non-negative integer",
+            SCALER_REPLICAS_ANN,
+            value,
+            md.name_any()
+        );
+    }
+    parsed
+}
+
+/// An HPA someone else created for the live Deployment. While one exists `live.replicas` is
+/// left to it, as with `manageReplicas: false`, rather than fighting it on every reconcile.
+pub async fn external_hpa(client: &Client, md: &ModelDeployment) -> Result<Option<String>, Error> {
+    let ns = child_namespace(md);
+    let api: Api<HorizontalPodAutoscaler> = Api::namespaced(client.clone(), &ns);
+    let live = md.spec.deployment_name(&child_base_name(md), "live");
+    let lp = ListParams::default();
+    let hpas = retry_read(|| api.list(&lp)).await?;
+    Ok(hpas
+        .into_iter()
+        .find(|hpa| {
+            let target = hpa.spec.as_ref().map(|s| &s.scale_target_ref);
+            target.is_some_and(|t| t.kind == "Deployment" && t.name == live) && !owned_by(md, hpa)
+        })
+        .map(|hpa| hpa.name_any()))
+}
+
 fn owner_ref(md: &ModelDeployment) -> OwnerReference {
     md.controller_owner_ref(&()).unwrap()
 }
 
+/// Copies `propagate.ml.jedimindtricks.example/<key>` annotations as `<key>`. Keys in the
+/// operator's own domain are dropped so propagation can never shadow managed annotations.
+fn propagated_annotations(md: &ModelDeployment) -> Option<BTreeMap<String, String>> {
+    let annotations: BTreeMap<String, String> = md
+        .annotations()
+        .iter()
+        .filter_map(|(k, v)| {
+            let key = k.strip_prefix(PROPAGATE_PREFIX)?;
+            (!key.is_empty() && !key.starts_with(OPERATOR_DOMAIN))
+                .then(|| (key.to_string(), v.clone()))
+        })
+        .collect();
+
+    (!annotations.is_empty()).then_some(annotations)
+}
+
+/// Propagated annotations, plus `serviceAnnotations` on a load balancer.
+fn service_annotations(
+    md: &ModelDeployment,
+    service_type: Option<&str>,
+) -> Option<BTreeMap<String, String>> {
+    let mut annotations = propagated_annotations(md).unwrap_or_default();
+    if service_type == Some("LoadBalancer") {
+        annotations.extend(md.spec.service_annotations.clone().unwrap_or_default());
+    }
+    (!annotations.is_empty()).then_some(annotations)
+}
+
+pub fn serving_port(variant: &ModelVariant) -> i32 {
+    variant.port.unwrap_or(SERVING_PORT)
+}
+
+/// The variant serving `role`; a Service for a shadow that is gone falls back to live.
+fn role_variant<'a>(spec: &'a ModelDeploymentSpec, role: &DeploymentType) -> &'a ModelVariant {
+    match role {
+        DeploymentType::Live => &spec.live,
+        DeploymentType::Shadow => spec.shadow.as_ref().unwrap_or(&spec.live),
+    }
+}
+
+/// The ports of the `models` containers, named after them.
+fn model_ports(variant: &ModelVariant) -> impl Iterator<Item = ContainerPort> + '_ {
+    variant.models.iter().flatten().map(|model| ContainerPort {
+        name: Some(model.name.clone()),
+        container_port: model.port,
+        ..Default::default()
+    })
+}
+
+/// Named ports survive a change of the container port number, so prefer the name when set.
+fn serving_port_ref(spec: &ModelDeploymentSpec, variant: &ModelVariant) -> IntOrString {
+    match &spec.port_name {
+        Some(name) => IntOrString::String(name.clone()),
+        None => IntOrString::Int(serving_port(variant)),
+    }
+}
+
+/// `probes.port` as a number or a name, else the serving port.
+pub fn probe_port(spec: &ModelDeploymentSpec, variant: &ModelVariant) -> IntOrString {
+    match spec.probes.as_ref().and_then(|p| p.port.as_deref()) {
+        Some(port) => port
+            .parse()
+            .map_or_else(|_| IntOrString::String(port.into()), IntOrString::Int),
+        None => serving_port_ref(spec, variant),
+    }
+}
+
+/// The serving port followed by any extra ports. Once there is more than one port, the
+/// `models` ones included, every port needs a name, so the serving port falls back to `http`.
+pub fn container_ports(spec: &ModelDeploymentSpec, variant: &ModelVariant) -> Vec<ContainerPort> {
+    let extras = spec.extra_ports.as_deref().unwrap_or_default();
+    let several = !extras.is_empty() || variant.models.as_ref().is_some_and(|m| !m.is_empty());
+    let serving_name = spec
+        .port_name
+        .clone()
+        .or_else(|| several.then(|| "http".into()));
+
+    std::iter::once(ContainerPort {
+        name: serving_name,
+        container_port: serving_port(variant),
+        host_port: spec.host_port,
+        ..Default::default()
+    })
+    .chain(extras.iter().map(|p| ContainerPort {
+        name: Some(p.name.clone()),
+        container_port: p.container_port,
+        host_port: p.host_port,
+        ..Default::default()
+    }))
+    .collect()
+}
+
+fn resource_requirements(res: &ResourceSpec) -> ResourceRequirements {
+    fn to_map(limits: &Option<ResourceLimits>) -> Option<BTreeMap<String, Quantity>> {
+        let limits = limits.as_ref()?;
+        let mut map = BTreeMap::new();
+        if let Some(cpu) = &limits.cpu {
+            map.insert("cpu".into(), Quantity(cpu.clone()));
+        }
+        if let Some(memory) = &limits.memory {
+            map.insert("memory".into(), Quantity(memory.clone()));
+        }
+        (!map.is_empty()).then_some(map)
+    }
+
+    ResourceRequirements {
+        limits: to_map(&res.limits),
+        requests: to_map(&res.requests),
+        ..Default::default()
+    }
+}
+
+fn http_probe(path: &str, port: IntOrString) -> Probe {
+    Probe {
+        http_get: Some(HTTPGetAction {
+            path: Some(path.into()),
+            port,
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// How often the `warmupSeconds` startup probe checks the server.
+const WARMUP_PERIOD: u32 = 10;
+
+fn startup_probe(probes: &ProbeSpec, port: IntOrString) -> Option<Probe> {
+    let warmup = probes.warmup_seconds?;
+    Some(Probe {
+        period_seconds: Some(WARMUP_PERIOD as i32),
+        failure_threshold: Some(warmup.div_ceil(WARMUP_PERIOD) as i32),
+        ..http_probe(&probes.liveness_path, port)
+    })
+}
+
+#[tracing::instrument(
+    skip_all,
+    fields(namespace = %md.namespace().unwrap_or_default(), name = %md.name_any())
+)]
 pub async fn reconsile(md: Arc<ModelDeployment>, ctx: Arc<Ctx>) -> Result<Action, Error> {
+    let instance = assigned_instance(&md);
+    if instance != ctx.config.instance {
+        tracing::debug!(
+            "{} is assigned to operator instance {}, not {}; skipping",
+            md.name_any(),
+            instance,
+            ctx.config.instance
+        );
+        return Ok(Action::await_change());
+    }
+
+    let uid = md.uid().unwrap_or_default();
+    let Some(guard) = ctx.locks.try_acquire(&uid) else {
+        tracing::debug!(
+            "{} is busy; coalescing into a later reconcile",
+            md.name_any()
+        );
+        return Ok(Action::requeue(LOCK_RETRY));
+    };
+
+    let key = format!(
+        "{}/{}",
+        md.namespace().unwrap_or_else(|| "default".into()),
+        md.name_any()
+    );
+    let (started_at, clock) = (Utc::now(), Instant::now());
+    let limit = ctx.config.reconcile_timeout;
+    let result = match tokio::time::timeout(limit, reconsile_inner(md.clone(), ctx.clone())).await {
+        Ok(result) => result,
+        Err(_) => {
+            tracing::warn!("Reconcile timed out after {:?}", limit);
+            // the timeout is what gets retried and recorded, whether or not the event went out
+            if let Err(e) = emit_event(
+                &ctx,
+                &*md,
+                "ReconcileTimedOut",
+                &format!("Reconcile did not finish within {:?}; retrying.", limit),
+                EventType::Warning,
+            )
+            .await
+            {
+                tracing::warn!("Could not record the timeout event: {}", e);
+            }
+            Err(Error::Timeout(limit))
+        }
+    };
+    // released first, or the lock would still be held and could not be forgotten
+    drop(guard);
+    // the final pass of a deletion leaves nothing to look back on
+    if is_deleting(&md) && matches!(&result, Ok(action) if *action == Action::await_change()) {
+        ctx.forget(&*md);
+    } else {
+        ctx.history
+            .record(&key, started_at, clock.elapsed(), &result);
+    }
+    result
+}
+
+async fn reconsile_inner(md: Arc<ModelDeployment>, ctx: Arc<Ctx>) -> Result<Action, Error> {
+    let mut md = ctx.defaults.read().unwrap().apply(&md);
+    if let Some(replicas) = scaler_replicas(&md) {
+        md.spec.live.manage_replicas = true;
+        md.spec.replicas = Some(replicas);
+    }
+    let capped = ctx
+        .config
+        .max_replicas_cap
+        .map(|cap| (cap, clamp_replicas(&mut md.spec, cap)));
+    let old_server = strip_traffic_distribution(ctx.server_version.as_ref(), &mut md.spec);
+    let hpa = external_hpa(&ctx.client, &md).await?;
+    if let Some(hpa_name) = &hpa {
+        md.spec.live.manage_replicas = false;
+        if md.status.as_ref().and_then(|s| s.external_hpa.as_ref()) != Some(hpa_name) {
+            tracing::info!(
+                "{} is scaled by HorizontalPodAutoscaler {}; leaving live replicas to it",
+                md.name_any(),
+                hpa_name
+            );
+        }
+    }
+    let idle = idle::observe(&md, Utc::now()).await;
+    let was_zero = md
+        .status
+        .as_ref()
+        .and_then(|s| s.idle.as_ref())
+        .is_some_and(|i| i.scaled_to_zero);
+    if let Some(state) = &idle {
+        if state.scaled_to_zero {
+            md.spec.replicas = Some(0);
+        }
+        md.status.get_or_insert_with(Default::default).idle = Some(state.clone());
+    }
+    let md = Arc::new(md);
     let ns = md.namespace().unwrap_or_else(|| "default".into());
-    let base_name = md.name_any();
+    let name = md.name_any();
+    let base_name = child_base_name(&md);
+    let child_ns = child_namespace(&md);
     let spec = md.spec();
+    let key = format!("{}/{}", ns, name);
+
+    if let Some(remaining) = ctx.failures.backoff_remaining(&key, md.meta().generation) {
+        tracing::debug!("{} failed recently; retrying in {:?}", key, remaining);
+        return Ok(Action::requeue(remaining));
+    }
 
-    tracing::info!("Reconciling ModelDeployment {}/{}", ns, base_name);
+    tracing::info!("Reconciling ModelDeployment {}/{}", ns, name);
     let mut changed = false;
 
+    if let Some(requested) = pending_force_sync(&md) {
+        tracing::info!(
+            "Force-sync {} requested; re-applying all children",
+            requested
+        );
+    }
+
+    // lasting problems, kept as conditions so their warnings go out once rather than per pass
+    let mut warnings = Vec::new();
+    if let Some((cap, fields)) = capped.filter(|(_, fields)| !fields.is_empty()) {
+        let message = format!(
+            "Clamped {} to the operator replica cap of {}",
+            fields.join(", "),
+            cap
+        );
+        warnings.push(warn_once(&ctx, &md, "ReplicasCapped", "OperatorCap", message).await?);
+    }
+
+    if let Some(version) = old_server {
+        let message = format!(
+            "API server {} predates Service trafficDistribution (1.{}+); leaving it unset",
+            version, TRAFFIC_DISTRIBUTION_MINOR
+        );
+        warnings.push(
+            warn_once(
+                &ctx,
+                &md,
+                "TrafficDistributionUnsupported",
+                "ApiServerTooOld",
+                message,
+            )
+            .await?,
+        );
+    }
+
     if is_deleting(&md) {
         if has_finalizer(&md, FINALIZER) {
             emit_event(
@@ -87,6 +556,29 @@ pub async fn reconsile(md: Arc<ModelDeployment>, ctx: Arc<Ctx>) -> Result<Action
                 EventType::Normal,
             )
             .await?;
+            if let Some(remaining) = drain_before_delete(&ctx, &md, &ns, &base_name).await? {
+                return Ok(Action::requeue(remaining));
+            }
+            with_event(
+                &ctx,
+                &*md,
+                "Released adopted children",
+                "ChildrenDisowned",
+                "DisownFailed",
+                disown_adopted(&ctx.client, &md, &child_ns),
+            )
+            .await?;
+            if cross_namespace(&md) {
+                with_event(
+                    &ctx,
+                    &*md,
+                    "Deleted children in the target namespace",
+                    "ChildrenDeleted",
+                    "ChildCleanupFailed",
+                    delete_cross_namespace(&ctx.client, &md, &child_ns),
+                )
+                .await?;
+            }
             let _ = with_event(
                 &ctx,
                 &*md,
@@ -100,6 +592,107 @@ pub async fn reconsile(md: Arc<ModelDeployment>, ctx: Arc<Ctx>) -> Result<Action
         return Ok(Action::await_change());
     }
 
+    let validation = match validate(&md, &ctx.config)
+        .and_then(|()| check_registries(&md, &ctx.config.registry_allowlist))
+    {
+        Ok(()) if cross_namespace(&md) => preflight::check_namespace(&ctx.client, &child_ns)
+            .await
+            .map_err(|e| Error::Validation(format!("targetNamespace {}: {}", child_ns, e))),
+        validation => validation,
+    };
+    if let Err(e) = validation {
+        emit_event(
+            &ctx,
+            &*md,
+            "ValidationFailed",
+            &e.to_string(),
+            EventType::Warning,
+        )
+        .await?;
+        let mut status = md.status.clone().unwrap_or_default();
+        status.set_condition(accepted_condition(Err(&e), has_finalizer(&md, FINALIZER)));
+        update_status(&ctx, &md, &ns, &status).await?;
+        return Err(e);
+    }
+
+    if ctx.config.features.image_check {
+        check_images(&ctx, &md).await?;
+    }
+    check_spread(&ctx, &md).await?;
+
+    if let Some(remaining) = spec
+        .maintenance_window
+        .as_ref()
+        .and_then(|window| maintenance::remaining(window, Utc::now()))
+    {
+        tracing::info!(
+            "{} is in its maintenance window for another {:?}",
+            key,
+            remaining
+        );
+        // the one child the freeze tightens rather than leaves alone
+        if spec.disruption_budget.is_some() {
+            ensure_pdb(&ctx, &md, true).await?;
+        }
+        return refresh_status_only(&ctx, &md, &ns, &base_name, remaining).await;
+    }
+
+    if let Some((pending, remaining)) = rollout_debounce(&md) {
+        tracing::info!(
+            "{} changed recently; applying it after {:?} without further changes",
+            key,
+            remaining
+        );
+        let message = format!(
+            "Generation {} is applied once the spec has been unchanged for {}s.",
+            md.meta().generation.unwrap_or_default(),
+            spec.rollout_debounce_seconds.unwrap_or_default()
+        );
+        refresh_observed(&ctx, &md, &ns, &base_name, |status| {
+            status.pending_change = Some(pending);
+            status.set_condition(Condition {
+                r#type: "ChangesPending".into(),
+                status: "True".into(),
+                reason: Some("RolloutDebounce".into()),
+                message: Some(message),
+                ..Default::default()
+            });
+        })
+        .await?;
+        return Ok(Action::requeue(remaining));
+    }
+
+    let adopting = md.annotations().get(ADOPT_FROM_ANN);
+    if let Some(old) = adopting {
+        let moved = migrate::adopt_children(&ctx.client, &md, &ns, old).await?;
+        if moved > 0 {
+            emit_event(
+                &ctx,
+                &*md,
+                "ChildrenAdopted",
+                &format!("Took over {} children of ModelDeployment {}", moved, old),
+                EventType::Normal,
+            )
+            .await?;
+        }
+    }
+
+    if settled(&ctx, &md, &key) {
+        tracing::debug!(
+            "{} unchanged since its last full sync; refreshing status only",
+            key
+        );
+        let held = refresh_observed(&ctx, &md, &ns, &base_name, |status| {
+            status.external_hpa = hpa;
+        })
+        .await?;
+        return Ok(Action::requeue(if held {
+            STALE_READ_RECHECK
+        } else {
+            resync_interval(&ctx, last_phase(&md))
+        }));
+    }
+
     let out = with_event(
         &ctx,
         &*md,
@@ -111,16 +704,34 @@ pub async fn reconsile(md: Arc<ModelDeployment>, ctx: Arc<Ctx>) -> Result<Action
     .await?;
     changed |= out != Outcome::NoOp;
 
-    let svc_api: Api<Service> = Api::namespaced(ctx.client.clone(), &ns);
-    let out = with_event(
-        &ctx,
-        &*md,
-        "Created live svc for ModelDeployment",
-        "LiveSvcCreated",
-        "LiveSvcFailed",
-        ensure_service(&svc_api, &md, &base_name, DeploymentType::Live),
-    )
-    .await?;
+    let mut managed = Vec::new();
+    let svc_api: Api<Service> = Api::namespaced(ctx.client.clone(), &child_ns);
+    let out = if live_service_managed(spec) {
+        let out = with_event(
+            &ctx,
+            &*md,
+            "Created live svc for ModelDeployment",
+            "LiveSvcCreated",
+            "LiveSvcFailed",
+            ensure_service(&ctx, &svc_api, &md, &base_name, DeploymentType::Live),
+        )
+        .await?;
+        managed.push(ManagedResource::new(
+            "Service",
+            spec.service_name(&base_name, "live"),
+        ));
+        out
+    } else {
+        with_event(
+            &ctx,
+            &*md,
+            "Removed live svc in favour of an existing Service",
+            "LiveSvcDeleted",
+            "LiveSvcDeleteFailed",
+            release_live_service(&svc_api, &md, &base_name),
+        )
+        .await?
+    };
     changed |= out != Outcome::NoOp;
 
     if spec.shadow.is_some() {
@@ -130,215 +741,3063 @@ pub async fn reconsile(md: Arc<ModelDeployment>, ctx: Arc<Ctx>) -> Result<Action
             "Created shadow svc for ModelDeployment",
             "ShadowSvcCreated",
             "ShadowSvcFailed",
-            ensure_service(&svc_api, &md, &base_name, DeploymentType::Shadow),
+            ensure_service(&ctx, &svc_api, &md, &base_name, DeploymentType::Shadow),
         )
         .await?;
         changed |= out != Outcome::NoOp;
+        managed.push(ManagedResource::new(
+            "Service",
+            spec.service_name(&base_name, "shadow"),
+        ));
     }
 
-    let deployment_api: Api<Deployment> = Api::namespaced(ctx.client.clone(), &ns);
     let out = with_event(
         &ctx,
         &*md,
-        "Created live Deployment",
-        "LiveDeploymentCreated",
-        "LiveDeploymentFailed",
-        ensure_deployment(
-            &deployment_api,
-            &md,
-            &format!("{}-live", base_name),
-            &base_name,
-            &spec.live.image,
-            spec.live.replicas,
-            DeploymentType::Live,
-        ),
+        "Updated additional Services for ModelDeployment",
+        "ServicesUpdated",
+        "ServicesFailed",
+        ensure_extra_services(&ctx, &svc_api, &md, &base_name),
     )
     .await?;
     changed |= out != Outcome::NoOp;
+    for config in spec.services.iter().flatten() {
+        managed.push(ManagedResource::new(
+            "Service",
+            extra_service_name(spec, &base_name, config),
+        ));
+    }
 
-    if let Some(shadow) = &spec.shadow {
+    let out = if spec.alias_service {
         let out = with_event(
             &ctx,
             &*md,
-            "Created shadow Deployment",
-            "ShadowDeploymentCreated",
-            "ShadowDeploymentFailed",
+            "Applied alias Service",
+            "AliasServiceApplied",
+            "AliasServiceFailed",
+            ensure_alias_service(&ctx, &svc_api, &md, &base_name, &child_ns),
+        )
+        .await?;
+        managed.push(ManagedResource::new("Service", base_name.clone()));
+        out
+    } else {
+        with_event(
+            &ctx,
+            &*md,
+            "Removed alias Service",
+            "AliasServiceDeleted",
+            "AliasServiceDeleteFailed",
+            delete_owned(&svc_api, &md, &base_name),
+        )
+        .await?
+    };
+    changed |= out != Outcome::NoOp;
+
+    warnings.extend(warn_missing_secrets(&ctx, &md, &child_ns).await?);
+    warnings.extend(warn_host_port_collisions(&ctx, &md).await?);
+
+    let cm_api: Api<ConfigMap> = Api::namespaced(ctx.client.clone(), &child_ns);
+    let cm_name = inline_config_name(&base_name);
+    let out = match &spec.inline_config {
+        Some(data) => {
+            let out = with_event(
+                &ctx,
+                &*md,
+                "Created inline config ConfigMap",
+                "ConfigMapCreated",
+                "ConfigMapFailed",
+                ensure_config_map(&ctx, &cm_api, &md, &base_name, data),
+            )
+            .await?;
+            managed.push(ManagedResource::new("ConfigMap", cm_name.clone()));
+            out
+        }
+        None => {
+            with_event(
+                &ctx,
+                &*md,
+                "Removed inline config ConfigMap",
+                "ConfigMapDeleted",
+                "ConfigMapDeleteFailed",
+                delete_owned(&cm_api, &md, &cm_name),
+            )
+            .await?
+        }
+    };
+    changed |= out != Outcome::NoOp;
+
+    let deployment_api: Api<Deployment> = Api::namespaced(ctx.client.clone(), &child_ns);
+    let mut image_change = md.status.as_ref().and_then(|s| s.last_image_change.clone());
+    let previous_image =
+        current_release(&deployment_api, &spec.deployment_name(&base_name, "live")).await?;
+    let mut live_changed_at = None;
+    // only the image is held back; the rest of the live spec still goes through
+    let mut live = spec.live.clone();
+    if let Some((running, condition)) = blocked_downgrade(&ctx, &md, &base_name).await? {
+        live.image = running;
+        warnings.push(condition);
+    }
+    let held = held_deployment(&deployment_api, spec, &base_name).await?;
+    if let Some(role) = &held {
+        tracing::info!("{} rolloutOrder holds the {} Deployment back", key, role);
+    }
+    if held != Some(DeploymentType::Live) {
+        let out = with_event(
+            &ctx,
+            &*md,
+            "Created live Deployment",
+            "LiveDeploymentCreated",
+            "LiveDeploymentFailed",
             ensure_deployment(
+                &ctx,
                 &deployment_api,
                 &md,
-                &format!("{}-shadow", base_name),
                 &base_name,
-                &shadow.image,
-                shadow.replicas,
-                DeploymentType::Shadow,
+                &live,
+                effective_replicas(spec, &DeploymentType::Live),
+                DeploymentType::Live,
             ),
         )
         .await?;
         changed |= out != Outcome::NoOp;
+        if let Some(change) = note_image_change(
+            &ctx,
+            &md,
+            DeploymentType::Live,
+            out,
+            previous_image,
+            &live.release(),
+        )
+        .await?
+        {
+            live_changed_at = Some(change.at.clone());
+            image_change = Some(change);
+        }
+    }
+    managed.push(ManagedResource::new(
+        "Deployment",
+        spec.deployment_name(&base_name, "live"),
+    ));
+
+    let hpa_api: Api<HorizontalPodAutoscaler> = Api::namespaced(ctx.client.clone(), &child_ns);
+    let hpa_name = spec.deployment_name(&base_name, "live");
+    let out = match &spec.autoscaling {
+        Some(autoscaling) if hpa_enabled(spec) => {
+            let out = with_event(
+                &ctx,
+                &*md,
+                "Created live HorizontalPodAutoscaler",
+                "HpaCreated",
+                "HpaFailed",
+                ensure_hpa(&ctx, &hpa_api, &md, &hpa_name, autoscaling),
+            )
+            .await?;
+            managed.push(ManagedResource::new(
+                "HorizontalPodAutoscaler",
+                hpa_name.clone(),
+            ));
+            out
+        }
+        _ => {
+            with_event(
+                &ctx,
+                &*md,
+                "Removed live HorizontalPodAutoscaler",
+                "HpaDeleted",
+                "HpaDeleteFailed",
+                delete_owned(&hpa_api, &md, &hpa_name),
+            )
+            .await?
+        }
+    };
+    changed |= out != Outcome::NoOp;
+
+    let np_api: Api<NetworkPolicy> = Api::namespaced(ctx.client.clone(), &child_ns);
+    let np_name = format!("{}-netpol", base_name);
+    let out = match &spec.network_policy {
+        Some(policy) => {
+            let out = with_event(
+                &ctx,
+                &*md,
+                "Created NetworkPolicy",
+                "NetworkPolicyCreated",
+                "NetworkPolicyFailed",
+                ensure_network_policy(&ctx, &np_api, &md, &np_name, &base_name, policy),
+            )
+            .await?;
+            managed.push(ManagedResource::new("NetworkPolicy", np_name.clone()));
+            out
+        }
+        None => {
+            with_event(
+                &ctx,
+                &*md,
+                "Removed NetworkPolicy",
+                "NetworkPolicyDeleted",
+                "NetworkPolicyDeleteFailed",
+                delete_owned(&np_api, &md, &np_name),
+            )
+            .await?
+        }
+    };
+    changed |= out != Outcome::NoOp;
+
+    changed |= ensure_pdb(&ctx, &md, false).await? != Outcome::NoOp;
+    if spec.disruption_budget.is_some() {
+        managed.push(ManagedResource::new(
+            "PodDisruptionBudget",
+            pdb_name(&base_name),
+        ));
     }
 
-    if spec.traffic_mirror {
-        let ts_api: Api<TraefikService> = Api::namespaced(ctx.client.clone(), &ns);
+    if let Some(shadow) = spec
+        .shadow
+        .as_ref()
+        .filter(|_| held != Some(DeploymentType::Shadow))
+    {
+        let previous_image =
+            current_release(&deployment_api, &spec.deployment_name(&base_name, "shadow")).await?;
         let out = with_event(
             &ctx,
             &*md,
-            "Created Traefik Service",
-            "TraefikServiceCreated",
-            "TraefikServiceFailed",
-            ensure_traefik_service(&ts_api, &md, &base_name, &ns),
+            "Created shadow Deployment",
+            "ShadowDeploymentCreated",
+            "ShadowDeploymentFailed",
+            ensure_deployment(
+                &ctx,
+                &deployment_api,
+                &md,
+                &base_name,
+                shadow,
+                shadow_replicas(&md),
+                DeploymentType::Shadow,
+            ),
         )
         .await?;
         changed |= out != Outcome::NoOp;
-
-        let ir_api: Api<IngressRoute> = Api::namespaced(ctx.client.clone(), &ns);
-        let out = with_event(
+        managed.push(ManagedResource::new(
+            "Deployment",
+            spec.deployment_name(&base_name, "shadow"),
+        ));
+        if let Some(change) = note_image_change(
             &ctx,
-            &*md,
-            "Created Ingress Route",
-            "IngressRouteCreated",
-            "IngressRouteFailed",
-            ensure_ingress_route(&ir_api, &md, &base_name, &ns),
+            &md,
+            DeploymentType::Shadow,
+            out,
+            previous_image,
+            &shadow.release(),
         )
-        .await?;
-        changed |= out != Outcome::NoOp;
+        .await?
+        {
+            image_change = Some(change);
+        }
     }
 
-    let (live_status, shadow_status) = get_child_status(&ctx.client, &base_name, &ns).await?;
-    let model_deployment_status =
-        compute_model_deployment_status(spec, &live_status, &shadow_status).await;
-    update_status(&ctx.client, &md, &ns, &model_deployment_status).await?;
-
-    if changed {
+    let mut extra_resources = Vec::new();
+    let out = with_event(
+        &ctx,
+        &*md,
+        "Applied extra resources",
+        "ExtraResourcesApplied",
+        "ExtraResourcesFailed",
+        async {
+            let (out, applied) = ensure_extra_resources(&ctx, &md, &child_ns).await?;
+            extra_resources = applied;
+            Ok::<_, Error>(out)
+        },
+    )
+    .await?;
+    changed |= out != Outcome::NoOp;
+    managed.extend(
+        extra_resources
+            .iter()
+            .map(|r| ManagedResource::new(&r.kind, r.name.clone())),
+    );
+
+    let ChildReads {
+        live: live_status,
+        shadow: shadow_status,
+        confident,
+    } = get_child_status(&ctx.client, spec, &base_name, &child_ns).await?;
+
+    // the status reports it as Degraded for as long as it lasts, so warn when that starts
+    let quota: Vec<String> = [("live", &live_status), ("shadow", &shadow_status)]
+        .into_iter()
+        .filter_map(|(role, child)| {
+            let message = child.as_ref()?.quota_exceeded.as_ref()?;
+            Some(format!("{}: {}", role, message))
+        })
+        .collect();
+    if !quota.is_empty() && !reported(&md, "Degraded", "QuotaExceeded") {
+        emit_event(
+            &ctx,
+            &*md,
+            "QuotaExceeded",
+            &format!(
+                "Replicas are capped by a ResourceQuota: {}",
+                quota.join("; ")
+            ),
+            EventType::Warning,
+        )
+        .await?;
+    }
+
+    let canary = match &spec.shadow {
+        Some(shadow) if spec.is_canary() => {
+            let steps = spec
+                .canary_steps
+                .clone()
+                .filter(|steps| !steps.is_empty())
+                .unwrap_or_else(canary::default_steps);
+            let available = shadow_status
+                .as_ref()
+                .and_then(|s| s.available_replicas)
+                .unwrap_or(0);
+            let target = shadow_replicas(&md).unwrap_or(shadow.replicas);
+            let canary_available = target > 0 && available >= target;
+            let current = md.status.as_ref().and_then(|s| s.canary.as_ref());
+
+            if spec.shadow_standby() {
+                Some(canary::standby(&shadow.release()))
+            } else if md
+                .annotations()
+                .get(canary::ABORT_ANNOTATION)
+                .is_some_and(|v| v == "true")
+            {
+                if !current.is_some_and(|c| c.aborted) {
+                    emit_event(
+                        &ctx,
+                        &*md,
+                        "CanaryAborted",
+                        "Canary aborted by annotation; all traffic routed to live.",
+                        EventType::Warning,
+                    )
+                    .await?;
+                }
+                Some(canary::abort(&shadow.release(), current, false))
+            } else if current.is_some_and(|c| c.analysis_failed && c.image == shadow.release()) {
+                Some(canary::abort(&shadow.release(), current, true))
+            } else {
+                let features = &ctx.config.features;
+                let now = Utc::now();
+                // what the analysis of this same canary has seen so far
+                let last =
+                    current.filter(|c| c.image == shadow.release() && !c.aborted && !c.standby);
+                let analysis = spec
+                    .canary_analysis
+                    .as_ref()
+                    .filter(|_| canary_available && features.canary_analysis);
+                let (verdict, queried) = match analysis {
+                    Some(analysis) => (
+                        analysis::evaluate(analysis, last, now).await,
+                        analysis::due(analysis, last, now),
+                    ),
+                    None => (Verdict::Passed, false),
+                };
+                let failures =
+                    analysis::failures(last.map_or(0, |c| c.analysis_failures), &verdict);
+                let exhausted = analysis.is_some_and(|a| analysis::exhausted(a, failures));
+                match verdict {
+                    Verdict::Failed(value) if features.auto_rollback && exhausted => {
+                        let threshold = spec.canary_analysis.as_ref().map_or(0.0, |a| a.threshold);
+                        emit_event(
+                            &ctx,
+                            &*md,
+                            "CanaryAnalysisFailed",
+                            &format!(
+                                "Canary analysis returned {} (threshold {}) {} times in a row; all traffic routed to live.",
+                                value, threshold, failures
+                            ),
+                            EventType::Warning,
+                        )
+                        .await?;
+                        Some(canary::abort(&shadow.release(), current, true))
+                    }
+                    verdict => {
+                        match &verdict {
+                            Verdict::Inconclusive(reason) => tracing::warn!(
+                                "Canary analysis for {}/{} inconclusive, holding step: {}",
+                                ns,
+                                md.name_any(),
+                                reason
+                            ),
+                            Verdict::Failed(value) => {
+                                let held = if features.auto_rollback {
+                                    "the step is held until it fails again"
+                                } else {
+                                    "auto-rollback is disabled, so the step is held"
+                                };
+                                emit_event(
+                                    &ctx,
+                                    &*md,
+                                    "CanaryAnalysisFailed",
+                                    &format!(
+                                        "Canary analysis returned {} ({} in a row); {}.",
+                                        value, failures, held
+                                    ),
+                                    EventType::Warning,
+                                )
+                                .await?
+                            }
+                            Verdict::Passed | Verdict::Waiting => {}
+                        }
+                        // without auto-promotion a due step is held like an unpassed analysis
+                        let mut decision = canary::progress(
+                            &steps,
+                            &shadow.release(),
+                            current,
+                            canary_available,
+                            available > 0,
+                            matches!(verdict, Verdict::Passed) && features.auto_promotion,
+                            now,
+                        );
+                        decision.status.analysis_failures = failures;
+                        if queried {
+                            decision.status.analyzed_at = Some(now.to_rfc3339());
+                        }
+                        Some(decision)
+                    }
+                }
+            }
+        }
+        _ => None,
+    };
+
+    if let Some(decision) = canary.as_ref().filter(|d| d.advanced) {
+        emit_event(
+            &ctx,
+            &*md,
+            "CanaryStepAdvanced",
+            &format!(
+                "Canary advanced to step {} at {}% traffic",
+                decision.status.step + 1,
+                decision.status.weight
+            ),
+            EventType::Normal,
+        )
+        .await?;
+    }
+
+    // a canary without a single available pod would only serve errors, so `canary_down` sends
+    // its weight to live until it recovers, while its step (and so the weight it returns to)
+    // is kept. `failed_over` is narrower: only a canary that has been up counts as failed over
+    // (and degraded); before that it is still starting, and gets no traffic either way
+    let canary_down = canary.as_ref().is_some_and(|d| d.status.weight > 0)
+        && shadow_status
+            .as_ref()
+            .and_then(|s| s.available_replicas)
+            .unwrap_or(0)
+            == 0;
+    let failed_over = canary_down && canary.as_ref().is_some_and(|d| d.status.started);
+    let was_failed_over = md
+        .status
+        .as_ref()
+        .and_then(|s| s.conditions.as_ref())
+        .into_iter()
+        .flatten()
+        .any(|c| c.r#type == "Degraded" && c.reason.as_deref() == Some("CanaryFailedOver"));
+    if let Some(decision) = canary.as_ref().filter(|_| failed_over != was_failed_over) {
+        let (reason, message, type_) = if failed_over {
+            (
+                "CanaryFailedOver",
+                format!(
+                    "Canary has no available pods; its {}% weight is routed to live until it recovers.",
+                    decision.status.weight
+                ),
+                EventType::Warning,
+            )
+        } else {
+            (
+                "CanaryRecovered",
+                format!(
+                    "Canary is available again; restored its {}% weight.",
+                    decision.status.weight
+                ),
+                EventType::Normal,
+            )
+        };
+        emit_event(&ctx, &*md, reason, &message, type_).await?;
+    }
+
+    let routed = spec.traffic_mirror || canary.is_some();
+    let routes = spec.effective_routes();
+
+    let standby = spec.shadow_standby();
+    let hold_mirror = canary.is_none()
+        && !standby
+        && spec.wait_for_shadow_ready
+        && !shadow_ready(spec, &shadow_status)
+        && !mirror_enabled(&ctx.client, &child_ns, &base_name).await?;
+    if hold_mirror {
+        tracing::info!("{} shadow is not ready yet; holding the mirror back", key);
+    }
+    let now = Utc::now();
+    let off_schedule = canary.is_none()
+        && spec
+            .mirror_window
+            .as_ref()
+            .is_some_and(|window| maintenance::remaining(window, now).is_none());
+
+    // keep the workloads reconciled on clusters without Traefik
+    let routing_unavailable = routed && !traefik_installed(&ctx.client).await?;
+    if routing_unavailable && !reported(&md, "RoutingReady", "RoutingUnavailable") {
+        emit_event(
+            &ctx,
+            &*md,
+            "RoutingUnavailable",
+            "Traefik CRDs are not installed; skipping TraefikService and IngressRoutes.",
+            EventType::Warning,
+        )
+        .await?;
+    }
+    if routed && !routing_unavailable {
+        let weight = canary
+            .as_ref()
+            .map(|d| if canary_down { 0 } else { d.status.weight });
+        changed |= ensure_routing(
+            &ctx,
+            &md,
+            &base_name,
+            &child_ns,
+            weight,
+            &routes,
+            !hold_mirror && !standby && !off_schedule,
+        )
+        .await?;
+        managed.push(ManagedResource::new("TraefikService", base_name.clone()));
+        managed.extend(routes.iter().map(|r| {
+            ManagedResource::new(
+                "IngressRoute",
+                ingress_route_name(&base_name, &r.entry_point),
+            )
+        }));
+    }
+
+    let mut model_deployment_status =
+        compute_model_deployment_status(spec, &live_status, &shadow_status, Ok(()), true).await;
+    let held_phase =
+        hold_uncertain_phase(&mut model_deployment_status, md.status.as_ref(), confident);
+    if let (Some(order), Some(_)) = (&spec.rollout_order, &spec.shadow) {
+        model_deployment_status.set_condition(Condition {
+            r#type: "RolloutOrdered".into(),
+            status: if held.is_some() { "True" } else { "False" }.into(),
+            reason: Some(
+                match held {
+                    Some(DeploymentType::Live) => "WaitingForShadow",
+                    Some(DeploymentType::Shadow) => "WaitingForLive",
+                    None => "NotWaiting",
+                }
+                .into(),
+            ),
+            message: Some(match &held {
+                Some(role) => format!(
+                    "{}: the {} Deployment is held until the other one is available.",
+                    order, role
+                ),
+                None => format!("{}: nothing is held back.", order),
+            }),
+            ..Default::default()
+        });
+    }
+    track_rollout(
+        &md,
+        &mut model_deployment_status,
+        live_changed_at,
+        confident,
+    );
+    if let Some(decision) = &canary {
+        if decision.degraded {
+            model_deployment_status.set_condition(Condition {
+                r#type: "Degraded".into(),
+                status: "True".into(),
+                reason: Some("CanaryNotAvailable".into()),
+                message: Some(format!(
+                    "Canary held at step {} ({}% traffic) until it is fully available.",
+                    decision.status.step + 1,
+                    decision.status.weight
+                )),
+                ..Default::default()
+            });
+        }
+        if failed_over {
+            model_deployment_status.set_condition(Condition {
+                r#type: "Degraded".into(),
+                status: "True".into(),
+                reason: Some("CanaryFailedOver".into()),
+                message: Some(format!(
+                    "Canary has no available pods; its {}% weight is routed to live until it recovers.",
+                    decision.status.weight
+                )),
+                ..Default::default()
+            });
+        }
+        if decision.status.aborted {
+            model_deployment_status.set_condition(Condition {
+                r#type: "CanaryAborted".into(),
+                status: "True".into(),
+                reason: Some(
+                    if decision.status.analysis_failed {
+                        "AnalysisFailed"
+                    } else {
+                        "AbortAnnotation"
+                    }
+                    .into(),
+                ),
+                message: Some(if decision.status.analysis_failed {
+                    "Canary analysis failed; roll out a new shadow image to start a fresh canary."
+                        .into()
+                } else {
+                    format!(
+                        "Remove the {} annotation to start a fresh canary.",
+                        canary::ABORT_ANNOTATION
+                    )
+                }),
+                ..Default::default()
+            });
+        }
+        model_deployment_status.canary = Some(decision.status.clone());
+    }
+    if hold_mirror {
+        model_deployment_status.set_condition(Condition {
+            r#type: "MirrorPending".into(),
+            status: "True".into(),
+            reason: Some("ShadowNotReady".into()),
+            message: Some("Mirroring starts once the shadow is fully available.".into()),
+            ..Default::default()
+        });
+    } else if off_schedule {
+        model_deployment_status.set_condition(Condition {
+            r#type: "MirrorPending".into(),
+            status: "True".into(),
+            reason: Some("OutsideMirrorWindow".into()),
+            message: Some("Mirroring is only on inside mirrorWindow.".into()),
+            ..Default::default()
+        });
+    }
+    if routing_unavailable {
+        for r#type in ["Ready", "RoutingReady"] {
+            model_deployment_status.set_condition(Condition {
+                r#type: r#type.into(),
+                status: "False".into(),
+                reason: Some("RoutingUnavailable".into()),
+                message: Some(
+                    "Routing was requested but the Traefik CRDs are not installed.".into(),
+                ),
+                ..Default::default()
+            });
+        }
+    } else if routed {
+        model_deployment_status.set_condition(
+            routing_condition(
+                &ctx.client,
+                &child_ns,
+                &base_name,
+                &routes,
+                canary.is_some(),
+            )
+            .await?,
+        );
+    }
+    let hosts = match &spec.match_rule {
+        Some(rule) => rule_hosts(rule),
+        None => vec![format!(
+            "{}.{}",
+            base_name,
+            spec.domain.as_deref().unwrap_or("local")
+        )],
+    };
+    // a matchRule without a Host() matcher has no address to advertise
+    model_deployment_status.endpoints =
+        (routed && !routing_unavailable && !hosts.is_empty()).then(|| {
+            routes
+                .iter()
+                .flat_map(|r| {
+                    let scheme = if r.tls.is_some() { "https" } else { "http" };
+                    hosts
+                        .iter()
+                        .map(move |host| format!("{}://{} ({})", scheme, host, r.entry_point))
+                })
+                .collect()
+        });
+    for condition in warnings {
+        model_deployment_status.set_condition(condition);
+    }
+    // every child was applied, so none of them is another ModelDeployment's
+    model_deployment_status.set_condition(Condition {
+        r#type: "NameConflict".into(),
+        status: "False".into(),
+        reason: Some("ChildrenOwned".into()),
+        message: Some("Every child is owned by this ModelDeployment.".into()),
+        ..Default::default()
+    });
+    model_deployment_status.consecutive_failures = Some(0);
+    model_deployment_status.last_force_sync = md.annotations().get(FORCE_SYNC_ANN).cloned();
+    model_deployment_status.observed_generation = md.meta().generation;
+    model_deployment_status.last_image_change = image_change;
+    model_deployment_status.last_reconcile_time = last_reconcile_time(&md);
+    model_deployment_status.extra_resources =
+        (!extra_resources.is_empty()).then_some(extra_resources);
+    let missing = missing_children(&ctx.client, &child_ns, &managed).await?;
+    model_deployment_status.set_condition(if missing.is_empty() {
+        Condition {
+            r#type: "Reconciling".into(),
+            status: "False".into(),
+            reason: Some("ChildrenApplied".into()),
+            message: Some("Every child of the last pass is in place.".into()),
+            ..Default::default()
+        }
+    } else {
+        tracing::warn!(
+            "{} is missing {}; driving it again",
+            key,
+            missing.join(", ")
+        );
+        Condition {
+            r#type: "Reconciling".into(),
+            status: "True".into(),
+            reason: Some("ChildrenMissing".into()),
+            message: Some(format!("Not yet in place: {}.", missing.join(", "))),
+            ..Default::default()
+        }
+    });
+    model_deployment_status.managed_resources = Some(managed);
+    model_deployment_status.external_hpa = hpa;
+    model_deployment_status.fallback_image = spec.fallback_image.clone();
+    model_deployment_status.observed_api_version = Some(observe_api_version(&ctx, &md).await?);
+    model_deployment_status.managed_by_version = Some(observe_operator_version(&ctx, &md).await?);
+    model_deployment_status.idle = idle.clone();
+    model_deployment_status.resource_recommendation =
+        rightsize::recommend(&ctx.client, &md, &child_ns, &base_name).await;
+    update_status(&ctx, &md, &ns, &model_deployment_status).await?;
+    // the children are ours, so the migration is done; only their prefix is left to remember
+    if let Some(old) = adopting {
+        let api: Api<ModelDeployment> = Api::namespaced(ctx.client.clone(), &ns);
+        let patch = json!({
+            "metadata": {"annotations": {ADOPT_FROM_ANN: null, CHILD_BASE_ANN: old}}
+        });
+        api.patch_metadata(&name, &PatchParams::default(), &Patch::Merge(&patch))
+            .await?;
+    }
+    ctx.failures.reset(&key);
+    // an incomplete pass must not let the next one skip the children
+    if missing.is_empty() {
+        ctx.syncs.record(&key, md.meta().generation);
+    }
+    metrics::CONSECUTIVE_FAILURES
+        .with_label_values(&[&ns, &name])
+        .set(0);
+
+    if changed {
+        ctx.history.mark_changed(&key);
+        emit_event(
+            &ctx,
+            &*md,
+            "Reconciled",
+            "Reconciliation completed",
+            EventType::Normal,
+        )
+        .await?;
+    }
+
+    if let Some(state) = idle.as_ref().filter(|i| i.scaled_to_zero != was_zero) {
+        let (reason, message) = if state.scaled_to_zero {
+            (
+                "ScaledToZero",
+                format!("Live idle since {}; scaled to zero", state.last_active),
+            )
+        } else {
+            ("Activated", "Traffic returned; scaled live back up".into())
+        };
+        emit_event(&ctx, &*md, reason, &message, EventType::Normal).await?;
+    }
+
+    tracing::info!("Reconsiliation completed.");
+
+    let requeue = if !missing.is_empty() {
+        INCOMPLETE_RECHECK
+    } else if held_phase {
+        STALE_READ_RECHECK
+    } else if hold_mirror {
+        MIRROR_RECHECK
+    } else if held.is_some() {
+        ORDER_RECHECK
+    } else {
+        resync_interval(&ctx, model_deployment_status.phase.as_deref())
+    };
+    // wake up to switch the mirror on or off on time
+    let requeue = spec
+        .mirror_window
+        .as_ref()
+        .filter(|_| spec.traffic_mirror)
+        .and_then(|window| maintenance::next_change(window, now))
+        .map_or(requeue, |change| change.min(requeue));
+    // tighten the PodDisruptionBudget as the maintenance window opens
+    let requeue = spec
+        .maintenance_window
+        .as_ref()
+        .filter(|_| spec.disruption_budget.is_some())
+        .and_then(|window| maintenance::next_change(window, now))
+        .map_or(requeue, |change| change.min(requeue));
+    // scale to zero as soon as the cooldown is over
+    let requeue = idle
+        .as_ref()
+        .and_then(|state| idle::remaining(&md, state, now))
+        .map_or(requeue, |left| left.min(requeue));
+    Ok(Action::requeue(
+        canary
+            .and_then(|d| d.requeue_after)
+            .map_or(requeue, |after| after.min(requeue)),
+    ))
+}
+
+/// The children this pass applied that can't be read back, because an apply was cut short or
+/// something deleted them since. Every apply is idempotent, so the next pass creates them.
+async fn missing_children(
+    client: &Client,
+    ns: &str,
+    managed: &[ManagedResource],
+) -> Result<Vec<String>, Error> {
+    async fn exists<K>(client: &Client, ns: &str, name: &str) -> Result<bool, Error>
+    where
+        K: Resource<DynamicType = (), Scope = NamespaceResourceScope>
+            + Clone
+            + DeserializeOwned
+            + std::fmt::Debug,
+    {
+        let api: Api<K> = Api::namespaced(client.clone(), ns);
+        Ok(retry_read(|| api.get_metadata_opt(name)).await?.is_some())
+    }
+
+    let mut missing = Vec::new();
+    for child in managed {
+        let name = child.name.as_str();
+        let found = match child.kind.as_str() {
+            "Deployment" => exists::<Deployment>(client, ns, name).await?,
+            "Service" => exists::<Service>(client, ns, name).await?,
+            "HorizontalPodAutoscaler" => {
+                exists::<HorizontalPodAutoscaler>(client, ns, name).await?
+            }
+            "NetworkPolicy" => exists::<NetworkPolicy>(client, ns, name).await?,
+            "PodDisruptionBudget" => exists::<PodDisruptionBudget>(client, ns, name).await?,
+            "ConfigMap" => exists::<ConfigMap>(client, ns, name).await?,
+            "TraefikService" => exists::<TraefikService>(client, ns, name).await?,
+            "IngressRoute" => exists::<IngressRoute>(client, ns, name).await?,
+            _ => true,
+        };
+        if !found {
+            missing.push(format!("{} {}", child.kind, name));
+        }
+    }
+    Ok(missing)
+}
+
+fn shadow_ready(spec: &ModelDeploymentSpec, shadow: &Option<ChildStatus>) -> bool {
+    let desired = effective_replicas(spec, &DeploymentType::Shadow)
+        .or_else(|| shadow.as_ref().and_then(|s| s.desired_replicas))
+        .unwrap_or(0);
+    let available = shadow
+        .as_ref()
+        .and_then(|s| s.available_replicas)
+        .unwrap_or(0);
+    desired > 0 && available >= desired
+}
+
+/// Whether the TraefikService already mirrors to a shadow. Once it does, the mirror is never
+/// held back again, so a later shadow rollout can't toggle mirroring on and off.
+async fn mirror_enabled(client: &Client, ns: &str, base_name: &str) -> Result<bool, Error> {
+    let api: Api<TraefikService> = Api::namespaced(client.clone(), ns);
+    match retry_read(|| api.get_opt(base_name)).await {
+        Ok(ts) => Ok(ts
+            .and_then(|ts| ts.spec.mirroring?.mirrors)
+            .is_some_and(|mirrors| !mirrors.is_empty())),
+        // no Traefik CRDs: routing reports that itself
+        Err(kube::Error::Api(resp)) if resp.code == 404 => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// A settled object has had this generation fully applied recently and has nothing in flight
+/// (canary steps, a held mirror, a force-sync), so its children can be left alone.
+fn settled(ctx: &Ctx, md: &ModelDeployment, key: &str) -> bool {
+    let Some(status) = &md.status else {
+        return false;
+    };
+    let condition = |r#type: &str| {
+        status
+            .conditions
+            .iter()
+            .flatten()
+            .find(|c| c.r#type == r#type)
+            .map(|c| c.status.as_str())
+    };
+    ctx.config.features.settled_skip
+        && status.observed_generation == md.meta().generation
+        && pending_force_sync(md).is_none()
+        && !md.annotations().contains_key(ADOPT_FROM_ANN)
+        // the scaler's count is an annotation, so it doesn't bump the generation
+        && !md.annotations().contains_key(SCALER_REPLICAS_ANN)
+        && !md.spec.is_canary()
+        // the mirror has to be switched off when its window closes
+        && md.spec.mirror_window.is_none()
+        // idleness is only measured on a full pass
+        && md.spec.scale_to_zero.is_none()
+        && condition("Ready") == Some("True")
+        && condition("MirrorPending") != Some("True")
+        && condition("RolloutOrdered") != Some("True")
+        && ctx.syncs.is_fresh(key, md.meta().generation)
+}
+
+/// The `apiVersion` `md` is read as. The first time an object is seen at it, a version the
+/// CRD marks deprecated gets a Warning event carrying the CRD's deprecation warning.
+async fn observe_api_version(ctx: &Ctx, md: &ModelDeployment) -> Result<String, Error> {
+    let version = ModelDeployment::api_version(&()).into_owned();
+    let previous = md
+        .status
+        .as_ref()
+        .and_then(|s| s.observed_api_version.as_deref());
+    if previous == Some(version.as_str()) {
+        return Ok(version);
+    }
+    if let Some(warning) = preflight::version_deprecation(&ctx.client).await {
+        emit_event(
+            ctx,
+            md,
+            "DeprecatedApiVersion",
+            &format!("Read as {}, which is deprecated: {}", version, warning),
+            EventType::Warning,
+        )
+        .await?;
+    }
+    Ok(version)
+}
+
+/// The running operator's version: `OPERATOR_VERSION` at build time, else the crate version.
+pub const OPERATOR_VERSION: &str = match option_env!("OPERATOR_VERSION") {
+    Some(version) => version,
+    None => env!("CARGO_PKG_VERSION"),
+};
+
+/// `OPERATOR_VERSION`, announced with an event the first time an object last reconciled by
+/// another version is reconciled by this one.
+async fn observe_operator_version(ctx: &Ctx, md: &ModelDeployment) -> Result<String, Error> {
+    let previous = md
+        .status
+        .as_ref()
+        .and_then(|s| s.managed_by_version.as_deref());
+    if let Some(previous) = previous.filter(|p| *p != OPERATOR_VERSION) {
+        emit_event(
+            ctx,
+            md,
+            "ManagedByNewOperatorVersion",
+            &format!(
+                "Now managed by operator {}, previously {}",
+                OPERATOR_VERSION, previous
+            ),
+            EventType::Normal,
+        )
+        .await?;
+    }
+    Ok(OPERATOR_VERSION.into())
+}
+
+/// Writes the children's observed state (phase, child status, conditions) over the last
+/// status without touching the children, returning whether the phase was held on stale reads.
+async fn refresh_observed(
+    ctx: &Ctx,
+    md: &ModelDeployment,
+    ns: &str,
+    base_name: &str,
+    extra: impl FnOnce(&mut ModelDeploymentStatus),
+) -> Result<bool, Error> {
+    let reads = get_child_status(&ctx.client, &md.spec, base_name, &child_namespace(md)).await?;
+    let mut computed = compute_model_deployment_status(
+        &md.spec,
+        &reads.live,
+        &reads.shadow,
+        Ok(()),
+        has_finalizer(md, FINALIZER),
+    )
+    .await;
+    let held = hold_uncertain_phase(&mut computed, md.status.as_ref(), reads.confident);
+
+    let mut status = md.status.clone().unwrap_or_default();
+    status.phase = computed.phase;
+    status.live_status = computed.live_status;
+    status.shadow_status = computed.shadow_status;
+    track_rollout(md, &mut status, None, reads.confident);
+    for condition in computed.conditions.into_iter().flatten() {
+        status.set_condition(condition);
+    }
+    extra(&mut status);
+    status.last_reconcile_time = last_reconcile_time(md);
+    update_status(ctx, md, ns, &status).await?;
+    Ok(held)
+}
+
+/// Times live rollouts: a live image change (`changed_at`) starts the clock, kept in status
+/// across reconciles, and live next being fully available on the new template stops it.
+fn track_rollout(
+    md: &ModelDeployment,
+    status: &mut ModelDeploymentStatus,
+    changed_at: Option<String>,
+    confident: bool,
+) {
+    let previous = md.status.as_ref();
+    status.last_rollout_duration = previous.and_then(|s| s.last_rollout_duration);
+    status.rollout_started_at =
+        changed_at.or_else(|| previous.and_then(|s| s.rollout_started_at.clone()));
+
+    // counts read before the Deployment controller saw the new image still describe the old pods
+    let available = confident
+        && status.live_status.as_ref().is_some_and(|live| {
+            let desired = live.desired_replicas.unwrap_or(0);
+            desired > 0
+                && live.updated_replicas.unwrap_or(0) >= desired
+                && live.available_replicas.unwrap_or(0) >= desired
+        });
+    if !available {
+        return;
+    }
+    let Some(started) = status
+        .rollout_started_at
+        .take()
+        .and_then(|t| DateTime::parse_from_rfc3339(&t).ok())
+    else {
+        return;
+    };
+    let seconds = (Utc::now() - started.with_timezone(&Utc)).num_milliseconds() as f64 / 1000.0;
+    let ns = md.namespace().unwrap_or_else(|| "default".into());
+    metrics::ROLLOUT_SECONDS
+        .with_label_values(&[&ns, &md.name_any()])
+        .observe(seconds);
+    status.last_rollout_duration = Some(seconds.round() as i64);
+}
+
+/// Inside a maintenance window children are left alone; only their observed state is
+/// reported, and we wake up again no later than when the window closes.
+async fn refresh_status_only(
+    ctx: &Ctx,
+    md: &ModelDeployment,
+    ns: &str,
+    base_name: &str,
+    remaining: Duration,
+) -> Result<Action, Error> {
+    let generation = md.meta().generation;
+    let pending = generation != md.status.as_ref().and_then(|s| s.observed_generation);
+    let held = refresh_observed(ctx, md, ns, base_name, |status| {
+        status.set_condition(Condition {
+            r#type: "ChangesPending".into(),
+            status: if pending {
+                "True".into()
+            } else {
+                "False".into()
+            },
+            reason: Some("MaintenanceWindow".into()),
+            message: Some(if pending {
+                format!(
+                    "Generation {} will be applied when the maintenance window closes.",
+                    generation.unwrap_or_default()
+                )
+            } else {
+                "No spec changes are waiting on the maintenance window.".into()
+            }),
+            ..Default::default()
+        });
+    })
+    .await?;
+
+    let recheck = if held {
+        STALE_READ_RECHECK
+    } else {
+        resync_interval(ctx, last_phase(md))
+    };
+    Ok(Action::requeue(remaining.min(recheck)))
+}
+
+/// With `rolloutDebounceSeconds`, the spec change to hold back and how much longer to wait.
+/// The clock restarts whenever the spec's hash differs from the pending one.
+fn rollout_debounce(md: &ModelDeployment) -> Option<(PendingChange, Duration)> {
+    let quiet = Duration::from_secs(md.spec.rollout_debounce_seconds?.into());
+    let status = md.status.as_ref()?;
+    // nothing applied yet, or nothing new to apply
+    if status.observed_generation.is_none()
+        || status.observed_generation == md.meta().generation
+        || pending_force_sync(md).is_some()
+    {
+        return None;
+    }
+
+    let mut hasher = Sha256::new();
+    hasher.update(serde_json::to_string(&md.spec).ok()?);
+    let fingerprint = format!("{:x}", hasher.finalize());
+    let now = Utc::now();
+    let first_seen = status
+        .pending_change
+        .as_ref()
+        .filter(|p| p.fingerprint == fingerprint)
+        .and_then(|p| DateTime::parse_from_rfc3339(&p.first_seen).ok())
+        .map_or(now, |t| t.with_timezone(&Utc));
+    let elapsed = (now - first_seen).to_std().unwrap_or_default();
+    let remaining = quiet.checked_sub(elapsed).filter(|d| !d.is_zero())?;
+    Some((
+        PendingChange {
+            fingerprint,
+            first_seen: first_seen.to_rfc3339(),
+        },
+        remaining,
+    ))
+}
+
+/// With `drainSeconds`, holds deletion back until the routing has been gone that long, so
+/// requests already in flight finish. The first pass deletes the IngressRoutes and records the
+/// start in `status.drainingSince`; returns how much of the drain is left.
+async fn drain_before_delete(
+    ctx: &Ctx,
+    md: &ModelDeployment,
+    ns: &str,
+    base_name: &str,
+) -> Result<Option<Duration>, Error> {
+    let Some(seconds) = md.spec.drain_seconds else {
+        return Ok(None);
+    };
+    let drain = Duration::from_secs(seconds.into());
+    let started = md
+        .status
+        .as_ref()
+        .and_then(|s| s.draining_since.as_deref())
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok());
+    if let Some(started) = started {
+        let elapsed = (Utc::now() - started.with_timezone(&Utc))
+            .to_std()
+            .unwrap_or_default();
+        return Ok((elapsed < drain).then(|| drain - elapsed));
+    }
+    if !md.spec.traffic_mirror && !md.spec.is_canary() {
+        return Ok(None);
+    }
+
+    // nothing routes to the pods without Traefik
+    if !traefik_installed(&ctx.client).await? {
+        return Ok(None);
+    }
+    let api: Api<IngressRoute> = Api::namespaced(ctx.client.clone(), &child_namespace(md));
+    prune_ingress_routes(&api, md, base_name, &[]).await?;
+    emit_event(
+        ctx,
+        md,
+        "Draining",
+        &format!(
+            "Removed the IngressRoutes; deleting the children in {}s",
+            seconds
+        ),
+        EventType::Normal,
+    )
+    .await?;
+    let mut status = md.status.clone().unwrap_or_default();
+    status.phase = Some("Draining".into());
+    status.draining_since = Some(Utc::now().to_rfc3339());
+    update_status(ctx, md, ns, &status).await?;
+    Ok(Some(drain))
+}
+
+/// Looks up the live and shadow images of a generation not yet observed in their registries
+/// and warns about any that do not exist. Lookups that fail are only logged: the pull will tell.
+async fn check_images(ctx: &Ctx, md: &ModelDeployment) -> Result<(), Error> {
+    if md.status.as_ref().and_then(|s| s.observed_generation) == md.meta().generation {
+        return Ok(());
+    }
+    for (role, variant) in [
+        ("live", Some(&md.spec.live)),
+        ("shadow", md.spec.shadow.as_ref()),
+    ] {
+        let Some(variant) = variant else {
+            continue;
+        };
+        // validation already rejected references that don't parse
+        let Ok(reference) = image::parse(&variant.image) else {
+            continue;
+        };
+        match image::exists(&reference).await {
+            Ok(true) => {}
+            Ok(false) => {
+                emit_event(
+                    ctx,
+                    md,
+                    "ImageNotFound",
+                    &format!(
+                        "{} image {} does not exist in {}",
+                        role, variant.image, reference.registry
+                    ),
+                    EventType::Warning,
+                )
+                .await?
+            }
+            Err(reason) => tracing::debug!("Could not look up {}: {}", variant.image, reason),
+        }
+    }
+    Ok(())
+}
+
+/// Warns, for a generation not yet observed, about a role running fewer replicas than the
+/// `minDomains` of its topology spread: the pods schedule, but can't cover that many domains.
+async fn check_spread(ctx: &Ctx, md: &ModelDeployment) -> Result<(), Error> {
+    if md.status.as_ref().and_then(|s| s.observed_generation) == md.meta().generation {
+        return Ok(());
+    }
+    let spec = &md.spec;
+    for (role, variant) in [
+        (DeploymentType::Live, Some(&spec.live)),
+        (DeploymentType::Shadow, spec.shadow.as_ref()),
+    ] {
+        let Some(variant) = variant else {
+            continue;
+        };
+        // an autoscaled live is only sure to run its minimum
+        let replicas = match role {
+            DeploymentType::Live if hpa_enabled(spec) => spec
+                .autoscaling
+                .as_ref()
+                .map(|a| a.min_replicas.unwrap_or(1)),
+            _ => effective_replicas(spec, &role),
+        };
+        let Some(replicas) = replicas.filter(|r| *r > 0) else {
+            continue;
+        };
+        let scheduling = variant.scheduling.as_ref().or(spec.scheduling.as_ref());
+        for spread in scheduling
+            .and_then(|s| s.topology_spread.as_ref())
+            .into_iter()
+            .flatten()
+        {
+            let Some(min_domains) = spread.min_domains.filter(|m| *m > replicas) else {
+                continue;
+            };
+            emit_event(
+                ctx,
+                md,
+                "SpreadUnsatisfiable",
+                &format!(
+                    "{} runs {} replicas, too few to cover minDomains {} of {}",
+                    role, replicas, min_domains, spread.topology_key
+                ),
+                EventType::Warning,
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// With `blockImageDowngrades`, the running live image to keep while `live.image` would move
+/// to an older tag, until the image is fixed or the downgrade is allowed, and the condition
+/// that reports it.
+async fn blocked_downgrade(
+    ctx: &Ctx,
+    md: &ModelDeployment,
+    base_name: &str,
+) -> Result<Option<(String, Condition)>, Error> {
+    if !md.spec.block_image_downgrades
+        || md
+            .annotations()
+            .get(downgrade::ALLOW_ANNOTATION)
+            .is_some_and(|v| v == "true")
+    {
+        return Ok(None);
+    }
+    let api: Api<Deployment> = Api::namespaced(ctx.client.clone(), &child_namespace(md));
+    let live = md.spec.deployment_name(base_name, "live");
+    let Some(running) = current_image(&api, &live).await? else {
+        return Ok(None);
+    };
+    let image = &md.spec.live.image;
+    if !downgrade::is_downgrade(&running, image) {
+        return Ok(None);
+    }
+
+    let message = format!(
+        "live image {} is older than the running {}; set the {} annotation to \"true\" to allow it",
+        image,
+        running,
+        downgrade::ALLOW_ANNOTATION
+    );
+    let condition = warn_once(ctx, md, "ImageDowngradeBlocked", "OlderImageTag", message).await?;
+    Ok(Some((running, condition)))
+}
+
+/// Image of the model container in an existing Deployment.
+async fn current_image(api: &Api<Deployment>, name: &str) -> Result<Option<String>, Error> {
+    Ok(retry_read(|| api.get_opt(name)).await?.and_then(|dep| {
+        dep.spec?
+            .template
+            .spec?
+            .containers
+            .into_iter()
+            .find(|c| c.name == name)?
+            .image
+    }))
+}
+
+/// The Deployment `rolloutOrder` holds back this pass: the second one, until the first runs
+/// its desired release, has rolled out and is fully available.
+async fn held_deployment(
+    api: &Api<Deployment>,
+    spec: &ModelDeploymentSpec,
+    base_name: &str,
+) -> Result<Option<DeploymentType>, Error> {
+    let Some(shadow) = &spec.shadow else {
+        return Ok(None);
+    };
+    let (first, variant, second) = match spec.rollout_order.as_deref() {
+        Some("LiveFirst") => (DeploymentType::Live, &spec.live, DeploymentType::Shadow),
+        Some("ShadowFirst") => (DeploymentType::Shadow, shadow, DeploymentType::Live),
+        _ => return Ok(None),
+    };
+    let name = spec.deployment_name(base_name, first.as_ref());
+    if current_release(api, &name).await? != Some(variant.release()) {
+        return Ok(Some(second));
+    }
+    let available = retry_read(|| api.get_opt(&name)).await?.is_some_and(|dep| {
+        let desired = dep.spec.as_ref().and_then(|s| s.replicas).unwrap_or(1);
+        dep.status.as_ref().is_some_and(|st| {
+            st.observed_generation >= dep.metadata.generation
+                && st.updated_replicas.unwrap_or(0) >= desired
+                && st.available_replicas.unwrap_or(0) >= desired
+        })
+    });
+    Ok((!available).then_some(second))
+}
+
+/// Release running in an existing Deployment: its model image, plus the companion's.
+async fn current_release(api: &Api<Deployment>, name: &str) -> Result<Option<String>, Error> {
+    let containers = retry_read(|| api.get_opt(name))
+        .await?
+        .and_then(|dep| dep.spec?.template.spec)
+        .map(|pod| pod.containers)
+        .unwrap_or_default();
+    let image = |container: &str| {
+        containers
+            .iter()
+            .find(|c| c.name == container)
+            .and_then(|c| c.image.as_deref())
+    };
+    Ok(image(name).map(|model| release(model, image(COMPANION_CONTAINER))))
+}
+
+/// Whether the last written status already holds `r#type` for `reason`.
+fn reported(md: &ModelDeployment, r#type: &str, reason: &str) -> bool {
+    md.status
+        .as_ref()
+        .and_then(|s| s.conditions.as_ref())
+        .into_iter()
+        .flatten()
+        .any(|c| c.r#type == r#type && c.reason.as_deref() == Some(reason))
+}
+
+/// The true `r#type` condition for a problem that lasts across passes, warning about it only
+/// when the last written status did not report it yet. The condition is dropped from status
+/// once the problem goes away, so a recurrence is warned about again.
+async fn warn_once(
+    ctx: &Ctx,
+    md: &ModelDeployment,
+    r#type: &str,
+    reason: &str,
+    message: String,
+) -> Result<Condition, Error> {
+    if !reported(md, r#type, reason) {
+        emit_event(ctx, md, r#type, &message, EventType::Warning).await?;
+    }
+    Ok(Condition {
+        r#type: r#type.into(),
+        status: "True".into(),
+        reason: Some(reason.into()),
+        message: Some(message),
+        ..Default::default()
+    })
+}
+
+/// A missing secret only holds the pods back (they wait in `CreateContainerConfigError` until it
+/// appears), so it is reported as a condition rather than failing the reconcile.
+async fn warn_missing_secrets(
+    ctx: &Ctx,
+    md: &ModelDeployment,
+    ns: &str,
+) -> Result<Option<Condition>, Error> {
+    let api: Api<Secret> = Api::namespaced(ctx.client.clone(), ns);
+    let mut names: Vec<&str> = [Some(&md.spec.live), md.spec.shadow.as_ref()]
+        .into_iter()
+        .flatten()
+        .flat_map(|v| v.secret_refs.as_deref().unwrap_or_default())
+        .map(|secret| secret.name.as_str())
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+
+    let mut missing = Vec::new();
+    for name in names {
+        if retry_read(|| api.get_metadata_opt(name)).await?.is_none() {
+            missing.push(name);
+        }
+    }
+    if missing.is_empty() {
+        return Ok(None);
+    }
+    let message = format!(
+        "Secrets not found in {}: {}; pods will not start until they exist",
+        ns,
+        missing.join(", ")
+    );
+    warn_once(ctx, md, "SecretsMissing", "SecretNotFound", message)
+        .await
+        .map(Some)
+}
+
+/// Two pods binding the same node port can't share a node, so past the number of eligible
+/// nodes further replicas sit Pending. Allowed, as edge setups often run one pod per node, but
+/// worth a warning, given once while the condition holds.
+async fn warn_host_port_collisions(
+    ctx: &Ctx,
+    md: &ModelDeployment,
+) -> Result<Option<Condition>, Error> {
+    let spec = &md.spec;
+    let binds_node_ports = spec.host_network == Some(true)
+        || spec.host_port.is_some()
+        || spec
+            .extra_ports
+            .iter()
+            .flatten()
+            .any(|p| p.host_port.is_some());
+    let live = spec
+        .autoscaling
+        .as_ref()
+        .filter(|_| hpa_enabled(spec))
+        .and_then(|a| a.max_replicas)
+        .unwrap_or(spec.replicas.unwrap_or(spec.live.replicas));
+    let pods = live + spec.shadow.as_ref().map_or(0, |s| s.replicas);
+    if !binds_node_ports || pods <= 1 {
+        return Ok(None);
+    }
+    let message = format!(
+        "Up to {} pods bind the same node ports; each needs its own node or stays Pending",
+        pods
+    );
+    warn_once(ctx, md, "HostPortCollision", "SharedNodePorts", message)
+        .await
+        .map(Some)
+}
+
+/// Emits `ImageUpdated` when the apply (`out`) moved an existing Deployment to a different
+/// release. A skipped apply, such as on a frozen Deployment, changed nothing.
+async fn note_image_change(
+    ctx: &Ctx,
+    md: &ModelDeployment,
+    role: DeploymentType,
+    out: Outcome,
+    previous: Option<String>,
+    image: &str,
+) -> Result<Option<ImageChange>, Error> {
+    let Some(from) = previous.filter(|prev| prev != image && out != Outcome::NoOp) else {
+        return Ok(None);
+    };
+    emit_event(
+        ctx,
+        md,
+        "ImageUpdated",
+        &format!("{} image {} → {}", role, from, image),
+        EventType::Normal,
+    )
+    .await?;
+    Ok(Some(ImageChange {
+        role: role.to_string(),
+        from,
+        to: image.into(),
+        at: Utc::now().to_rfc3339(),
+    }))
+}
+
+/// Lowers every replica count in `spec` to `cap`, returning the fields that were clamped.
+pub fn clamp_replicas(spec: &mut ModelDeploymentSpec, cap: i32) -> Vec<&'static str> {
+    let mut clamped = Vec::new();
+    let mut clamp = |field: &'static str, value: &mut i32| {
+        if *value > cap {
+            *value = cap;
+            clamped.push(field);
+        }
+    };
+
+    clamp("live.replicas", &mut spec.live.replicas);
+    if let Some(replicas) = spec.replicas.as_mut() {
+        clamp("replicas", replicas);
+    }
+    if let Some(shadow) = spec.shadow.as_mut() {
+        clamp("shadow.replicas", &mut shadow.replicas);
+    }
+    if let Some(autoscaling) = spec.autoscaling.as_mut() {
+        if let Some(min) = autoscaling.min_replicas.as_mut() {
+            clamp("autoscaling.minReplicas", min);
+        }
+        if let Some(max) = autoscaling.max_replicas.as_mut() {
+            clamp("autoscaling.maxReplicas", max);
+        }
+    }
+
+    clamped
+}
+
+/// The first Kubernetes minor release that serves `Service.spec.trafficDistribution` by default.
+const TRAFFIC_DISTRIBUTION_MINOR: u32 = 31;
+
+/// Clears `trafficDistribution` when the API server is too old to accept it, returning the
+/// server's version for the warning. A server that won't report its version is left to decide.
+pub fn strip_traffic_distribution(
+    server: Option<&Info>,
+    spec: &mut ModelDeploymentSpec,
+) -> Option<String> {
+    spec.traffic_distribution.as_ref()?;
+    let info = server?;
+    // managed clusters report minors like "30+"
+    let minor: u32 = info
+        .minor
+        .trim_end_matches(|c: char| !c.is_ascii_digit())
+        .parse()
+        .ok()?;
+    if info.major != "1" || minor >= TRAFFIC_DISTRIBUTION_MINOR {
+        return None;
+    }
+    spec.traffic_distribution = None;
+    Some(info.git_version.clone())
+}
+
+pub fn error_policy(md: Arc<ModelDeployment>, error: &Error, ctx: Arc<Ctx>) -> Action {
+    let ns = md.namespace().unwrap_or_else(|| "default".into());
+    let name = md.name_any();
+    // when throttled, come back when the API server asked us to rather than on the flat delay
+    let retry_in = error
+        .kube()
+        .and_then(retry::retry_after)
+        .unwrap_or(RETRY_DELAY);
+    let failures =
+        ctx.failures
+            .record_failure(&format!("{}/{}", ns, name), md.meta().generation, retry_in);
+    metrics::CONSECUTIVE_FAILURES
+        .with_label_values(&[&ns, &name])
+        .set(failures.into());
+    tracing::warn!(
+        "Reconcile of {}/{} failed {} time(s) in a row: {}",
+        ns,
+        name,
+        failures,
+        error
+    );
+
+    tokio::spawn(async move {
+        let _guard = ctx.locks.acquire(&md.uid().unwrap_or_default()).await;
+        // the whole status, under the same field manager as every other status write, so the
+        // next successful pass does not conflict with the streak
+        let mut status = md.status.clone().unwrap_or_default();
+        status.consecutive_failures = Some(failures);
+        if let Err(e) = update_status(&ctx, &md, &ns, &status).await {
+            tracing::warn!("Failed to record failure streak for {}/{}: {}", ns, name, e);
+        }
+    });
+
+    Action::requeue(retry_in)
+}
+
+/// `externalTrafficPolicy` is rejected by the API server on ClusterIP Services, so it is only
+/// passed through for the types that expose the model outside the cluster. Validation makes
+/// sure at least one Service is of such a type.
+fn external_traffic_policy(
+    spec: &ModelDeploymentSpec,
+    service_type: Option<&str>,
+) -> Option<String> {
+    spec.external_traffic_policy
+        .clone()
+        .filter(|_| matches!(service_type, Some("NodePort" | "LoadBalancer")))
+}
+
+fn load_balancer_source_ranges(
+    spec: &ModelDeploymentSpec,
+    service_type: Option<&str>,
+) -> Option<Vec<String>> {
+    spec.load_balancer_source_ranges
+        .clone()
+        .filter(|_| service_type == Some("LoadBalancer"))
+}
+
+pub fn desired_service(md: &ModelDeployment, base_name: &str, role: DeploymentType) -> Service {
+    let svc_name = md.spec.service_name(base_name, role.as_ref());
+
+    let mut labels = BTreeMap::new();
+    labels.insert("app".into(), base_name.to_string());
+    labels.insert("role".into(), role.to_string());
+
+    Service {
+        metadata: ObjectMeta {
+            name: Some(svc_name.clone()),
+            labels: Some(labels.clone()),
+            annotations: service_annotations(md, md.spec.service_type.as_deref()),
+            owner_references: Some(vec![owner_ref(md)]),
+            ..Default::default()
+        },
+        spec: Some(ServiceSpec {
+            selector: Some(labels),
+            ports: Some(
+                container_ports(&md.spec, role_variant(&md.spec, &role))
+                    .into_iter()
+                    .chain(model_ports(role_variant(&md.spec, &role)))
+                    .map(|p| ServicePort {
+                        target_port: Some(match &p.name {
+                            Some(name) => IntOrString::String(name.clone()),
+                            None => IntOrString::Int(p.container_port),
+                        }),
+                        name: p.name,
+                        port: p.container_port,
+                        ..Default::default()
+                    })
+                    .collect(),
+            ),
+            type_: md.spec.service_type.clone(),
+            external_traffic_policy: external_traffic_policy(
+                &md.spec,
+                md.spec.service_type.as_deref(),
+            ),
+            load_balancer_source_ranges: load_balancer_source_ranges(
+                &md.spec,
+                md.spec.service_type.as_deref(),
+            ),
+            internal_traffic_policy: md.spec.internal_traffic_policy.clone(),
+            traffic_distribution: md.spec.traffic_distribution.clone(),
+            publish_not_ready_addresses: md.spec.publish_not_ready_addresses,
+            session_affinity: md.spec.session_affinity.clone(),
+            session_affinity_config: md.spec.session_affinity_timeout_seconds.map(|timeout| {
+                SessionAffinityConfig {
+                    client_ip: Some(ClientIPConfig {
+                        timeout_seconds: Some(timeout),
+                    }),
+                }
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+async fn ensure_service(
+    ctx: &Ctx,
+    api: &Api<Service>,
+    md: &ModelDeployment,
+    base_name: &str,
+    role: DeploymentType,
+) -> Result<Outcome, Error> {
+    let svc = desired_service(md, base_name, role);
+    let result = reconsile_resource(api, &svc, ApplyOptions::new(ctx, md)).await?;
+    tracing::info!("Created Service {:?}", svc.name_any());
+
+    Ok(result)
+}
+
+/// The Service an alias points at: the activator while live is scaled to zero, else live's.
+fn alias_target(md: &ModelDeployment, base_name: &str) -> String {
+    idle::activator(md)
+        .map(String::from)
+        .unwrap_or_else(|| md.spec.live_service_name(base_name))
+}
+
+/// `aliasService`: a stable `<name>.<namespace>.svc` CNAME for `target`.
+pub fn desired_alias_service(
+    md: &ModelDeployment,
+    base_name: &str,
+    ns: &str,
+    cluster_domain: &str,
+) -> Service {
+    let target = alias_target(md, base_name);
+    Service {
+        metadata: ObjectMeta {
+            name: Some(base_name.into()),
+            labels: Some(BTreeMap::from([("app".to_string(), base_name.to_string())])),
+            owner_references: Some(vec![owner_ref(md)]),
+            ..Default::default()
+        },
+        spec: Some(ServiceSpec {
+            type_: Some("ExternalName".into()),
+            external_name: Some(format!("{}.{}.svc.{}", target, ns, cluster_domain)),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+async fn ensure_alias_service(
+    ctx: &Ctx,
+    api: &Api<Service>,
+    md: &ModelDeployment,
+    base_name: &str,
+    ns: &str,
+) -> Result<Outcome, Error> {
+    let svc = desired_alias_service(md, base_name, ns, &ctx.config.cluster_domain);
+    let wanted = svc.spec.as_ref().and_then(|s| s.external_name.clone());
+    let previous = retry_read(|| api.get_opt(base_name))
+        .await?
+        .and_then(|live| live.spec)
+        .and_then(|s| s.external_name);
+    let result = reconsile_resource(api, &svc, ApplyOptions::new(ctx, md)).await?;
+    if let Some(previous) = previous.filter(|p| Some(p) != wanted.as_ref()) {
+        emit_event(
+            ctx,
+            md,
+            "AliasRepointed",
+            &format!(
+                "Service {} now points at {} instead of {}",
+                base_name,
+                wanted.unwrap_or_default(),
+                previous
+            ),
+            EventType::Normal,
+        )
+        .await?;
+    }
+    Ok(result)
+}
+
+pub fn live_service_managed(spec: &ModelDeploymentSpec) -> bool {
+    spec.service.as_ref().is_none_or(|s| s.manage)
+}
+
+/// Once `service.manage` is switched off, the live Service we created earlier goes. One we
+/// adopted, or that is itself the `existingName`, is left in place.
+async fn release_live_service(
+    api: &Api<Service>,
+    md: &ModelDeployment,
+    base_name: &str,
+) -> Result<Outcome, Error> {
+    let name = md.spec.service_name(base_name, "live");
+    if md.spec.live_service_name(base_name) == name {
+        return Ok(Outcome::NoOp);
+    }
+    let adopted = retry_read(|| api.get_metadata_opt(&name))
+        .await?
+        .is_some_and(|svc| svc.annotations().contains_key(ADOPTED_ANN));
+    if adopted {
+        return Ok(Outcome::NoOp);
+    }
+    delete_owned(api, md, &name).await
+}
+
+pub fn extra_service_name(
+    spec: &ModelDeploymentSpec,
+    base_name: &str,
+    config: &ServiceConfig,
+) -> String {
+    spec.service_name(base_name, &config.name)
+}
+
+/// An additional Service from `spec.services`: the live Service with its own name, type,
+/// ports and annotations. It is labelled with [`SERVICE_LABEL`] so dropped entries can be
+/// found and pruned.
+pub fn desired_extra_service(
+    md: &ModelDeployment,
+    base_name: &str,
+    config: &ServiceConfig,
+) -> Service {
+    let mut svc = desired_service(md, base_name, DeploymentType::Live);
+    let meta = &mut svc.metadata;
+    meta.name = Some(extra_service_name(&md.spec, base_name, config));
+    meta.labels
+        .get_or_insert_with(Default::default)
+        .insert(SERVICE_LABEL.into(), config.name.clone());
+    meta.annotations = service_annotations(md, config.service_type.as_deref());
+    if let Some(annotations) = &config.annotations {
+        meta.annotations
+            .get_or_insert_with(Default::default)
+            .extend(annotations.clone());
+    }
+
+    let spec = svc.spec.get_or_insert_with(Default::default);
+    spec.type_ = config.service_type.clone();
+    spec.external_traffic_policy =
+        external_traffic_policy(&md.spec, config.service_type.as_deref());
+    spec.load_balancer_source_ranges =
+        load_balancer_source_ranges(&md.spec, config.service_type.as_deref());
+    if let Some(wanted) = &config.ports {
+        for ports in spec.ports.iter_mut() {
+            // a lone serving port is unnamed; validation knows it as `http`
+            ports.retain(|p| wanted.contains(&p.name.clone().unwrap_or_else(|| "http".into())));
+        }
+    }
+    svc
+}
+
+async fn ensure_extra_services(
+    ctx: &Ctx,
+    api: &Api<Service>,
+    md: &ModelDeployment,
+    base_name: &str,
+) -> Result<Outcome, Error> {
+    let configs = md.spec.services.as_deref().unwrap_or_default();
+    let mut outcome = Outcome::NoOp;
+    let applies: Vec<_> = configs
+        .iter()
+        .map(|config| async move {
+            let svc = desired_extra_service(md, base_name, config);
+            let out = reconsile_resource(api, &svc, ApplyOptions::new(ctx, md)).await?;
+            if out != Outcome::NoOp {
+                tracing::info!("Applied Service {:?}", svc.name_any());
+            }
+            Ok::<_, Error>(out)
+        })
+        .collect();
+    let applied: Vec<Result<Outcome, Error>> = futures::stream::iter(applies)
+        .buffered(ctx.config.child_concurrency)
+        .collect()
+        .await;
+    for out in applied {
+        if out? != Outcome::NoOp {
+            outcome = Outcome::Updated;
+        }
+    }
+
+    let keep: Vec<String> = configs
+        .iter()
+        .map(|c| extra_service_name(&md.spec, base_name, c))
+        .collect();
+    let lp = ListParams::default().labels(&format!("app={},{}", base_name, SERVICE_LABEL));
+    let listed = retry_read(|| api.list(&lp)).await?.items;
+    if !list_is_consistent(&listed, "Service", &keep) {
+        return Ok(outcome);
+    }
+    for svc in listed {
+        let name = svc.name_any();
+        if !keep.contains(&name) && delete_owned(api, md, &name).await? != Outcome::NoOp {
+            outcome = Outcome::Updated;
+        }
+    }
+
+    Ok(outcome)
+}
+
+/// The pod volume for a `volumes` entry; validation guarantees exactly one source is set.
+fn volume(config: &VolumeConfig) -> Volume {
+    Volume {
+        name: config.name.clone(),
+        persistent_volume_claim: config.persistent_volume_claim.as_ref().map(|claim| {
+            PersistentVolumeClaimVolumeSource {
+                claim_name: claim.clone(),
+                read_only: config.read_only.then_some(true),
+            }
+        }),
+        empty_dir: config.empty_dir.as_ref().map(|dir| EmptyDirVolumeSource {
+            medium: dir.medium.clone(),
+            size_limit: dir.size_limit.clone().map(Quantity),
+        }),
+        config_map: config
+            .config_map
+            .as_ref()
+            .map(|name| ConfigMapVolumeSource {
+                name: name.clone(),
+                ..Default::default()
+            }),
+        secret: config.secret.as_ref().map(|name| SecretVolumeSource {
+            secret_name: Some(name.clone()),
+            ..Default::default()
+        }),
+        csi: config.csi.as_ref().map(|csi| CSIVolumeSource {
+            driver: csi.driver.clone(),
+            volume_attributes: csi.volume_attributes.clone(),
+            node_publish_secret_ref: csi
+                .node_publish_secret
+                .as_ref()
+                .map(|name| LocalObjectReference { name: name.clone() }),
+            read_only: config.read_only.then_some(true),
+            ..Default::default()
+        }),
+        host_path: config.host_path.as_ref().map(|path| HostPathVolumeSource {
+            path: path.clone(),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// The operator's own helper image, for the debug sidecar and the model verifier.
+pub const DEFAULT_DEBUG_IMAGE: &str = "busybox:1.36";
+const VERIFY_CONTAINER: &str = "verify-model";
+const PREPULL_CONTAINER: &str = "prepull-fallback";
+const COMPANION_CONTAINER: &str = "companion";
+const DEBUG_CONTAINER: &str = "debug";
+const MODEL_VOLUME: &str = "model";
+const MODEL_DIR: &str = "/models";
+const INLINE_CONFIG_VOLUME: &str = "inline-config";
+const INLINE_CONFIG_DIR: &str = "/etc/model-config";
+const SHM_VOLUME: &str = "dshm";
+const SHM_DIR: &str = "/dev/shm";
+
+/// Fetches the model into the shared volume and exits non-zero on a checksum mismatch, which
+/// keeps the server container from ever starting on a tampered or truncated file.
+fn verify_model_container(checksum: &ModelChecksum) -> Container {
+    let env = |name: &str, value: &str| EnvVar {
+        name: name.into(),
+        value: Some(value.into()),
+        ..Default::default()
+    };
+    Container {
+        name: VERIFY_CONTAINER.into(),
+        image: Some(DEFAULT_DEBUG_IMAGE.into()),
+        command: Some(vec![
+            "sh".into(),
+            "-c".into(),
+            format!(
+                "wget -q -O {dir}/model \"$MODEL_URL\" && echo \"$MODEL_SHA256  {dir}/model\" | sha256sum -c -",
+                dir = MODEL_DIR
+            ),
+        ]),
+        env: Some(vec![
+            env("MODEL_URL", &checksum.url),
+            env("MODEL_SHA256", &checksum.sha256),
+        ]),
+        volume_mounts: Some(vec![VolumeMount {
+            name: MODEL_VOLUME.into(),
+            mount_path: MODEL_DIR.into(),
+            ..Default::default()
+        }]),
+        ..Default::default()
+    }
+}
+
+/// Exits at once; it is only there so the kubelet pulls and caches `image`.
+fn prepull_container(image: &str) -> Container {
+    Container {
+        name: PREPULL_CONTAINER.into(),
+        image: Some(image.into()),
+        image_pull_policy: Some("IfNotPresent".into()),
+        command: Some(vec!["true".into()]),
+        ..Default::default()
+    }
+}
+
+fn topology_spread(
+    scheduling: &SchedulingSpec,
+    labels: &BTreeMap<String, String>,
+) -> Option<Vec<TopologySpreadConstraint>> {
+    let spreads = scheduling.topology_spread.as_ref()?;
+    Some(
+        spreads
+            .iter()
+            .map(|spread| TopologySpreadConstraint {
+                topology_key: spread.topology_key.clone(),
+                max_skew: spread.max_skew,
+                when_unsatisfiable: spread
+                    .when_unsatisfiable
+                    .clone()
+                    .unwrap_or_else(|| "DoNotSchedule".into()),
+                min_domains: spread.min_domains,
+                match_label_keys: spread.match_label_keys.clone(),
+                label_selector: Some(LabelSelector {
+                    match_labels: Some(labels.clone()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .collect(),
+    )
+}
+
+fn node_affinity(scheduling: &SchedulingSpec) -> Option<Affinity> {
+    let expressions = scheduling.node_affinity.as_ref()?;
+    Some(Affinity {
+        node_affinity: Some(NodeAffinity {
+            required_during_scheduling_ignored_during_execution: Some(NodeSelector {
+                node_selector_terms: vec![NodeSelectorTerm {
+                    match_expressions: Some(
+                        expressions
+                            .iter()
+                            .map(|e| NodeSelectorRequirement {
+                                key: e.key.clone(),
+                                operator: e.operator.clone(),
+                                values: (!e.values.is_empty()).then(|| e.values.clone()),
+                            })
+                            .collect(),
+                    ),
+                    ..Default::default()
+                }],
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+}
+
+/// An idle shell container sharing the pod's network, for `kubectl exec -c debug`.
+fn debug_sidecar(spec: &ModelDeploymentSpec) -> Container {
+    Container {
+        name: DEBUG_CONTAINER.into(),
+        image: Some(
+            spec.debug_sidecar_image
+                .clone()
+                .unwrap_or_else(|| DEFAULT_DEBUG_IMAGE.into()),
+        ),
+        command: Some(vec!["sleep".into(), "infinity".into()]),
+        ..Default::default()
+    }
+}
+
+pub fn desired_deployment(
+    md: &ModelDeployment,
+    deployment_name: &str,
+    base_name: &str,
+    variant: &ModelVariant,
+    replicas: Option<i32>,
+    role: DeploymentType,
+) -> Deployment {
+    let mut labels = BTreeMap::new();
+    labels.insert("app".into(), base_name.to_string());
+    labels.insert("role".into(), role.to_string());
+
+    let spec = &md.spec;
+    let probe_port = probe_port(spec, variant);
+    let scheduling = variant.scheduling.as_ref().or(spec.scheduling.as_ref());
+
+    let mut volumes = Vec::new();
+    let mut volume_mounts = Vec::new();
+    if variant.verify_checksum.is_some() {
+        volumes.push(Volume {
+            name: MODEL_VOLUME.into(),
+            empty_dir: Some(Default::default()),
+            ..Default::default()
+        });
+        volume_mounts.push(VolumeMount {
+            name: MODEL_VOLUME.into(),
+            mount_path: MODEL_DIR.into(),
+            read_only: Some(true),
+            ..Default::default()
+        });
+    }
+    if spec.inline_config.is_some() {
+        volumes.push(Volume {
+            name: INLINE_CONFIG_VOLUME.into(),
+            config_map: Some(ConfigMapVolumeSource {
+                name: inline_config_name(base_name),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        volume_mounts.push(VolumeMount {
+            name: INLINE_CONFIG_VOLUME.into(),
+            mount_path: INLINE_CONFIG_DIR.into(),
+            read_only: Some(true),
+            ..Default::default()
+        });
+    }
+    if let Some(size) = &spec.shared_memory {
+        volumes.push(Volume {
+            name: SHM_VOLUME.into(),
+            empty_dir: Some(EmptyDirVolumeSource {
+                medium: Some("Memory".into()),
+                size_limit: Some(Quantity(size.clone())),
+            }),
+            ..Default::default()
+        });
+        volume_mounts.push(VolumeMount {
+            name: SHM_VOLUME.into(),
+            mount_path: SHM_DIR.into(),
+            ..Default::default()
+        });
+    }
+    let secret_refs = variant.secret_refs.as_deref().unwrap_or_default();
+    for (i, secret) in secret_refs.iter().enumerate() {
+        let Some(path) = &secret.mount_path else {
+            continue;
+        };
+        // indexed, as secret names may be longer than a volume name allows
+        let name = format!("secret-{}", i);
+        volumes.push(Volume {
+            name: name.clone(),
+            secret: Some(SecretVolumeSource {
+                secret_name: Some(secret.name.clone()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        volume_mounts.push(VolumeMount {
+            name,
+            mount_path: path.clone(),
+            read_only: Some(true),
+            ..Default::default()
+        });
+    }
+    for config in variant.volumes.iter().flatten() {
+        volumes.push(volume(config));
+        volume_mounts.push(VolumeMount {
+            name: config.name.clone(),
+            mount_path: config.mount_path.clone(),
+            sub_path: config.sub_path.clone(),
+            read_only: config.read_only.then_some(true),
+            ..Default::default()
+        });
+    }
+    let env_from: Vec<_> = secret_refs
+        .iter()
+        .filter(|secret| secret.mount_path.is_none())
+        .map(|secret| EnvFromSource {
+            secret_ref: Some(SecretEnvSource {
+                name: secret.name.clone(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .collect();
+
+    let env: Vec<_> = variant
+        .verify_checksum
+        .as_ref()
+        .map(|_| EnvVar {
+            name: "MODEL_PATH".into(),
+            value: Some(format!("{}/model", MODEL_DIR)),
+            ..Default::default()
+        })
+        .into_iter()
+        .chain(variant.env.iter().flatten().map(|entry| EnvVar {
+            name: entry.name.clone(),
+            value: entry.value.clone(),
+            value_from: entry.field_ref.as_ref().map(|path| EnvVarSource {
+                field_ref: Some(ObjectFieldSelector {
+                    field_path: path.clone(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+        }))
+        .collect();
+
+    let termination_message_policy = Some(
+        spec.termination_message_policy
+            .clone()
+            .unwrap_or_else(|| "FallbackToLogsOnError".into()),
+    );
+    // stays up while load balancers stop sending it requests
+    let lifecycle = spec.drain_seconds.map(|seconds| Lifecycle {
+        pre_stop: Some(LifecycleHandler {
+            sleep: Some(SleepAction {
+                seconds: seconds.into(),
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+    let container = Container {
+        name: deployment_name.into(),
+        lifecycle: lifecycle.clone(),
+        termination_message_policy: termination_message_policy.clone(),
+        image: Some(variant.image.clone()),
+        ports: Some(container_ports(spec, variant)),
+        liveness_probe: spec
+            .probes
+            .as_ref()
+            .map(|p| http_probe(&p.liveness_path, probe_port.clone())),
+        readiness_probe: spec
+            .probes
+            .as_ref()
+            .map(|p| http_probe(&p.readiness_path, probe_port.clone())),
+        startup_probe: spec
+            .probes
+            .as_ref()
+            .and_then(|p| startup_probe(p, probe_port.clone())),
+        resources: spec.resources.as_ref().map(resource_requirements),
+        env: (!env.is_empty()).then_some(env),
+        working_dir: spec.working_dir.clone(),
+        // omitted rather than `false`, like `paused`, so the live object matches
+        stdin: spec.stdin.then_some(true),
+        tty: spec.tty.then_some(true),
+        env_from: (!env_from.is_empty()).then_some(env_from),
+        volume_mounts: (!volume_mounts.is_empty()).then_some(volume_mounts.clone()),
+        ..Default::default()
+    };
+    // shares the model's volumes, so it can read the same files
+    let companion = variant.companion.as_ref().map(|companion| Container {
+        name: COMPANION_CONTAINER.into(),
+        image: Some(companion.image.clone()),
+        termination_message_policy,
+        args: companion.args.clone(),
+        ports: companion.port.map(|port| {
+            vec![ContainerPort {
+                container_port: port,
+                ..Default::default()
+            }]
+        }),
+        volume_mounts: (!volume_mounts.is_empty()).then_some(volume_mounts),
+        ..Default::default()
+    });
+
+    // co-equal with the main container, so probed and configured the same way
+    let models: Vec<Container> = variant
+        .models
+        .iter()
+        .flatten()
+        .map(|model| {
+            let port = IntOrString::String(model.name.clone());
+            Container {
+                name: model.name.clone(),
+                image: Some(model.image.clone()),
+                termination_message_policy: container.termination_message_policy.clone(),
+                args: model.args.clone(),
+                ports: Some(vec![ContainerPort {
+                    name: Some(model.name.clone()),
+                    container_port: model.port,
+                    ..Default::default()
+                }]),
+                liveness_probe: spec
+                    .probes
+                    .as_ref()
+                    .map(|p| http_probe(&p.liveness_path, port.clone())),
+                readiness_probe: spec
+                    .probes
+                    .as_ref()
+                    .map(|p| http_probe(&p.readiness_path, port.clone())),
+                startup_probe: spec
+                    .probes
+                    .as_ref()
+                    .and_then(|p| startup_probe(p, port.clone())),
+                resources: model
+                    .resources
+                    .as_ref()
+                    .or(spec.resources.as_ref())
+                    .map(resource_requirements),
+                lifecycle: lifecycle.clone(),
+                env: container.env.clone(),
+                env_from: container.env_from.clone(),
+                volume_mounts: container.volume_mounts.clone(),
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    let mut deployment = Deployment {
+        metadata: ObjectMeta {
+            name: Some(deployment_name.into()),
+            labels: Some(labels.clone()),
+            annotations: propagated_annotations(md),
+            owner_references: Some(vec![owner_ref(md)]),
+            ..Default::default()
+        },
+        spec: Some(DeploymentSpec {
+            replicas,
+            // omitted rather than `false` so an unpaused Deployment matches the live object
+            paused: variant.pause_rollout.then_some(true),
+            min_ready_seconds: variant.min_ready_seconds.or(spec.min_ready_seconds),
+            progress_deadline_seconds: variant
+                .progress_deadline_seconds
+                .or(spec.progress_deadline_seconds),
+            revision_history_limit: variant.revision_history_limit.or(match role {
+                DeploymentType::Live => None,
+                DeploymentType::Shadow => Some(0),
+            }),
+            selector: LabelSelector {
+                match_labels: Some(labels.clone()),
+                ..Default::default()
+            },
+            template: PodTemplateSpec {
+                metadata: Some(ObjectMeta {
+                    labels: Some(
+                        spec.cost_labels
+                            .iter()
+                            .flatten()
+                            .map(|(k, v)| (k.clone(), v.clone()))
+                            .chain(labels.clone())
+                            .collect(),
+                    ),
+                    annotations: spec.pod_annotations.clone(),
+                    ..Default::default()
+                }),
+                spec: Some(PodSpec {
+                    containers: std::iter::once(container)
+                        .chain(companion)
+                        .chain(models)
+                        .chain(spec.debug.then(|| debug_sidecar(spec)))
+                        .collect(),
+                    init_containers: Some(
+                        spec.fallback_image
+                            .as_deref()
+                            .filter(|_| role == DeploymentType::Live)
+                            .map(prepull_container)
+                            .into_iter()
+                            .chain(variant.verify_checksum.as_ref().map(verify_model_container))
+                            .collect::<Vec<_>>(),
+                    )
+                    .filter(|containers| !containers.is_empty()),
+                    node_selector: scheduling.and_then(|s| s.node_selector.clone()),
+                    affinity: scheduling.and_then(node_affinity),
+                    topology_spread_constraints: scheduling
+                        .and_then(|s| topology_spread(s, &labels)),
+                    volumes: (!volumes.is_empty()).then_some(volumes),
+                    host_network: spec.host_network,
+                    automount_service_account_token: spec.automount_service_account_token,
+                    enable_service_links: spec.enable_service_links,
+                    scheduler_name: spec.scheduler_name.clone(),
+                    // the default 30s on top of the preStop sleep
+                    termination_grace_period_seconds: spec
+                        .drain_seconds
+                        .map(|seconds| i64::from(seconds) + 30),
+                    // keep resolving cluster names from the node's network namespace
+                    dns_policy: spec
+                        .host_network
+                        .filter(|&on| on)
+                        .map(|_| "ClusterFirstWithHostNet".into()),
+                    readiness_gates: spec.readiness_gates.as_ref().map(|gates| {
+                        gates
+                            .iter()
+                            .map(|gate| PodReadinessGate {
+                                condition_type: gate.clone(),
+                            })
+                            .collect()
+                    }),
+                    ..Default::default()
+                }),
+            },
+            strategy: Some(DeploymentStrategy {
+                rolling_update: Some(RollingUpdateDeployment::default()),
+                ..Default::default()
+            }),
+        }),
+        ..Default::default()
+    };
+    if let Some(overlay) = &spec.pod_template_overlay {
+        let template = &mut deployment.spec.as_mut().unwrap().template;
+        overlay_pod_template(template, overlay);
+        // the selector has to keep matching
+        template
+            .metadata
+            .get_or_insert_with(Default::default)
+            .labels
+            .get_or_insert_with(Default::default)
+            .extend(labels);
+    }
+    deployment
+}
+
+/// Merges `podTemplateOverlay` over `template`. Validation has already checked the overlay
+/// reads as a pod template, so a result that doesn't is only logged and left unpatched.
+fn overlay_pod_template(template: &mut PodTemplateSpec, overlay: &serde_json::Value) {
+    let mut merged = match serde_json::to_value(&*template) {
+        Ok(value) => value,
+        Err(_) => return,
+    };
+    merge_patch(&mut merged, overlay);
+    match serde_json::from_value(merged) {
+        Ok(patched) => *template = patched,
+        Err(e) => tracing::warn!("Ignoring podTemplateOverlay: {}", e),
+    }
+}
+
+/// RFC 7386 JSON merge patch: objects merge key by key, `null` removes, anything else
+/// (lists included) replaces.
+fn merge_patch(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    use serde_json::Value;
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Default::default());
+    }
+    let Value::Object(target) = target else {
+        return;
+    };
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+pub fn inline_config_name(base_name: &str) -> String {
+    format!("{}-config", base_name)
+}
+
+pub fn desired_config_map(
+    md: &ModelDeployment,
+    base_name: &str,
+    data: &BTreeMap<String, String>,
+) -> ConfigMap {
+    ConfigMap {
+        metadata: ObjectMeta {
+            name: Some(inline_config_name(base_name)),
+            labels: Some(BTreeMap::from([("app".into(), base_name.into())])),
+            owner_references: Some(vec![owner_ref(md)]),
+            ..Default::default()
+        },
+        data: Some(data.clone()),
+        ..Default::default()
+    }
+}
+
+async fn ensure_config_map(
+    ctx: &Ctx,
+    api: &Api<ConfigMap>,
+    md: &ModelDeployment,
+    base_name: &str,
+    data: &BTreeMap<String, String>,
+) -> Result<Outcome, Error> {
+    let cm = desired_config_map(md, base_name, data);
+    reconsile_resource(api, &cm, ApplyOptions::new(ctx, md)).await
+}
+
+/// Marks the pod template with a hash of the configuration's data, from `inlineConfig` or the
+/// `configRef` ConfigMap: same-named config edits otherwise leave the template, and so the
+/// running pods, untouched.
+pub async fn stamp_config_hash(
+    client: &Client,
+    md: &ModelDeployment,
+    deploy: &mut Deployment,
+) -> Result<(), Error> {
+    let data = if let Some(inline) = &md.spec.inline_config {
+        // hashed like the rendered ConfigMap, which has no binary data
+        serde_json::to_string(&(inline, None::<()>))?
+    } else if let Some(name) = &md.spec.config_ref {
+        let ns = child_namespace(md);
+        let api: Api<ConfigMap> = Api::namespaced(client.clone(), &ns);
+        // the pods mounting it wait for it to appear, and pick it up once it does
+        let Some(config) = retry_read(|| api.get_opt(name)).await? else {
+            return Ok(());
+        };
+        serde_json::to_string(&(&config.data, &config.binary_data))?
+    } else {
+        return Ok(());
+    };
+
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    let hash = format!("{:x}", hasher.finalize());
+    if let Some(template) = deploy.spec.as_mut().map(|s| &mut s.template) {
+        template
+            .metadata
+            .get_or_insert_with(Default::default)
+            .annotations
+            .get_or_insert_with(Default::default)
+            .insert(CONFIG_HASH_ANN.into(), hash);
+    }
+    Ok(())
+}
+
+async fn ensure_deployment(
+    ctx: &Ctx,
+    api: &Api<Deployment>,
+    md: &ModelDeployment,
+    base_name: &str,
+    variant: &ModelVariant,
+    replicas: Option<i32>,
+    role: DeploymentType,
+) -> Result<Outcome, Error> {
+    let deployment_name = md.spec.deployment_name(base_name, role.as_ref());
+    let mut deploy = desired_deployment(md, &deployment_name, base_name, variant, replicas, role);
+    stamp_config_hash(&ctx.client, md, &mut deploy).await?;
+    let resized = if md.spec.rollout_strategy == "inPlace" {
+        resize_in_place(ctx, api, md, &mut deploy).await?
+    } else {
+        0
+    };
+    let mut result = reconsile_resource(api, &deploy, ApplyOptions::new(ctx, md)).await?;
+    if resized > 0 {
         emit_event(
-            &ctx,
-            &*md,
-            "Reconciled",
-            "Reconciliation completed",
+            ctx,
+            md,
+            "ResizedInPlace",
+            &format!(
+                "Resized {} pods of {} to the new resources without restarting them",
+                resized, deployment_name
+            ),
             EventType::Normal,
         )
         .await?;
+        result = Outcome::Updated;
+    }
+    if result != Outcome::NoOp {
+        tracing::info!("Created Deployment: {}", deployment_name);
+    }
+    if variant.prune_replica_sets {
+        prune_replica_sets(ctx, md, api, &deployment_name).await?;
     }
 
-    tracing::info!("Reconsiliation completed.");
+    Ok(result)
+}
+
+/// `rolloutStrategy: inPlace`: when nothing but container resources differs from the live
+/// Deployment, the running pods are resized through their `resize` subresource (Kubernetes
+/// 1.33+) instead of being rolled. `deploy` keeps the live resources so applying it starts no
+/// rollout; pods started from it later are resized on the next reconcile, and the next change
+/// to anything else rolls the new resources out with it. Returns how many pods were resized.
+async fn resize_in_place(
+    ctx: &Ctx,
+    api: &Api<Deployment>,
+    md: &ModelDeployment,
+    deploy: &mut Deployment,
+) -> Result<usize, Error> {
+    fn resources(template: &PodTemplateSpec) -> BTreeMap<String, serde_json::Value> {
+        template
+            .spec
+            .iter()
+            .flat_map(|ps| &ps.containers)
+            .map(|c| {
+                (
+                    c.name.clone(),
+                    serde_json::to_value(&c.resources).unwrap_or_default(),
+                )
+            })
+            .collect()
+    }
+
+    let name = deploy.name_any();
+    let Some(live) = retry_read(|| api.get_opt(&name)).await? else {
+        return Ok(0);
+    };
+    // its pods are as frozen as the Deployment
+    if matches!(guard(md, &live), Guard::Frozen) {
+        return Ok(0);
+    }
+    let Some(live_template) = live.spec.as_ref().map(|s| &s.template) else {
+        return Ok(0);
+    };
+    let Some(spec) = deploy.spec.as_mut() else {
+        return Ok(0);
+    };
+    let wanted = resources(&spec.template);
+    let running = resources(live_template);
+    if !wanted.keys().eq(running.keys())
+        || wanted
+            .iter()
+            .all(|(name, r)| json_contains(&running[name], r))
+    {
+        return Ok(0);
+    }
+
+    // everything but the resources has to match already
+    let mut template = spec.template.clone();
+    for container in template.spec.iter_mut().flat_map(|ps| &mut ps.containers) {
+        container.resources = serde_json::from_value(running[&container.name].clone()).ok();
+    }
+    let live_value = serde_json::to_value(live_template).unwrap_or_default();
+    if !json_contains(
+        &live_value,
+        &serde_json::to_value(&template).unwrap_or_default(),
+    ) {
+        return Ok(0);
+    }
+
+    let selector = spec
+        .selector
+        .match_labels
+        .iter()
+        .flatten()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join(",");
+    let pods: Api<Pod> = Api::namespaced(ctx.client.clone(), &child_namespace(md));
+    let lp = ListParams::default().labels(&selector);
+    let listed = retry_read(|| pods.list(&lp)).await?;
+    let mut resized = 0;
+    for pod in listed {
+        if pod.metadata.deletion_timestamp.is_some() {
+            continue;
+        }
+        let containers: Vec<_> = pod
+            .spec
+            .iter()
+            .flat_map(|ps| &ps.containers)
+            .filter_map(|c| {
+                let target = wanted.get(&c.name)?;
+                let current = serde_json::to_value(&c.resources).unwrap_or_default();
+                (!json_contains(&current, target))
+                    .then(|| json!({"name": c.name, "resources": target}))
+            })
+            .collect();
+        if containers.is_empty() {
+            continue;
+        }
+        let patch = json!({"spec": {"containers": containers}});
+        match pods
+            .patch_subresource(
+                "resize",
+                &pod.name_any(),
+                &PatchParams::default(),
+                &Patch::Strategic(&patch),
+            )
+            .await
+        {
+            Ok(_) => resized += 1,
+            // a cluster without the subresource gets an ordinary rollout instead
+            Err(kube::Error::Api(resp)) if resp.code == 404 && resized == 0 => {
+                tracing::warn!(
+                    "{} cannot be resized in place; rolling it instead",
+                    pod.name_any()
+                );
+                return Ok(0);
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    spec.template = template;
+    Ok(resized)
+}
+
+pub fn desired_hpa(
+    md: &ModelDeployment,
+    name: &str,
+    autoscaling: &AutoScalingSpec,
+) -> HorizontalPodAutoscaler {
+    let min_replicas = autoscaling.min_replicas.unwrap_or(1);
+    let cpu = autoscaling
+        .target_cpu_utilization_percentage
+        .map(|target| MetricSpec {
+            type_: "Resource".into(),
+            resource: Some(ResourceMetricSource {
+                name: "cpu".into(),
+                target: MetricTarget {
+                    type_: "Utilization".into(),
+                    average_utilization: Some(target),
+                    ..Default::default()
+                },
+            }),
+            ..Default::default()
+        });
+    let metrics: Vec<_> = cpu
+        .into_iter()
+        .chain(autoscaling.metrics.iter().flatten().map(metric_spec))
+        .collect();
+
+    HorizontalPodAutoscaler {
+        metadata: ObjectMeta {
+            name: Some(name.into()),
+            owner_references: Some(vec![owner_ref(md)]),
+            ..Default::default()
+        },
+        spec: Some(HorizontalPodAutoscalerSpec {
+            scale_target_ref: CrossVersionObjectReference {
+                api_version: Some("apps/v1".into()),
+                kind: "Deployment".into(),
+                name: name.into(),
+            },
+            min_replicas: Some(min_replicas),
+            max_replicas: autoscaling.max_replicas.unwrap_or(min_replicas),
+            metrics: (!metrics.is_empty()).then_some(metrics),
+            behavior: autoscaling.behavior.as_ref().map(hpa_behavior),
+        }),
+        ..Default::default()
+    }
+}
 
-    Ok(Action::requeue(Duration::from_secs(60)))
+fn metric_spec(metric: &ScalingMetric) -> MetricSpec {
+    let identifier = MetricIdentifier {
+        name: metric.name.clone(),
+        selector: metric.selector.clone().map(|labels| LabelSelector {
+            match_labels: Some(labels),
+            ..Default::default()
+        }),
+    };
+    let target = Quantity(metric.target.clone());
+    if metric.r#type == "Pods" {
+        return MetricSpec {
+            type_: "Pods".into(),
+            pods: Some(PodsMetricSource {
+                metric: identifier,
+                target: MetricTarget {
+                    type_: "AverageValue".into(),
+                    average_value: Some(target),
+                    ..Default::default()
+                },
+            }),
+            ..Default::default()
+        };
+    }
+    MetricSpec {
+        type_: "External".into(),
+        external: Some(ExternalMetricSource {
+            metric: identifier,
+            target: if metric.per_pod {
+                MetricTarget {
+                    type_: "AverageValue".into(),
+                    average_value: Some(target),
+                    ..Default::default()
+                }
+            } else {
+                MetricTarget {
+                    type_: "Value".into(),
+                    value: Some(target),
+                    ..Default::default()
+                }
+            },
+        }),
+        ..Default::default()
+    }
 }
 
-pub fn error_policy(_object: Arc<ModelDeployment>, _error: &Error, _ctx: Arc<Ctx>) -> Action {
-    Action::requeue(Duration::from_secs(10))
+fn hpa_behavior(behavior: &ScalingBehavior) -> HorizontalPodAutoscalerBehavior {
+    let period = behavior.scale_up_period_seconds.unwrap_or(60);
+    let policies: Vec<_> = [
+        ("Pods", behavior.scale_up_pods),
+        ("Percent", behavior.scale_up_percent),
+    ]
+    .into_iter()
+    .filter_map(|(type_, value)| {
+        Some(HPAScalingPolicy {
+            type_: type_.into(),
+            value: value?,
+            period_seconds: period,
+        })
+    })
+    .collect();
+
+    let scale_up = (behavior.scale_up_stabilization_seconds.is_some() || !policies.is_empty())
+        .then(|| HPAScalingRules {
+            stabilization_window_seconds: behavior.scale_up_stabilization_seconds,
+            policies: (!policies.is_empty()).then_some(policies),
+            ..Default::default()
+        });
+    HorizontalPodAutoscalerBehavior {
+        scale_down: behavior
+            .scale_down_stabilization_seconds
+            .map(|seconds| HPAScalingRules {
+                stabilization_window_seconds: Some(seconds),
+                ..Default::default()
+            }),
+        scale_up,
+    }
 }
 
-async fn ensure_service(
-    api: &Api<Service>,
+/// `metrics` is an atomic list, so moving between the CPU target and custom metrics is
+/// applied in place. Targets are validated up front, so a rejected apply is a real error and
+/// is surfaced as one rather than answered by deleting the HPA.
+async fn ensure_hpa(
+    ctx: &Ctx,
+    api: &Api<HorizontalPodAutoscaler>,
     md: &ModelDeployment,
-    base_name: &str,
-    role: DeploymentType,
+    name: &str,
+    autoscaling: &AutoScalingSpec,
 ) -> Result<Outcome, Error> {
-    let svc_name = format!("{}-{}-svc", base_name, role);
+    let hpa = desired_hpa(md, name, autoscaling);
+    let result = reconsile_resource(api, &hpa, ApplyOptions::new(ctx, md)).await?;
+    if result != Outcome::NoOp {
+        tracing::info!("Applied HorizontalPodAutoscaler {}", name);
+    }
+    Ok(result)
+}
 
-    let mut labels = BTreeMap::new();
-    labels.insert("app".into(), base_name.to_string());
-    labels.insert("role".into(), role.to_string());
+/// Selects every model pod (live and shadow) and only admits the configured sources on the
+/// ports the pods declare. With no sources at all the policy has no ingress rule, which blocks
+/// all ingress: a rule with an empty `from` would admit everyone instead.
+pub fn desired_network_policy(
+    md: &ModelDeployment,
+    name: &str,
+    base_name: &str,
+    policy: &NetworkPolicyConfig,
+) -> NetworkPolicy {
+    let mut selector = BTreeMap::new();
+    selector.insert("app".to_string(), base_name.to_string());
+
+    let from_namespaces = policy.from_namespaces.iter().map(|ns| NetworkPolicyPeer {
+        namespace_selector: Some(LabelSelector {
+            match_labels: Some(BTreeMap::from([(
+                "kubernetes.io/metadata.name".to_string(),
+                ns.clone(),
+            )])),
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+    let from_pods = policy
+        .from_pod_labels
+        .iter()
+        .map(|labels| NetworkPolicyPeer {
+            pod_selector: Some(LabelSelector {
+                match_labels: Some(labels.clone()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+    let from: Vec<NetworkPolicyPeer> = from_namespaces.chain(from_pods).collect();
+    // live and shadow may listen on different ports
+    let mut ports: Vec<NetworkPolicyPort> = Vec::new();
+    let extras = md.spec.extra_ports.iter().flatten();
+    for variant in std::iter::once(&md.spec.live).chain(md.spec.shadow.as_ref()) {
+        let models = variant.models.iter().flatten().map(|m| &m.name);
+        let named = extras
+            .clone()
+            .map(|p| &p.name)
+            .chain(models)
+            .map(|name| IntOrString::String(name.clone()));
+        for port in std::iter::once(serving_port_ref(&md.spec, variant)).chain(named) {
+            let port = Some(port);
+            if ports.iter().any(|p| p.port == port) {
+                continue;
+            }
+            ports.push(NetworkPolicyPort {
+                port,
+                protocol: Some("TCP".into()),
+                ..Default::default()
+            });
+        }
+    }
 
-    let svc = Service {
+    NetworkPolicy {
         metadata: ObjectMeta {
-            name: Some(svc_name.clone()),
-            labels: Some(labels.clone()),
+            name: Some(name.into()),
             owner_references: Some(vec![owner_ref(md)]),
             ..Default::default()
         },
-        spec: Some(ServiceSpec {
-            selector: Some(labels),
-            ports: Some(vec![ServicePort {
-                port: 8000,
-                target_port: Some(IntOrString::Int(8000)),
+        spec: Some(NetworkPolicySpec {
+            pod_selector: Some(LabelSelector {
+                match_labels: Some(selector),
                 ..Default::default()
-            }]),
+            }),
+            policy_types: Some(vec!["Ingress".into()]),
+            ingress: Some(
+                (!from.is_empty())
+                    .then_some(NetworkPolicyIngressRule {
+                        from: Some(from),
+                        ports: Some(ports),
+                    })
+                    .into_iter()
+                    .collect(),
+            ),
             ..Default::default()
         }),
-        ..Default::default()
-    };
+    }
+}
 
-    let result = reconsile_resource(api, &svc).await?;
-    tracing::info!("Created Service {:?}", svc_name);
+pub fn pdb_name(base_name: &str) -> String {
+    format!("{}-pdb", base_name)
+}
 
-    Ok(result)
+/// Applies the PodDisruptionBudget, `tightened` to its maintenance-window allowance, or deletes
+/// it once `disruptionBudget` is unset.
+async fn ensure_pdb(ctx: &Ctx, md: &ModelDeployment, tightened: bool) -> Result<Outcome, Error> {
+    let api: Api<PodDisruptionBudget> = Api::namespaced(ctx.client.clone(), &child_namespace(md));
+    let base_name = child_base_name(md);
+    let name = pdb_name(&base_name);
+    let Some(budget) = &md.spec.disruption_budget else {
+        return with_event(
+            ctx,
+            md,
+            "Removed PodDisruptionBudget",
+            "DisruptionBudgetDeleted",
+            "DisruptionBudgetDeleteFailed",
+            delete_owned(&api, md, &name),
+        )
+        .await;
+    };
+    let max_unavailable = budget.max_unavailable(tightened);
+    let (message, reason) = if tightened {
+        let message = format!(
+            "PodDisruptionBudget {} allows {} unavailable live pods until the maintenance window closes",
+            name, max_unavailable
+        );
+        (message, "DisruptionBudgetTightened")
+    } else {
+        (
+            "Applied PodDisruptionBudget".into(),
+            "DisruptionBudgetApplied",
+        )
+    };
+    let pdb = desired_pdb(md, &name, &base_name, max_unavailable);
+    with_event(
+        ctx,
+        md,
+        &message,
+        reason,
+        "DisruptionBudgetFailed",
+        reconsile_resource(&api, &pdb, ApplyOptions::new(ctx, md)),
+    )
+    .await
 }
 
-async fn ensure_deployment(
-    api: &Api<Deployment>,
+/// Covers the live pods only: the shadow serves no user traffic, so draining it costs nothing.
+pub fn desired_pdb(
     md: &ModelDeployment,
-    deployment_name: &str,
+    name: &str,
     base_name: &str,
-    image: &str,
-    replicas: i32,
-    role: DeploymentType,
+    max_unavailable: i32,
+) -> PodDisruptionBudget {
+    let selector = BTreeMap::from([
+        ("app".to_string(), base_name.to_string()),
+        ("role".to_string(), DeploymentType::Live.to_string()),
+    ]);
+    PodDisruptionBudget {
+        metadata: ObjectMeta {
+            name: Some(name.into()),
+            owner_references: Some(vec![owner_ref(md)]),
+            ..Default::default()
+        },
+        spec: Some(PodDisruptionBudgetSpec {
+            selector: Some(LabelSelector {
+                match_labels: Some(selector),
+                ..Default::default()
+            }),
+            max_unavailable: Some(IntOrString::Int(max_unavailable)),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+async fn ensure_network_policy(
+    ctx: &Ctx,
+    api: &Api<NetworkPolicy>,
+    md: &ModelDeployment,
+    name: &str,
+    base_name: &str,
+    policy: &NetworkPolicyConfig,
 ) -> Result<Outcome, Error> {
-    let mut labels = BTreeMap::new();
-    labels.insert("app".into(), base_name.to_string());
-    labels.insert("role".into(), role.to_string());
+    let np = desired_network_policy(md, name, base_name, policy);
+    reconsile_resource(api, &np, ApplyOptions::new(ctx, md)).await
+}
 
-    let container = Container {
-        name: deployment_name.into(),
-        image: Some(image.into()),
-        ports: Some(vec![ContainerPort {
-            container_port: 8000,
-            ..Default::default()
-        }]),
+/// Deletes `name` only while `md` still owns it, pinned to the uid that was read so an object
+/// recreated in between (by someone else, or under a new owner) is never the one removed.
+pub async fn delete_owned<K>(
+    api: &Api<K>,
+    md: &ModelDeployment,
+    name: &str,
+) -> Result<Outcome, Error>
+where
+    K: Resource + Clone + DeserializeOwned + std::fmt::Debug,
+{
+    // the plural from the URL, as `K` may only be known at runtime
+    let resource = api.resource_url().rsplit('/').next().unwrap_or_default();
+    let Some(obj) = retry_read(|| api.get_metadata_opt(name))
+        .await
+        .map_err(Error::operation("read", resource, name))?
+    else {
+        return Ok(Outcome::NoOp);
+    };
+    if !owned_by(md, &obj) {
+        tracing::warn!("Not deleting {}: not owned by {}", name, md.name_any());
+        return Ok(Outcome::NoOp);
+    }
+
+    let dp = DeleteParams {
+        preconditions: Some(Preconditions {
+            uid: obj.uid(),
+            resource_version: None,
+        }),
         ..Default::default()
     };
+    match api.delete(name, &dp).await {
+        Ok(_) => {
+            tracing::info!("deleted {}", name);
+            Ok(Outcome::Updated)
+        }
+        // gone already, or replaced by an object we never saw
+        Err(kube::Error::Api(resp)) if resp.code == 404 || resp.code == 409 => Ok(Outcome::NoOp),
+        Err(e) => Err(Error::operation("delete", resource, name)(e)),
+    }
+}
 
-    let deploy = Deployment {
+/// A list that lacks something applied moments ago came from a stale or partial cache, and
+/// pruning against it could delete everything; such a pass is skipped until the next reconcile.
+fn list_is_consistent<K: Resource>(listed: &[K], kind: &str, keep: &[String]) -> bool {
+    let missing: Vec<&String> = keep
+        .iter()
+        .filter(|name| !listed.iter().any(|o| o.meta().name.as_ref() == Some(*name)))
+        .collect();
+    if missing.is_empty() {
+        return true;
+    }
+    tracing::warn!(
+        "Not pruning {}s: list is missing just-applied {:?}",
+        kind,
+        missing
+    );
+    false
+}
+
+/// The shadow's mirror entry, sampling `mirrorPercent` of live traffic.
+fn mirror_targets(
+    spec: &ModelDeploymentSpec,
+    base_name: &str,
+) -> Vec<TraefikServiceMirroringMirrors> {
+    spec.shadow
+        .iter()
+        .map(|shadow| TraefikServiceMirroringMirrors {
+            name: spec.service_name(base_name, "shadow"),
+            kind: Some(TraefikServiceMirroringMirrorsKind::Service),
+            port: Some(IntOrString::Int(serving_port(shadow))),
+            percent: Some(spec.mirror_percent.unwrap_or(100).into()),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Mirrors live traffic to the shadows; with `mirror` false the route only reaches live.
+pub fn desired_traefik_service(
+    md: &ModelDeployment,
+    base_name: &str,
+    ns: &str,
+    mirror: bool,
+) -> TraefikService {
+    let ts_name = base_name.to_string();
+
+    // while scaled to zero, requests wait at the activator instead
+    let activator = idle::activator(md);
+    let live_svc_name =
+        activator.map_or_else(|| md.spec.live_service_name(base_name), String::from);
+
+    TraefikService {
         metadata: ObjectMeta {
-            name: Some(deployment_name.into()),
-            labels: Some(labels.clone()),
+            name: Some(ts_name.clone()),
+            namespace: Some(ns.into()),
             owner_references: Some(vec![owner_ref(md)]),
             ..Default::default()
         },
-        spec: Some(DeploymentSpec {
-            replicas: Some(replicas),
-            selector: LabelSelector {
-                match_labels: Some(labels.clone()),
-                ..Default::default()
-            },
-            template: PodTemplateSpec {
-                metadata: Some(ObjectMeta {
-                    labels: Some(labels.clone()),
-                    ..Default::default()
-                }),
-                spec: Some(PodSpec {
-                    containers: vec![container],
-                    ..Default::default()
-                }),
-            },
-            strategy: Some(DeploymentStrategy {
-                rolling_update: Some(RollingUpdateDeployment::default()),
+        spec: TraefikServiceSpec {
+            mirroring: Some(TraefikServiceMirroring {
+                name: live_svc_name,
+                kind: Some(TraefikServiceMirroringKind::Service),
+                port: Some(IntOrString::Int(serving_port(&md.spec.live))),
+                mirrors: (mirror && activator.is_none())
+                    .then(|| mirror_targets(&md.spec, base_name)),
                 ..Default::default()
             }),
             ..Default::default()
-        }),
-        ..Default::default()
-    };
-    let result = reconsile_resource(api, &deploy).await?;
-    if result != Outcome::NoOp {
-        tracing::info!("Created Deployment: {}", deployment_name);
+        },
     }
-
-    Ok(result)
 }
 
 async fn ensure_traefik_service(
+    ctx: &Ctx,
     api: &Api<TraefikService>,
     md: &ModelDeployment,
     base_name: &str,
     ns: &str,
+    mirror: bool,
 ) -> Result<Outcome, Error> {
+    let obj = desired_traefik_service(md, base_name, ns, mirror);
+    let result = reconsile_resource(api, &obj, ApplyOptions::new(ctx, md)).await?;
+    if result != Outcome::NoOp {
+        tracing::info!("created TraefikService {}", obj.name_any());
+    }
+    Ok(result)
+}
+
+/// Splits traffic between live and the shadow-as-canary by weight.
+pub fn desired_canary_service(
+    md: &ModelDeployment,
+    base_name: &str,
+    ns: &str,
+    weight: i32,
+) -> TraefikService {
     let ts_name = base_name.to_string();
 
-    let live_svc_name = format!("{}-live-svc", base_name);
-    let shadow_svc_name = format!("{}-shadow-svc", base_name);
+    let backend =
+        |name: String, role: DeploymentType, weight: i32| TraefikServiceWeightedServices {
+            name,
+            kind: Some(TraefikServiceWeightedServicesKind::Service),
+            port: Some(IntOrString::Int(serving_port(role_variant(
+                &md.spec, &role,
+            )))),
+            weight: Some(weight.into()),
+            ..Default::default()
+        };
 
-    let obj = TraefikService {
+    TraefikService {
         metadata: ObjectMeta {
             name: Some(ts_name.clone()),
             namespace: Some(ns.into()),
@@ -346,128 +3805,884 @@ async fn ensure_traefik_service(
             ..Default::default()
         },
         spec: TraefikServiceSpec {
-            mirroring: Some(TraefikServiceMirroring {
-                name: live_svc_name,
-                kind: Some(TraefikServiceMirroringKind::Service),
-                port: Some(IntOrString::Int(8000)),
-                mirrors: Some(vec![TraefikServiceMirroringMirrors {
-                    name: shadow_svc_name,
-                    kind: Some(TraefikServiceMirroringMirrorsKind::Service),
-                    port: Some(IntOrString::Int(8000)),
-                    percent: Some(100),
-                    ..Default::default()
-                }]),
+            weighted: Some(TraefikServiceWeighted {
+                services: Some(vec![
+                    backend(
+                        md.spec.live_service_name(base_name),
+                        DeploymentType::Live,
+                        100 - weight,
+                    ),
+                    backend(
+                        md.spec.service_name(base_name, "shadow"),
+                        DeploymentType::Shadow,
+                        weight,
+                    ),
+                ]),
                 ..Default::default()
             }),
             ..Default::default()
         },
-    };
+    }
+}
 
-    let result = reconsile_resource(api, &obj).await?;
+async fn ensure_canary_service(
+    ctx: &Ctx,
+    api: &Api<TraefikService>,
+    md: &ModelDeployment,
+    base_name: &str,
+    ns: &str,
+    weight: i32,
+) -> Result<Outcome, Error> {
+    let obj = desired_canary_service(md, base_name, ns, weight);
+    let result = reconsile_resource(api, &obj, ApplyOptions::new(ctx, md)).await?;
     if result != Outcome::NoOp {
-    tracing::info!("created TraefikService {}", ts_name);
+        tracing::info!(
+            "updated canary TraefikService {} to {}%",
+            obj.name_any(),
+            weight
+        );
     }
     Ok(result)
 }
 
-async fn ensure_ingress_route(
-    api: &Api<IngressRoute>,
+/// The `web` route keeps the bare base name so clusters created before multi-entrypoint
+/// support keep their existing IngressRoute.
+pub fn ingress_route_name(base_name: &str, entry_point: &str) -> String {
+    if entry_point == "web" {
+        base_name.to_string()
+    } else {
+        format!("{}-{}", base_name, entry_point)
+    }
+}
+
+/// Validation only lets `none`, `lax` and `strict` through.
+fn same_site(value: &str) -> Option<IngressRouteRoutesServicesStickyCookieSameSite> {
+    match value {
+        "none" => Some(IngressRouteRoutesServicesStickyCookieSameSite::None),
+        "lax" => Some(IngressRouteRoutesServicesStickyCookieSameSite::Lax),
+        "strict" => Some(IngressRouteRoutesServicesStickyCookieSameSite::Strict),
+        _ => None,
+    }
+}
+
+/// The hosts of every ``Host(`...`)`` matcher in a Traefik rule, in order.
+fn rule_hosts(rule: &str) -> Vec<String> {
+    let mut hosts = Vec::new();
+    let mut rest = rule;
+    while let Some(at) = rest.find("Host(") {
+        let preceded = rest[..at].chars().next_back();
+        rest = &rest[at + "Host(".len()..];
+        // part of another matcher's name, e.g. `ClientHost(`
+        if preceded.is_some_and(|c| c.is_ascii_alphanumeric()) {
+            continue;
+        }
+        let args = rest.split_once(')').map_or(rest, |(args, _)| args);
+        hosts.extend(
+            args.split('`')
+                .skip(1)
+                .step_by(2)
+                .filter(|host| !host.is_empty())
+                .map(String::from),
+        );
+    }
+    hosts
+}
+
+pub fn desired_ingress_route(
     md: &ModelDeployment,
     base_name: &str,
     ns: &str,
-) -> Result<Outcome, Error> {
-    let ir_name = base_name.to_string();
-    let host_rule = format!("Host(`{}.{}`)", base_name, "local");
+    route: &RouteSpec,
+) -> IngressRoute {
+    let ir_name = ingress_route_name(base_name, &route.entry_point);
+    let domain = md.spec.domain.as_deref().unwrap_or("local");
+    let rule = match &md.spec.match_rule {
+        Some(rule) => rule.clone(),
+        None => format!("Host(`{}.{}`)", base_name, domain),
+    };
+
+    let mut labels = BTreeMap::new();
+    labels.insert("app".into(), base_name.to_string());
+
+    let backend = |name: String, kind, port| IngressRouteRoutesServices {
+        name,
+        kind: Some(kind),
+        port: Some(IntOrString::Int(port)),
+        sticky: md
+            .spec
+            .sticky_cookie
+            .as_ref()
+            .map(|cookie| IngressRouteRoutesServicesSticky {
+                cookie: Some(IngressRouteRoutesServicesStickyCookie {
+                    name: cookie.name.clone(),
+                    secure: cookie.secure.then_some(true),
+                    http_only: cookie.http_only.then_some(true),
+                    same_site: cookie.same_site.as_deref().and_then(same_site),
+                    max_age: cookie.max_age,
+                    ..Default::default()
+                }),
+            }),
+        ..Default::default()
+    };
+    let mut routes = Vec::new();
+    match md
+        .spec
+        .mirror_match
+        .as_ref()
+        .filter(|_| md.spec.traffic_mirror && !md.spec.is_canary())
+    {
+        // matching requests go through the mirroring TraefikService, ranked above the rest
+        Some(mirror_match) => {
+            routes.push(IngressRouteRoutes {
+                kind: Some(IngressRouteRoutesKind::Rule),
+                r#match: format!("({}) && ({})", rule, mirror_match),
+                priority: md.spec.route_priority.map(|p| p + 1),
+                services: Some(vec![backend(
+                    base_name.into(),
+                    IngressRouteRoutesServicesKind::TraefikService,
+                    SERVING_PORT,
+                )]),
+                ..Default::default()
+            });
+            let live = idle::activator(md)
+                .map_or_else(|| md.spec.live_service_name(base_name), String::from);
+            routes.push(IngressRouteRoutes {
+                kind: Some(IngressRouteRoutesKind::Rule),
+                r#match: rule,
+                priority: md.spec.route_priority,
+                services: Some(vec![backend(
+                    live,
+                    IngressRouteRoutesServicesKind::Service,
+                    serving_port(&md.spec.live),
+                )]),
+                ..Default::default()
+            });
+        }
+        None => routes.push(IngressRouteRoutes {
+            kind: Some(IngressRouteRoutesKind::Rule),
+            r#match: rule,
+            priority: md.spec.route_priority,
+            services: Some(vec![backend(
+                base_name.into(),
+                IngressRouteRoutesServicesKind::TraefikService,
+                SERVING_PORT,
+            )]),
+            ..Default::default()
+        }),
+    }
 
-    let obj = IngressRoute {
+    IngressRoute {
         metadata: ObjectMeta {
             name: Some(ir_name.clone()),
             namespace: Some(ns.into()),
+            labels: Some(labels),
             owner_references: Some(vec![owner_ref(md)]),
             ..Default::default()
         },
         spec: IngressRouteSpec {
-            entry_points: Some(vec!["web".into()]),
-            routes: vec![IngressRouteRoutes {
-                kind: Some(IngressRouteRoutesKind::Rule),
-                r#match: host_rule,
-                services: Some(vec![IngressRouteRoutesServices {
-                    name: base_name.into(),
-                    kind: Some(IngressRouteRoutesServicesKind::TraefikService),
-                    port: Some(IntOrString::Int(8000)),
-                    ..Default::default()
-                }]),
+            entry_points: Some(vec![route.entry_point.clone()]),
+            routes,
+            tls: route.tls.as_ref().map(|tls| IngressRouteTls {
+                secret_name: tls.secret_name.clone(),
+                cert_resolver: tls.cert_resolver.clone(),
                 ..Default::default()
-            }],
+            }),
             ..Default::default()
         },
-    };
+    }
+}
 
-    let result = reconsile_resource(api, &obj).await?;
+async fn ensure_ingress_route(
+    ctx: &Ctx,
+    api: &Api<IngressRoute>,
+    md: &ModelDeployment,
+    base_name: &str,
+    ns: &str,
+    route: &RouteSpec,
+) -> Result<Outcome, Error> {
+    let obj = desired_ingress_route(md, base_name, ns, route);
+    let result = reconsile_resource(api, &obj, ApplyOptions::new(ctx, md)).await?;
     if result != Outcome::NoOp {
-    tracing::info!("created IngressRoute {}", ir_name);
+        tracing::info!("created IngressRoute {}", obj.name_any());
     }
     Ok(result)
 }
 
-async fn update_status(
+/// Applies the TraefikService and one IngressRoute per entrypoint, pruning dropped ones.
+/// Returns whether anything changed.
+async fn ensure_routing(
+    ctx: &Ctx,
+    md: &ModelDeployment,
+    base_name: &str,
+    ns: &str,
+    canary_weight: Option<i32>,
+    routes: &[RouteSpec],
+    mirror: bool,
+) -> Result<bool, Error> {
+    let mut changed = false;
+    let ts_api: Api<TraefikService> = Api::namespaced(ctx.client.clone(), ns);
+    let out = match canary_weight {
+        Some(weight) => {
+            with_event(
+                ctx,
+                md,
+                "Created weighted Traefik Service",
+                "TraefikServiceCreated",
+                "TraefikServiceFailed",
+                ensure_canary_service(ctx, &ts_api, md, base_name, ns, weight),
+            )
+            .await?
+        }
+        None => {
+            with_event(
+                ctx,
+                md,
+                "Created Traefik Service",
+                "TraefikServiceCreated",
+                "TraefikServiceFailed",
+                ensure_traefik_service(ctx, &ts_api, md, base_name, ns, mirror),
+            )
+            .await?
+        }
+    };
+    changed |= out != Outcome::NoOp;
+
+    let ir_api: Api<IngressRoute> = Api::namespaced(ctx.client.clone(), ns);
+    let mut routes_changed = false;
+    for route in routes {
+        let out = with_event(
+            ctx,
+            md,
+            &format!("Created Ingress Route for entrypoint {}", route.entry_point),
+            "IngressRouteCreated",
+            "IngressRouteFailed",
+            ensure_ingress_route(ctx, &ir_api, md, base_name, ns, route),
+        )
+        .await?;
+        routes_changed |= out != Outcome::NoOp;
+    }
+    changed |= routes_changed;
+    // said once per change of the routes rather than on every pass
+    if let Some(rule) = md.spec.match_rule.as_ref().filter(|_| routes_changed) {
+        emit_event(
+            ctx,
+            md,
+            "MatchRuleUnchecked",
+            &format!(
+                "IngressRoutes use matchRule {} verbatim; the operator does not check it, so a bad rule only shows up in Traefik's logs",
+                rule
+            ),
+            EventType::Warning,
+        )
+        .await?;
+    }
+
+    let keep: Vec<String> = routes
+        .iter()
+        .map(|r| ingress_route_name(base_name, &r.entry_point))
+        .collect();
+    let out = with_event(
+        ctx,
+        md,
+        "Removed Ingress Routes for dropped entrypoints",
+        "IngressRoutePruned",
+        "IngressRoutePruneFailed",
+        prune_ingress_routes(&ir_api, md, base_name, &keep),
+    )
+    .await?;
+    changed |= out != Outcome::NoOp;
+
+    Ok(changed)
+}
+
+/// Reads the routing objects back and reports whether they exist and point where we expect.
+/// A missing object usually means the Traefik CRDs are not installed in the cluster.
+async fn routing_condition(
     client: &Client,
+    ns: &str,
+    base_name: &str,
+    routes: &[RouteSpec],
+    canary: bool,
+) -> Result<Condition, Error> {
+    let ts_api: Api<TraefikService> = Api::namespaced(client.clone(), ns);
+    let ir_api: Api<IngressRoute> = Api::namespaced(client.clone(), ns);
+    let mut missing = Vec::new();
+    let mut wrong = Vec::new();
+
+    match retry_read(|| ts_api.get_opt(base_name)).await? {
+        None => missing.push(format!("TraefikService {}", base_name)),
+        Some(ts) if canary && ts.spec.weighted.is_none() => {
+            wrong.push(format!("TraefikService {} is not weighted", base_name))
+        }
+        Some(ts) if !canary && ts.spec.mirroring.is_none() => {
+            wrong.push(format!("TraefikService {} is not mirroring", base_name))
+        }
+        Some(_) => {}
+    }
+
+    for route in routes {
+        let name = ingress_route_name(base_name, &route.entry_point);
+        match retry_read(|| ir_api.get_opt(&name)).await? {
+            None => missing.push(format!("IngressRoute {}", name)),
+            Some(ir) => {
+                let on_entry_point = ir
+                    .spec
+                    .entry_points
+                    .as_ref()
+                    .is_some_and(|eps| eps.contains(&route.entry_point));
+                let to_service = ir
+                    .spec
+                    .routes
+                    .iter()
+                    .any(|r| r.services.iter().flatten().any(|svc| svc.name == base_name));
+                if !on_entry_point || !to_service {
+                    wrong.push(format!(
+                        "IngressRoute {} does not route {} to {}",
+                        name, route.entry_point, base_name
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(if !missing.is_empty() {
+        Condition {
+            r#type: "RoutingReady".into(),
+            status: "False".into(),
+            reason: Some("RoutingObjectsMissing".into()),
+            message: Some(format!(
+                "{} not found; check that the Traefik CRDs are installed.",
+                missing.join(", ")
+            )),
+            ..Default::default()
+        }
+    } else if !wrong.is_empty() {
+        Condition {
+            r#type: "RoutingReady".into(),
+            status: "False".into(),
+            reason: Some("RoutingMisconfigured".into()),
+            message: Some(wrong.join("; ")),
+            ..Default::default()
+        }
+    } else {
+        Condition {
+            r#type: "RoutingReady".into(),
+            status: "True".into(),
+            reason: Some("RoutingConfigured".into()),
+            message: Some("TraefikService and IngressRoutes are in place.".into()),
+            ..Default::default()
+        }
+    })
+}
+
+/// Deletes IngressRoutes this ModelDeployment owns that are no longer in `keep`.
+/// Whether the Traefik CRDs we route with are served. Asked of discovery rather than read off
+/// an apply's 404, which a missing namespace returns as well.
+async fn traefik_installed(client: &Client) -> Result<bool, Error> {
+    let group_version = TraefikService::api_version(&());
+    match retry_read(|| client.list_api_group_resources(&group_version)).await {
+        Ok(list) => Ok([TraefikService::plural(&()), IngressRoute::plural(&())]
+            .iter()
+            .all(|plural| list.resources.iter().any(|r| r.name == *plural))),
+        Err(kube::Error::Api(resp)) if resp.code == 404 => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn prune_ingress_routes(
+    api: &Api<IngressRoute>,
+    md: &ModelDeployment,
+    base_name: &str,
+    keep: &[String],
+) -> Result<Outcome, Error> {
+    let lp = ListParams::default().labels(&format!("app={}", base_name));
+    let mut outcome = Outcome::NoOp;
+
+    let listed = retry_read(|| api.list(&lp)).await?.items;
+    if !list_is_consistent(&listed, "IngressRoute", keep) {
+        return Ok(outcome);
+    }
+    for ir in listed {
+        let name = ir.name_any();
+        if !keep.contains(&name) && delete_owned(api, md, &name).await? != Outcome::NoOp {
+            outcome = Outcome::Updated;
+        }
+    }
+
+    Ok(outcome)
+}
+
+/// The untyped counterpart of the `ensure_*` functions: owns `obj` and applies it through
+/// [`reconsile_resource_with`], so fingerprints, drift detection and adoption behave exactly
+/// as for the built-in children.
+async fn reconsile_dynamic(
+    ctx: &Ctx,
+    md: &ModelDeployment,
+    ns: &str,
+    mut obj: DynamicObject,
+) -> Result<Outcome, Error> {
+    let types = obj
+        .types
+        .clone()
+        .ok_or_else(|| Error::Validation(format!("{} has no apiVersion/kind", obj.name_any())))?;
+    let (api, ar) = dynamic_api(&ctx.client, ns, &types).await?;
+    obj.metadata.namespace = Some(ns.into());
+    obj.metadata.owner_references = Some(vec![owner_ref(md)]);
+
+    let outcome = reconsile_resource_with(&api, &obj, ApplyOptions::new(ctx, md), &ar).await?;
+    if outcome != Outcome::NoOp {
+        tracing::info!("Applied {} {}", types.kind, obj.name_any());
+    }
+    Ok(outcome)
+}
+
+/// Applies `spec.extraResources` like any other child and deletes the ones that were applied
+/// before but are no longer listed. Returns what is now applied, for the status.
+async fn ensure_extra_resources(
+    ctx: &Ctx,
+    md: &ModelDeployment,
+    ns: &str,
+) -> Result<(Outcome, Vec<ExtraResourceRef>), Error> {
+    let mut outcome = Outcome::NoOp;
+    let mut objects = Vec::new();
+    let mut applied = Vec::new();
+
+    for raw in md.spec.extra_resources.iter().flatten() {
+        let obj: DynamicObject = serde_json::from_value(raw.clone())
+            .map_err(|e| Error::Validation(format!("extraResources: {}", e)))?;
+        let types = obj.types.clone().unwrap_or_default();
+        applied.push(ExtraResourceRef {
+            api_version: types.api_version,
+            kind: types.kind,
+            name: obj.name_any(),
+        });
+        objects.push(obj);
+    }
+    let applies: Vec<_> = objects
+        .into_iter()
+        .map(|obj| reconsile_dynamic(ctx, md, ns, obj))
+        .collect();
+    let results: Vec<Result<Outcome, Error>> = futures::stream::iter(applies)
+        .buffered(ctx.config.child_concurrency)
+        .collect()
+        .await;
+    for out in results {
+        if out? != Outcome::NoOp {
+            outcome = Outcome::Updated;
+        }
+    }
+
+    let previous = md.status.as_ref().and_then(|s| s.extra_resources.as_ref());
+    for stale in previous
+        .into_iter()
+        .flatten()
+        .filter(|r| !applied.contains(r))
+    {
+        let types = TypeMeta {
+            api_version: stale.api_version.clone(),
+            kind: stale.kind.clone(),
+        };
+        // a kind that can no longer be discovered took its objects with it
+        let api = match dynamic_api(&ctx.client, ns, &types).await {
+            Ok((api, _)) => api,
+            Err(e) => {
+                tracing::warn!("Not pruning {} {}: {}", stale.kind, stale.name, e);
+                continue;
+            }
+        };
+        if delete_owned(&api, md, &stale.name).await? != Outcome::NoOp {
+            outcome = Outcome::Updated;
+        }
+    }
+
+    Ok((outcome, applied))
+}
+
+async fn update_status(
+    ctx: &Ctx,
     md: &ModelDeployment,
     ns: &str,
     status: &ModelDeploymentStatus,
 ) -> Result<(), Error> {
-    let api: Api<ModelDeployment> = Api::namespaced(client.clone(), ns);
+    let api: Api<ModelDeployment> = Api::namespaced(ctx.client.clone(), ns);
+    let name = md.name_any();
+
+    let mut status = status.clone();
+    // conditions set after `compute_model_deployment_status` count too
+    status.set_healthy();
+    status.stamp_conditions(
+        md.status.as_ref(),
+        md.meta().generation,
+        &Utc::now().to_rfc3339(),
+    );
+    let status = &status;
+    metrics::record_status(ns, &name, status);
+
+    if !ctx.config.status_apply {
+        let patch = json!({
+            "status": status
+        });
+        let (pp, patch) = (PatchParams::default(), Patch::Merge(&patch));
+        retry_throttled(|| api.patch_status(&name, &pp, &patch)).await?;
+        return Ok(());
+    }
 
     let patch = json!({
+        "apiVersion": ModelDeployment::api_version(&()),
+        "kind": ModelDeployment::kind(&()),
         "status": status
     });
-
-    api.patch_status(
-        &md.name_any(),
-        &PatchParams::default(),
-        &Patch::Merge(&patch),
-    )
-    .await?;
+    let patch = Patch::Apply(&patch);
+    let pp = PatchParams::apply(&ctx.config.field_manager);
+    match retry_throttled(|| api.patch_status(&name, &pp, &patch)).await {
+        Ok(_) => {}
+        // fields still owned by another writer (including our own merge patches from before
+        // the switch); the operator is authoritative for status, so take them over loudly
+        Err(kube::Error::Api(resp)) if resp.code == 409 => {
+            tracing::warn!(
+                "Status of {}/{} conflicts with another field manager, forcing: {}",
+                ns,
+                name,
+                resp.message
+            );
+            let pp = pp.clone().force();
+            retry_throttled(|| api.patch_status(&name, &pp, &patch)).await?;
+        }
+        Err(e) => return Err(e.into()),
+    }
 
     Ok(())
 }
 
-async fn get_child_status(
-    client: &Client,
-    base_name: &str,
-    ns: &str,
-) -> Result<(Option<ChildStatus>, Option<ChildStatus>), Error> {
-    let deploy_api: Api<Deployment> = Api::namespaced(client.clone(), ns);
-
-    let live_name = format!("{}-live", base_name);
-    let shadow_name = format!("{}-shadow", base_name);
-
-    fn convert_to_child_status(deployment: &Deployment) -> ChildStatus {
-        let status = deployment.status.as_ref();
+async fn get_child_status(
+    client: &Client,
+    spec: &ModelDeploymentSpec,
+    base_name: &str,
+    ns: &str,
+) -> Result<ChildReads, Error> {
+    let deploy_api: Api<Deployment> = Api::namespaced(client.clone(), ns);
+
+    let live_name = spec.deployment_name(base_name, "live");
+    let shadow_name = spec.deployment_name(base_name, "shadow");
+
+    fn convert_to_child_status(deployment: &Deployment) -> ChildStatus {
+        let status = deployment.status.as_ref();
+        let desired = deployment.spec.as_ref().and_then(|sp| sp.replicas);
+        let available = status.and_then(|st| st.available_replicas);
+
+        ChildStatus {
+            ready: Some(format!(
+                "{}/{}",
+                available.unwrap_or_default(),
+                desired.unwrap_or_default()
+            )),
+            desired_replicas: desired,
+            available_replicas: available,
+            updated_replicas: status.and_then(|st| st.updated_replicas),
+            quota_exceeded: status
+                .and_then(|st| st.conditions.as_ref())
+                .into_iter()
+                .flatten()
+                .find(|c| c.type_ == "ReplicaFailure" && c.status == "True")
+                .and_then(|c| c.message.clone())
+                .filter(|message| message.contains("exceeded quota")),
+            ..Default::default()
+        }
+    }
+
+    // only pods of a Deployment that runs the verify init container can fail verification
+    fn verification_failed(dep: &Deployment, pods: &[Pod]) -> Option<bool> {
+        let verifies = dep
+            .spec
+            .as_ref()
+            .and_then(|sp| sp.template.spec.as_ref())
+            .and_then(|ps| ps.init_containers.as_ref())
+            .is_some_and(|ics| ics.iter().any(|c| c.name == VERIFY_CONTAINER));
+        verifies.then(|| {
+            pods.iter().any(|pod| {
+                pod.status
+                    .iter()
+                    .flat_map(|st| st.init_container_statuses.iter().flatten())
+                    .filter(|cs| cs.name == VERIFY_CONTAINER)
+                    .flat_map(|cs| [cs.state.as_ref(), cs.last_state.as_ref()])
+                    .flatten()
+                    .filter_map(|state| state.terminated.as_ref())
+                    .any(|t| t.exit_code != 0)
+            })
+        })
+    }
+
+    /// The latest non-zero exit of any container in `pods`, with the last line of its
+    /// termination message (the log tail, with `FallbackToLogsOnError`).
+    fn last_crash(pods: &[Pod]) -> Option<String> {
+        pods.iter()
+            .flat_map(|pod| {
+                pod.status
+                    .iter()
+                    .flat_map(|st| st.container_statuses.iter().flatten())
+                    .flat_map(move |cs| {
+                        [cs.state.as_ref(), cs.last_state.as_ref()]
+                            .into_iter()
+                            .flatten()
+                            .filter_map(|state| state.terminated.as_ref())
+                            .map(move |t| (pod, cs, t))
+                    })
+            })
+            .filter(|(_, _, t)| t.exit_code != 0)
+            .max_by_key(|(_, _, t)| t.finished_at.clone())
+            .map(|(pod, cs, t)| {
+                let mut crash = format!(
+                    "{}/{}: {} (exit code {})",
+                    pod.name_any(),
+                    cs.name,
+                    t.reason.as_deref().unwrap_or("Error"),
+                    t.exit_code
+                );
+                if let Some(line) = t.message.as_deref().and_then(|m| m.trim().lines().last()) {
+                    crash.push_str(": ");
+                    crash.push_str(line);
+                }
+                crash
+            })
+    }
+
+    /// Ready pods per `models` container: every container but the main one, the companion
+    /// and the debug shell.
+    fn model_readiness(dep: &Deployment, pods: &[Pod]) -> Option<Vec<ModelReadiness>> {
+        let main = dep.name_any();
+        let models: Vec<ModelReadiness> = dep
+            .spec
+            .as_ref()
+            .and_then(|sp| sp.template.spec.as_ref())
+            .into_iter()
+            .flat_map(|ps| &ps.containers)
+            .filter(|c| {
+                ![main.as_str(), COMPANION_CONTAINER, DEBUG_CONTAINER].contains(&c.name.as_str())
+            })
+            .map(|c| ModelReadiness {
+                name: c.name.clone(),
+                ready_pods: pods
+                    .iter()
+                    .filter(|pod| {
+                        pod.status
+                            .iter()
+                            .flat_map(|st| st.container_statuses.iter().flatten())
+                            .any(|cs| cs.name == c.name && cs.ready)
+                    })
+                    .count() as i32,
+            })
+            .collect();
+        (!models.is_empty()).then_some(models)
+    }
+
+    let rs_api: Api<ReplicaSet> = Api::namespaced(client.clone(), ns);
+    let old_count = |dep: &Deployment| {
+        let lp = ListParams::default().labels(&format!(
+            "app={},role={}",
+            base_name,
+            dep.labels().get("role").map_or("", String::as_str)
+        ));
+        let rs_api = rs_api.clone();
+        let dep = dep.clone();
+        async move {
+            let listed = retry_read(|| rs_api.list_metadata(&lp)).await?.items;
+            Ok::<_, Error>(old_replica_sets(&dep, &listed).len() as i32)
+        }
+    };
+
+    let pod_api: Api<Pod> = Api::namespaced(client.clone(), ns);
+    let pods = |role: DeploymentType| {
+        let lp = ListParams::default().labels(&format!("app={},role={}", base_name, role));
+        let pod_api = pod_api.clone();
+        async move { Ok::<_, Error>(retry_read(|| pod_api.list(&lp)).await?.items) }
+    };
+
+    // the Deployment controller has not caught up with the latest spec yet, so the replica
+    // counts describe an older state
+    let settled = |dep: &Deployment| {
+        dep.status.as_ref().and_then(|st| st.observed_generation) >= dep.metadata.generation
+    };
+
+    let mut reads = ChildReads {
+        confident: true,
+        ..Default::default()
+    };
+    match retry_read(|| deploy_api.get_opt(&live_name)).await? {
+        Some(dep) => {
+            let pods = pods(DeploymentType::Live).await?;
+            let mut status = convert_to_child_status(&dep);
+            status.verification_failed = verification_failed(&dep, &pods);
+            status.last_crash = last_crash(&pods);
+            status.models = model_readiness(&dep, &pods);
+            if spec.live.prune_replica_sets {
+                status.old_replica_sets = Some(old_count(&dep).await?);
+            }
+            reads.confident &= settled(&dep);
+            reads.live = Some(status);
+        }
+        // we just applied it; a miss is a stale read
+        None => reads.confident = false,
+    }
+
+    if let Some(dep) = retry_read(|| deploy_api.get_opt(&shadow_name)).await? {
+        let pods = pods(DeploymentType::Shadow).await?;
+        let mut status = convert_to_child_status(&dep);
+        status.verification_failed = verification_failed(&dep, &pods);
+        status.last_crash = last_crash(&pods);
+        status.models = model_readiness(&dep, &pods);
+        if spec.shadow.as_ref().is_some_and(|s| s.prune_replica_sets) {
+            status.old_replica_sets = Some(old_count(&dep).await?);
+        }
+        reads.confident &= settled(&dep);
+        reads.shadow = Some(status);
+    }
+
+    Ok(reads)
+}
+
+/// The ReplicaSets among `listed` that `dep` owns but no longer runs, newest revision first.
+fn old_replica_sets<'a, K: Resource>(dep: &Deployment, listed: &'a [K]) -> Vec<&'a K> {
+    const REVISION_ANN: &str = "deployment.kubernetes.io/revision";
+    let revision = |obj: &BTreeMap<String, String>| {
+        obj.get(REVISION_ANN)
+            .and_then(|r| r.parse::<i64>().ok())
+            .unwrap_or_default()
+    };
+    let current = revision(dep.annotations());
+    let uid = dep.uid();
+    let mut old: Vec<&K> = listed
+        .iter()
+        .filter(|rs| {
+            rs.owner_references()
+                .iter()
+                .any(|o| Some(&o.uid) == uid.as_ref())
+        })
+        .filter(|rs| revision(rs.annotations()) != current)
+        .collect();
+    old.sort_by_key(|rs| std::cmp::Reverse(revision(rs.annotations())));
+    old
+}
+
+/// With `pruneReplicaSets`, deletes the old ReplicaSets of `name` that are scaled to zero and
+/// beyond its `revisionHistoryLimit`. Only ReplicaSets the Deployment owns are considered.
+async fn prune_replica_sets(
+    ctx: &Ctx,
+    md: &ModelDeployment,
+    api: &Api<Deployment>,
+    name: &str,
+) -> Result<usize, Error> {
+    let Some(dep) = retry_read(|| api.get_opt(name)).await? else {
+        return Ok(0);
+    };
+    let limit = dep
+        .spec
+        .as_ref()
+        .and_then(|s| s.revision_history_limit)
+        .unwrap_or(10)
+        .max(0) as usize;
+    let rs_api: Api<ReplicaSet> = Api::namespaced(ctx.client.clone(), &child_namespace(md));
+    let lp = ListParams::default().labels(&format!(
+        "app={},role={}",
+        child_base_name(md),
+        dep.labels().get("role").map_or("", String::as_str)
+    ));
+    let listed = retry_read(|| rs_api.list(&lp)).await?.items;
+
+    let mut pruned = 0;
+    for rs in old_replica_sets(&dep, &listed).into_iter().skip(limit) {
+        let idle = rs.spec.as_ref().and_then(|s| s.replicas).unwrap_or(0) == 0
+            && rs.status.as_ref().is_none_or(|s| s.replicas == 0);
+        if !idle {
+            continue;
+        }
+        let dp = DeleteParams {
+            preconditions: Some(Preconditions {
+                uid: rs.uid(),
+                resource_version: None,
+            }),
+            ..Default::default()
+        };
+        match rs_api.delete(&rs.name_any(), &dp).await {
+            Ok(_) => pruned += 1,
+            Err(kube::Error::Api(resp)) if resp.code == 404 || resp.code == 409 => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    if pruned > 0 {
+        emit_event(
+            ctx,
+            md,
+            "ReplicaSetsPruned",
+            &format!(
+                "Deleted {} old ReplicaSets of {} beyond its revision history limit of {}",
+                pruned, name, limit
+            ),
+            EventType::Normal,
+        )
+        .await?;
+    }
+    Ok(pruned)
+}
+
+#[derive(Default)]
+struct ChildReads {
+    live: Option<ChildStatus>,
+    shadow: Option<ChildStatus>,
+    /// Every Deployment was found and reports on its current generation.
+    confident: bool,
+}
 
-        ChildStatus {
-            available_replicas: status.and_then(|st| st.available_replicas),
-            updated_replicas: status.and_then(|st| st.updated_replicas),
-        }
+/// A `Degraded` phase computed from reads we are not confident in is most likely an API
+/// server blip or a Deployment mid-update, so the previous phase is kept instead and the
+/// caller checks again shortly. Returns whether the phase was held.
+fn hold_uncertain_phase(
+    status: &mut ModelDeploymentStatus,
+    previous: Option<&ModelDeploymentStatus>,
+    confident: bool,
+) -> bool {
+    if confident || status.phase.as_deref() != Some("Degraded") {
+        return false;
     }
-
-    let live_status = match deploy_api.get_opt(&live_name).await? {
-        Some(dep) => Some(convert_to_child_status(&dep)),
-        None => None,
+    let Some(phase) = previous.and_then(|p| p.phase.clone()) else {
+        return false;
     };
+    tracing::info!("Child reads look stale; keeping phase {} for now", phase);
+    status.phase = Some(phase);
+    true
+}
 
-    let shadow_status = match deploy_api.get_opt(&shadow_name).await? {
-        Some(dep) => Some(convert_to_child_status(&dep)),
-        None => None,
+/// `Accepted` tracks whether the spec was taken on, independent of how the rollout goes: it
+/// is True once it validated and the finalizer is in place, so it is safe to
+/// `kubectl wait --for=condition=Accepted` on in a pipeline.
+fn accepted_condition(validation: Result<(), &Error>, finalized: bool) -> Condition {
+    let (status, reason, message) = match validation {
+        Err(e) => ("False", "ValidationFailed", e.to_string()),
+        Ok(()) if !finalized => (
+            "False",
+            "FinalizerPending",
+            "Spec is valid; waiting for the finalizer to be added.".into(),
+        ),
+        Ok(()) => (
+            "True",
+            "SpecValid",
+            "Spec is valid and being reconciled.".into(),
+        ),
     };
-
-    Ok((live_status, shadow_status))
+    Condition {
+        r#type: "Accepted".into(),
+        status: status.into(),
+        reason: Some(reason.into()),
+        message: Some(message),
+        ..Default::default()
+    }
 }
 
 async fn compute_model_deployment_status(
     spec: &ModelDeploymentSpec,
     live: &Option<ChildStatus>,
     shadow: &Option<ChildStatus>,
+    validation: Result<(), &Error>,
+    finalized: bool,
 ) -> ModelDeploymentStatus {
     // helper
     fn availabld_replicas(cs: &Option<ChildStatus>) -> i32 {
@@ -475,13 +4690,25 @@ async fn compute_model_deployment_status(
     }
 
     let live_available = availabld_replicas(live);
-    let live_desired = spec.live.replicas;
+    // an HPA-owned Deployment's desired count is whatever the HPA last set
+    let live_desired = effective_replicas(spec, &DeploymentType::Live)
+        .or_else(|| live.as_ref().and_then(|s| s.desired_replicas))
+        .unwrap_or(0);
 
     let shadow_available = availabld_replicas(shadow);
-    let shadow_desired = spec.shadow.as_ref().map(|r| r.replicas).unwrap_or(0);
+    // a scaled canary's target moves with its weight, so take it from the Deployment
+    let shadow_desired = if spec.scale_canary && spec.is_canary() {
+        shadow.as_ref().and_then(|s| s.desired_replicas)
+    } else {
+        effective_replicas(spec, &DeploymentType::Shadow)
+    }
+    .or_else(|| shadow.as_ref().and_then(|s| s.desired_replicas))
+    .unwrap_or(0);
 
     // calculate Phase of deployment
-    let phase = if live_available == live_desired
+    let phase = if spec.suspend {
+        Some("Suspended".into())
+    } else if live_available == live_desired
         && (spec.shadow.is_none() || shadow_available == shadow_desired)
     {
         Some("Available".into())
@@ -492,15 +4719,21 @@ async fn compute_model_deployment_status(
     };
 
     // create Conditions
-    let mut conditions = Vec::with_capacity(3);
+    let mut conditions = vec![accepted_condition(validation, finalized)];
 
     let ready = live_available == live_desired
         && (spec.shadow.is_none() || shadow_available == shadow_desired);
 
     conditions.push(Condition {
         r#type: "Ready".into(),
-        status: if ready { "True".into() } else { "False".into() },
-        reason: Some(if ready {
+        status: if ready && !spec.suspend {
+            "True".into()
+        } else {
+            "False".into()
+        },
+        reason: Some(if spec.suspend {
+            "Suspended".into()
+        } else if ready {
             "AllReplicasAvailable".into()
         } else {
             "ReplicasNotReady".into()
@@ -509,9 +4742,16 @@ async fn compute_model_deployment_status(
             "live {}/{} shadow {}/{} available",
             live_available, live_desired, shadow_available, shadow_desired
         )),
+        ..Default::default()
     });
 
-    let progressing = !ready;
+    // a paused rollout is waiting on the user, not making progress
+    let paused: Vec<&str> = std::iter::once(("live", &spec.live))
+        .chain(spec.shadow.as_ref().map(|s| ("shadow", s)))
+        .filter(|(_, v)| v.pause_rollout)
+        .map(|(role, _)| role)
+        .collect();
+    let progressing = !ready && paused.is_empty();
     conditions.push(Condition {
         r#type: "Progressing".into(),
         status: if progressing {
@@ -519,8 +4759,17 @@ async fn compute_model_deployment_status(
         } else {
             "False".into()
         },
-        reason: Some("Reconciling".into()),
-        message: Some("Deployment is rolling out or scaling.".into()),
+        reason: Some(if paused.is_empty() {
+            "Reconciling".into()
+        } else {
+            "RolloutPaused".into()
+        }),
+        message: Some(if paused.is_empty() {
+            "Deployment is rolling out or scaling.".into()
+        } else {
+            format!("Rollout paused for {}.", paused.join(", "))
+        }),
+        ..Default::default()
     });
 
     let degraded = live_available == 0 && live_desired > 0;
@@ -533,59 +4782,1274 @@ async fn compute_model_deployment_status(
         },
         reason: Some("NoAvailableReplicas".into()),
         message: Some("No live replicas are currently available.".into()),
+        ..Default::default()
+    });
+
+    if spec.shadow.is_some() {
+        let standby = spec.shadow_standby();
+        conditions.push(Condition {
+            r#type: "ShadowStandby".into(),
+            status: if standby {
+                "True".into()
+            } else {
+                "False".into()
+            },
+            reason: Some(if standby { "ZeroReplicas" } else { "Running" }.into()),
+            message: Some(if standby {
+                "Shadow is configured but scaled to zero; raise shadow.replicas to start it.".into()
+            } else {
+                "Shadow is running.".into()
+            }),
+            ..Default::default()
+        });
+    }
+
+    let crashes: Vec<String> = [("live", live), ("shadow", shadow)]
+        .into_iter()
+        .filter_map(|(role, cs)| {
+            let crash = cs.as_ref()?.last_crash.as_ref()?;
+            Some(format!("{} {}", role, crash))
+        })
+        .collect();
+    conditions.push(Condition {
+        r#type: "ContainerCrashed".into(),
+        status: if crashes.is_empty() {
+            "False".into()
+        } else {
+            "True".into()
+        },
+        reason: Some(if crashes.is_empty() {
+            "NoCrashes".into()
+        } else {
+            "ContainerTerminated".into()
+        }),
+        message: Some(if crashes.is_empty() {
+            "No model pod container has exited with an error.".into()
+        } else {
+            crashes.join("; ")
+        }),
+        ..Default::default()
     });
 
-    ModelDeploymentStatus {
+    let mut status = ModelDeploymentStatus {
         phase,
         live_status: live.clone(),
         shadow_status: shadow.clone(),
         conditions: Some(conditions),
+        ..Default::default()
+    };
+
+    let unverified: Vec<&str> = [("live", live), ("shadow", shadow)]
+        .into_iter()
+        .filter(|(_, cs)| {
+            cs.as_ref()
+                .and_then(|s| s.verification_failed)
+                .unwrap_or(false)
+        })
+        .map(|(role, _)| role)
+        .collect();
+    if !unverified.is_empty() {
+        status.set_condition(Condition {
+            r#type: "Degraded".into(),
+            status: "True".into(),
+            reason: Some("ModelVerificationFailed".into()),
+            message: Some(format!(
+                "Model checksum verification failed for {}.",
+                unverified.join(", ")
+            )),
+            ..Default::default()
+        });
+    }
+
+    // pods that can't be created won't show up by waiting, so this isn't progress
+    let quota: Vec<String> = [("live", live), ("shadow", shadow)]
+        .into_iter()
+        .filter_map(|(role, cs)| {
+            let message = cs.as_ref()?.quota_exceeded.as_ref()?;
+            Some(format!("{}: {}", role, message))
+        })
+        .collect();
+    if !quota.is_empty() && !spec.suspend {
+        status.phase = Some("Degraded".into());
+        for r#type in ["Degraded", "Progressing"] {
+            status.set_condition(Condition {
+                r#type: r#type.into(),
+                status: if r#type == "Degraded" {
+                    "True"
+                } else {
+                    "False"
+                }
+                .into(),
+                reason: Some("QuotaExceeded".into()),
+                message: Some(format!(
+                    "Stuck at live {}/{} shadow {}/{} replicas by a ResourceQuota: {}",
+                    live_available,
+                    live_desired,
+                    shadow_available,
+                    shadow_desired,
+                    quota.join("; ")
+                )),
+                ..Default::default()
+            });
+        }
+    }
+
+    status.set_healthy();
+    status
+}
+
+/// Assigns a ModelDeployment to one operator instance (see `INSTANCE_NAME`); every other
+/// instance leaves it alone.
+pub const MANAGED_BY_ANN: &str = "ml.jedimindtricks.example/managed-by";
+
+/// Children live in another namespace, so they are owned through `OWNER_LABEL`.
+pub fn cross_namespace(md: &ModelDeployment) -> bool {
+    md.namespace().as_deref().unwrap_or("default") != child_namespace(md)
+}
+
+/// Swaps the owner reference of a child in a `targetNamespace` for `OWNER_LABEL`.
+pub fn relabel_owner<K: Resource>(md: &ModelDeployment, obj: &mut K) {
+    if !cross_namespace(md) {
+        return;
+    }
+    let meta = obj.meta_mut();
+    meta.owner_references = None;
+    meta.labels
+        .get_or_insert_with(Default::default)
+        .insert(OWNER_LABEL.into(), md.uid().unwrap_or_default());
+}
+
+/// Adds `costLabels` to a child's labels, leaving the operator's own untouched.
+pub fn add_cost_labels<K: Resource>(md: &ModelDeployment, obj: &mut K) {
+    let Some(cost) = &md.spec.cost_labels else {
+        return;
+    };
+    let labels = obj.meta_mut().labels.get_or_insert_with(Default::default);
+    for (key, value) in cost {
+        labels.entry(key.clone()).or_insert_with(|| value.clone());
+    }
+}
+
+/// Whether `md` owns `obj`, by owner reference or, across namespaces, by `OWNER_LABEL`.
+pub fn owned_by<K: Resource>(md: &ModelDeployment, obj: &K) -> bool {
+    let Some(uid) = md.meta().uid.as_ref() else {
+        return false;
+    };
+    obj.owner_references().iter().any(|o| &o.uid == uid)
+        || obj.labels().get(OWNER_LABEL) == Some(uid)
+}
+
+/// The instance named by `managed-by`, or the default one for unassigned objects.
+fn assigned_instance(md: &ModelDeployment) -> &str {
+    md.annotations()
+        .get(MANAGED_BY_ANN)
+        .map_or(DEFAULT_INSTANCE, String::as_str)
+}
+
+/// Pod template annotation holding the hash of the `configRef` ConfigMap.
+pub const CONFIG_HASH_ANN: &str = "ml.jedimindtricks.example/config-hash";
+/// Set on the Services created from `spec.services`, holding the entry's name.
+pub const SERVICE_LABEL: &str = "ml.jedimindtricks.example/service";
+pub const FP_ANN: &str = "ml.jedimindtricks.example/desired-fingerprint";
+/// `"true"` on a child stops us applying to it, so it can be hand-edited for a while without
+/// being reverted. Deleting and pruning it still happen.
+pub const FREEZE_ANN: &str = "ml.jedimindtricks.example/freeze";
+/// Holds the owner's uid on children in a `targetNamespace`, standing in for the owner
+/// reference they cannot have.
+pub const OWNER_LABEL: &str = "ml.jedimindtricks.example/owner";
+/// Marks children that existed before we took them over, so finalization disowns them
+/// instead of letting garbage collection delete them.
+pub const ADOPTED_ANN: &str = "ml.jedimindtricks.example/adopted";
+/// Prefix of the fingerprint value. Bump it whenever the way desired objects are built or
+/// hashed changes: children stamped with another version are re-applied once and re-stamped.
+const FP_VERSION: &str = "v2";
+
+/// Fingerprints written before versioning were a bare hash; those count as `v1`.
+fn fingerprint_version(value: &str) -> &str {
+    value.split_once(':').map_or("v1", |(version, _)| version)
+}
+
+const FORCE_SYNC_ANN: &str = "ml.jedimindtricks.example/force-sync";
+
+/// A force-sync is pending while the annotation differs from the last value we acted on.
+fn pending_force_sync(md: &ModelDeployment) -> Option<&String> {
+    let requested = md.annotations().get(FORCE_SYNC_ANN)?;
+    let handled = md.status.as_ref().and_then(|s| s.last_force_sync.as_ref());
+    (Some(requested) != handled).then_some(requested)
+}
+
+#[derive(Clone, Copy)]
+struct ApplyOptions<'a> {
+    ctx: &'a Ctx,
+    md: &'a ModelDeployment,
+    adoption: AdoptionPolicy,
+    force: bool,
+    merge: bool,
+    field_manager: &'a str,
+}
+
+impl<'a> ApplyOptions<'a> {
+    fn new(ctx: &'a Ctx, md: &'a ModelDeployment) -> Self {
+        Self {
+            ctx,
+            md,
+            adoption: md.spec.adoption_policy,
+            force: pending_force_sync(md).is_some(),
+            merge: md.spec.patch_strategy.as_deref() == Some("Merge"),
+            field_manager: &ctx.config.field_manager,
+        }
+    }
+
+    /// Whether an apply conflict on `kind` is resolved by forcing, per `FORCE_APPLY_KINDS`.
+    fn takes_over(&self, kind: &str) -> bool {
+        self.ctx
+            .config
+            .force_apply_kinds
+            .iter()
+            .any(|k| k == "*" || k == kind)
+    }
+}
+
+/// True when every field set in `desired` has the same value in `live`. Fields the API server
+/// defaults (and anything we never set) are ignored; lists must match element by element.
+pub fn json_contains(live: &serde_json::Value, desired: &serde_json::Value) -> bool {
+    use serde_json::Value;
+    match (live, desired) {
+        (_, Value::Null) => true,
+        (Value::Object(live), Value::Object(desired)) => desired
+            .iter()
+            .all(|(k, v)| json_contains(live.get(k).unwrap_or(&Value::Null), v)),
+        (Value::Array(live), Value::Array(desired)) => {
+            live.len() == desired.len()
+                && live.iter().zip(desired).all(|(l, d)| json_contains(l, d))
+        }
+        (live, desired) => live == desired,
+    }
+}
+
+/// Hash of `desired` as we are about to build it, prefixed with [`FP_VERSION`].
+pub fn desired_fingerprint<T: Serialize>(t: &T) -> Result<String, Error> {
+    let json = serde_json::to_string(t)?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(json.as_bytes());
+
+    let hash = hasher.finalize();
+    Ok(format!("{}:{:x}", FP_VERSION, hash))
+}
+
+/// How a live child compares with the object we would apply.
+#[derive(Debug, PartialEq)]
+enum Drift {
+    /// Carries fingerprint `fp` and every field we set.
+    None,
+    /// Holds every field we set but was stamped under another [`FP_VERSION`]; applying only
+    /// rewrites the annotation, which is outside the pod template and so rolls nothing out.
+    Restamp,
+    /// Stamped for another spec, or edited out of band.
+    Changed,
+}
+
+/// The `(running, wanted)` roles when `live`'s selector still holds the capitalised
+/// `role: Shadow` older releases wrote. Selectors are immutable, so the corrected
+/// `role: shadow` can only be applied by recreating the Deployment; its pods go with it.
+fn legacy_selector(
+    live: &serde_json::Value,
+    desired: &serde_json::Value,
+) -> Option<(String, String)> {
+    let role = |obj: &serde_json::Value| {
+        obj.pointer("/spec/selector/matchLabels/role")
+            .and_then(serde_json::Value::as_str)
+            .map(String::from)
+    };
+    let (running, wanted) = (role(live)?, role(desired)?);
+    (running != wanted && running.eq_ignore_ascii_case(&wanted)).then_some((running, wanted))
+}
+
+/// How a live child that no other ModelDeployment owns is treated before we apply over it.
+#[derive(Debug, PartialEq)]
+enum Guard {
+    /// Carries `FREEZE_ANN`, so it is left as it is whatever its fingerprint says.
+    Frozen,
+    /// Stamped by us but stripped of its owner reference, so it would no longer be collected
+    /// with the ModelDeployment; the apply restores it.
+    LostOwner,
+    /// Compared with what we would apply as usual.
+    Check,
+}
+
+fn guard<K: Resource>(md: &ModelDeployment, live: &K) -> Guard {
+    if live.annotations().get(FREEZE_ANN).map(String::as_str) == Some("true") {
+        Guard::Frozen
+    } else if live.annotations().contains_key(FP_ANN) && !owned_by(md, live) {
+        Guard::LostOwner
+    } else {
+        Guard::Check
+    }
+}
+
+fn drift(live: &serde_json::Value, desired: &serde_json::Value, fp: &str) -> Drift {
+    let stamped = live
+        .pointer("/metadata/annotations")
+        .and_then(|a| a.get(FP_ANN))
+        .and_then(serde_json::Value::as_str);
+    match stamped {
+        _ if !json_contains(live, desired) => Drift::Changed,
+        Some(stamped) if stamped == fp => Drift::None,
+        Some(stamped) if fingerprint_version(stamped) != FP_VERSION => Drift::Restamp,
+        _ => Drift::Changed,
     }
 }
 
-async fn reconsile_resource<K>(api: &Api<K>, desired: &K) -> Result<Outcome, Error>
+/// Applies `desired` unless the live object already carries its fingerprint *and* still holds
+/// every field we set. The second check catches out-of-band edits (e.g. `kubectl set image`)
+/// that leave our annotation intact. Drift is re-applied without `force`, so a field another
+/// manager has claimed surfaces as a conflict; force-sync and owner repair do force. Values the
+/// server normalises (such as `1000m` -> `1`) read as drift, but the apply then changes nothing
+/// and, with the `resourceVersion` unchanged, is reported as a no-op.
+async fn reconsile_resource<K>(
+    api: &Api<K>,
+    desired: &K,
+    opts: ApplyOptions<'_>,
+) -> Result<Outcome, Error>
 where
     K: Resource + std::fmt::Debug + Clone + serde::Serialize + DeserializeOwned,
+    K::DynamicType: Default,
 {
-    const FP_ANN: &str = "ml.jedimindtricks.example/desired-fingerprint";
+    reconsile_resource_with(api, desired, opts, &Default::default()).await
+}
+
+/// API groups served by Kubernetes itself, whose kinds accept a strategic merge patch.
+const BUILT_IN_GROUPS: &[&str] = &[
+    "",
+    "apps",
+    "autoscaling",
+    "batch",
+    "policy",
+    "networking.k8s.io",
+    "rbac.authorization.k8s.io",
+    "scheduling.k8s.io",
+    "storage.k8s.io",
+];
 
-    pub fn desired_fingerprint<T: Serialize>(t: &T) -> String {
-        let json = serde_json::to_string(t).unwrap_or_default();
+/// Whether the kinds of `group` are built in; everything else is a custom resource, which the
+/// API server only merge-patches.
+fn built_in(group: &str) -> bool {
+    BUILT_IN_GROUPS.contains(&group)
+}
 
-        let mut hasher = Sha256::new();
-        hasher.update(json.as_bytes());
+/// `patchStrategy: Merge`: creates `desired`, or patches the whole of it over the live object.
+/// Built-in kinds get a strategic merge patch, so lists such as containers and ports are merged
+/// by key; custom resources only take a JSON merge patch. No field ownership is tracked, so
+/// there is nothing to conflict with or force.
+async fn apply_merge<K>(
+    api: &Api<K>,
+    desired: &K,
+    exists: bool,
+    field_manager: &str,
+    strategic: bool,
+) -> Result<K, kube::Error>
+where
+    K: Resource + std::fmt::Debug + Clone + serde::Serialize + DeserializeOwned,
+{
+    if !exists {
+        let pp = PostParams {
+            field_manager: Some(field_manager.into()),
+            ..Default::default()
+        };
+        return retry_throttled(|| api.create(&pp, desired)).await;
+    }
+    let pp = PatchParams {
+        field_manager: Some(field_manager.into()),
+        ..Default::default()
+    };
+    let name = desired.name_any();
+    let patch = if strategic {
+        Patch::Strategic(desired)
+    } else {
+        Patch::Merge(desired)
+    };
+    retry_throttled(|| api.patch(&name, &pp, &patch)).await
+}
 
-        let hash = hasher.finalize();
-        format!("{:x}", hash)
+/// Refuses a child that another ModelDeployment owns, reporting it on ours as a `NameConflict`
+/// condition and Warning event. The next successful reconcile sets the condition to false.
+async fn name_conflict(opts: ApplyOptions<'_>, kind: String, name: String, owner: String) -> Error {
+    let error = Error::NameConflict { kind, name, owner };
+    let message = format!("{}; rename one of the two ModelDeployments", error);
+    tracing::warn!(
+        "{}/{}: {}",
+        opts.md.namespace().unwrap_or_default(),
+        opts.md.name_any(),
+        message
+    );
+    let reported = async {
+        emit_event(
+            opts.ctx,
+            opts.md,
+            "NameConflict",
+            &message,
+            EventType::Warning,
+        )
+        .await?;
+        let mut status = opts.md.status.clone().unwrap_or_default();
+        status.set_condition(Condition {
+            r#type: "NameConflict".into(),
+            status: "True".into(),
+            reason: Some("OwnedByAnotherModelDeployment".into()),
+            message: Some(message.clone()),
+            ..Default::default()
+        });
+        let ns = opts.md.namespace().unwrap_or_else(|| "default".into());
+        update_status(opts.ctx, opts.md, &ns, &status).await
+    };
+    match reported.await {
+        Ok(()) => error,
+        Err(e) => e,
     }
+}
+
+/// [`reconsile_resource`] for types resolved at runtime, such as a `DynamicObject` together
+/// with its discovered `ApiResource`.
+async fn reconsile_resource_with<K>(
+    api: &Api<K>,
+    desired: &K,
+    opts: ApplyOptions<'_>,
+    dt: &K::DynamicType,
+) -> Result<Outcome, Error>
+where
+    K: Resource + std::fmt::Debug + Clone + serde::Serialize + DeserializeOwned,
+{
+    let mut desired = desired.clone();
+    relabel_owner(opts.md, &mut desired);
+    add_cost_labels(opts.md, &mut desired);
+    let uid = opts.md.uid().unwrap_or_default();
 
     let name = desired.name_any();
-    let existing = api.get_opt(&name).await?;
-    let fp = desired_fingerprint(&desired);
-
-    if let Some(ref resource) = existing {
-        if let Some(ref anno) = resource.meta().annotations {
-            if let Some(old) = anno.get(FP_ANN) {
-                if old == &fp {
-                    return Ok(Outcome::NoOp);
-                }
+    let mut existing = retry_read(|| api.get_opt(&name))
+        .await
+        .map_err(Error::operation("read", K::kind(dt), &name))?;
+    let fp = desired_fingerprint(&desired)?;
+
+    let mut force = opts.force;
+    let mut adopted = false;
+    let mut replaced = false;
+    if let Some(resource) = &existing {
+        // child names only depend on the CR name, so another one can end up with ours
+        let other_owner = resource
+            .owner_references()
+            .iter()
+            .find(|o| {
+                o.kind == ModelDeployment::kind(&())
+                    && o.api_version == ModelDeployment::api_version(&())
+                    && o.uid != uid
+            })
+            .map(|o| o.name.clone())
+            .or_else(|| {
+                let theirs = resource.labels().get(OWNER_LABEL).filter(|&o| *o != uid)?;
+                Some(format!("with uid {}", theirs))
+            });
+        if let Some(owner) = other_owner {
+            let kind = K::kind(dt).into_owned();
+            return Err(name_conflict(opts, kind, name, owner).await);
+        }
+        match guard(opts.md, resource) {
+            Guard::Frozen => {
+                tracing::info!(
+                    "{} {} is frozen by {}; leaving it as it is",
+                    K::kind(dt),
+                    name,
+                    FREEZE_ANN
+                );
+                return Ok(Outcome::NoOp);
+            }
+            Guard::LostOwner => {
+                let kind = K::kind(dt);
+                tracing::warn!("{} {} lost its owner reference; restoring it", kind, name);
+                emit_event(
+                    opts.ctx,
+                    opts.md,
+                    "OwnerReferenceRepaired",
+                    &format!(
+                        "{} {} had lost its owner reference; restored it",
+                        kind, name
+                    ),
+                    EventType::Warning,
+                )
+                .await?;
+                force = true;
+            }
+            Guard::Check => {}
+        }
+        let live = serde_json::to_value(resource)?;
+        let want = serde_json::to_value(&desired)?;
+        if let Some((running, wanted)) = legacy_selector(&live, &want) {
+            // the shadow takes a share of real traffic during a canary, so it can't go away
+            if opts.md.spec.is_canary() {
+                tracing::info!(
+                    "Deferring the move of {} {} from role={} to role={} until the canary ends",
+                    K::kind(dt),
+                    name,
+                    running,
+                    wanted
+                );
+                return Ok(Outcome::NoOp);
+            }
+            replaced = delete_owned(api, opts.md, &name).await? != Outcome::NoOp;
+            if replaced {
+                emit_event(
+                    opts.ctx,
+                    opts.md,
+                    "LegacySelectorReplaced",
+                    &format!(
+                        "Recreating {} {} to move its selector from role={} to role={}",
+                        K::kind(dt),
+                        name,
+                        running,
+                        wanted
+                    ),
+                    EventType::Normal,
+                )
+                .await?;
+            }
+        }
+        if !force && !replaced {
+            let stamped = resource.annotations().get(FP_ANN);
+            match drift(&live, &want, &fp) {
+                Drift::None => return Ok(Outcome::NoOp),
+                Drift::Restamp => tracing::info!(
+                    "Re-stamping {} {} fingerprint from {} to {}",
+                    K::kind(dt),
+                    name,
+                    stamped.map_or("v1", |s| fingerprint_version(s)),
+                    FP_VERSION
+                ),
+                Drift::Changed if stamped == Some(&fp) => tracing::info!(
+                    "{} {} drifted from its desired spec; re-applying",
+                    K::kind(dt),
+                    name
+                ),
+                Drift::Changed => {}
             }
         }
+
+        // we either stamped it with a fingerprint or it points back at our owner
+        let managed = resource.annotations().contains_key(FP_ANN) || owned_by(opts.md, resource);
+        if !managed && opts.adoption == AdoptionPolicy::RejectConflict {
+            return Err(Error::AdoptionConflict {
+                kind: K::kind(dt).into_owned(),
+                name,
+            });
+        }
+        adopted = !managed || resource.annotations().contains_key(ADOPTED_ANN);
+    }
+    if replaced {
+        existing = None;
     }
 
-    let mut desired = desired.clone();
-    desired
+    let annotations = desired
         .meta_mut()
         .annotations
-        .get_or_insert_with(|| Default::default())
-        .insert(FP_ANN.into(), fp);
+        .get_or_insert_with(Default::default);
+    annotations.insert(FP_ANN.into(), fp);
+    if adopted {
+        annotations.insert(ADOPTED_ANN.into(), "true".into());
+    }
 
-    let pp = PatchParams::apply("model-operator");
-    api.patch(&name, &pp, &Patch::Apply(&desired)).await?;
+    audit::record(&opts.ctx.config, &K::kind(dt), &desired)?;
 
-    Ok(if existing.is_none() {
-        Outcome::Created
+    let applied = if opts.merge {
+        let strategic = built_in(&K::group(dt));
+        apply_merge(
+            api,
+            &desired,
+            existing.is_some(),
+            opts.field_manager,
+            strategic,
+        )
+        .await
     } else {
-        Outcome::Updated
+        let mut pp = PatchParams::apply(opts.field_manager);
+        if force {
+            pp = pp.force();
+        }
+        let patch = Patch::Apply(&desired);
+        match retry_throttled(|| api.patch(&name, &pp, &patch)).await {
+            Err(kube::Error::Api(resp)) if resp.code == 409 && opts.takes_over(&K::kind(dt)) => {
+                let kind = K::kind(dt);
+                tracing::warn!("Forcing ownership of {} {}: {}", kind, name, resp.message);
+                emit_event(
+                    opts.ctx,
+                    opts.md,
+                    "ForcedTakeover",
+                    &format!(
+                        "Took over fields of {} {} from another field manager: {}",
+                        kind, name, resp.message
+                    ),
+                    EventType::Warning,
+                )
+                .await?;
+                let pp = pp.clone().force();
+                retry_throttled(|| api.patch(&name, &pp, &patch)).await
+            }
+            result => result,
+        }
+    };
+    let applied = match applied {
+        Ok(applied) => applied,
+        Err(e) => {
+            // the ModelDeployment hears about it through `with_event`; this is for the child itself
+            if let Some(resource) = &existing {
+                let _ = emit_event_for(
+                    opts.ctx,
+                    &resource.object_ref(dt),
+                    "ApplyFailed",
+                    &format!(
+                        "ModelDeployment {} could not apply this object: {}",
+                        opts.md.name_any(),
+                        e
+                    ),
+                    EventType::Warning,
+                )
+                .await;
+            }
+            return Err(Error::operation("apply", K::kind(dt), name)(e));
+        }
+    };
+
+    Ok(match &existing {
+        None => Outcome::Created,
+        // the server dropped the apply as a no-op, e.g. only normalised values differed
+        Some(live) if live.resource_version() == applied.resource_version() => Outcome::NoOp,
+        Some(_) => Outcome::Updated,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        crd::testing::model_deployment,
+        event::testing::{self, FakeApi},
+    };
+
+    fn spec(spec: serde_json::Value) -> ModelDeploymentSpec {
+        model_deployment(spec).spec
+    }
+
+    #[test]
+    fn replicas_follow_the_precedence_order() {
+        let live = |s: &ModelDeploymentSpec| effective_replicas(s, &DeploymentType::Live);
+        let shadow = |s: &ModelDeploymentSpec| effective_replicas(s, &DeploymentType::Shadow);
+
+        // default, then the variant's own count
+        assert_eq!(live(&spec(json!({"live": {"image": "m:1"}}))), Some(1));
+        assert_eq!(
+            live(&spec(json!({"live": {"image": "m:1", "replicas": 3}}))),
+            Some(3)
+        );
+        // spec.replicas beats the variant, but only for live
+        let overridden = spec(json!({
+            "live": {"image": "m:1", "replicas": 3},
+            "shadow": {"image": "m:2", "replicas": 2},
+            "replicas": 5,
+        }));
+        assert_eq!(live(&overridden), Some(5));
+        assert_eq!(shadow(&overridden), Some(2));
+        // an HPA takes live over entirely
+        let autoscaled = spec(json!({
+            "live": {"image": "m:1", "replicas": 3},
+            "shadow": {"image": "m:2", "replicas": 2},
+            "replicas": 5,
+            "autoscaling": {"enabled": true, "minReplicas": 2, "maxReplicas": 4},
+        }));
+        assert_eq!(live(&autoscaled), None);
+        assert_eq!(shadow(&autoscaled), Some(2));
+        // suspend beats everything, the HPA included
+        let suspended = spec(json!({
+            "live": {"image": "m:1", "replicas": 3},
+            "shadow": {"image": "m:2", "replicas": 2},
+            "replicas": 5,
+            "autoscaling": {"enabled": true, "minReplicas": 2, "maxReplicas": 4},
+            "suspend": true,
+        }));
+        assert_eq!(live(&suspended), Some(0));
+        assert_eq!(shadow(&suspended), Some(0));
+        assert!(!hpa_enabled(&suspended));
+    }
+
+    #[test]
+    fn missing_shadow_has_no_replicas() {
+        let spec = spec(json!({"live": {"image": "m:1"}}));
+        assert_eq!(effective_replicas(&spec, &DeploymentType::Shadow), Some(0));
+    }
+
+    #[test]
+    fn pause_rollout_sets_deployment_paused() {
+        let md = model_deployment(json!({
+            "live": {"image": "m:2", "pauseRollout": true},
+            "shadow": {"image": "m:3"},
+        }));
+        let build = |variant: &ModelVariant, role| {
+            desired_deployment(&md, "model-x", "model", variant, Some(2), role)
+                .spec
+                .unwrap()
+        };
+        let live = build(&md.spec.live, DeploymentType::Live);
+        assert_eq!(live.paused, Some(true));
+        // the template still carries the change, waiting to be rolled out
+        assert_eq!(
+            live.template.spec.unwrap().containers[0].image.as_deref(),
+            Some("m:2")
+        );
+        assert_eq!(live.replicas, Some(2));
+        // per variant, and left out rather than `false` when unset
+        let shadow = build(md.spec.shadow.as_ref().unwrap(), DeploymentType::Shadow);
+        assert_eq!(shadow.paused, None);
+    }
+
+    #[tokio::test]
+    async fn paused_rollout_is_not_progressing() {
+        let condition = |status: &ModelDeploymentStatus, r#type: &str| {
+            let c = status
+                .conditions
+                .iter()
+                .flatten()
+                .find(|c| c.r#type == r#type);
+            let c = c.unwrap().clone();
+            (c.status, c.reason.unwrap_or_default())
+        };
+        let child = |available| {
+            Some(ChildStatus {
+                available_replicas: Some(available),
+                ..Default::default()
+            })
+        };
+
+        let paused = spec(json!({"live": {"image": "m:1", "replicas": 2, "pauseRollout": true}}));
+        let status =
+            compute_model_deployment_status(&paused, &child(1), &None, Ok(()), false).await;
+        assert_eq!(
+            condition(&status, "Progressing"),
+            ("False".into(), "RolloutPaused".into())
+        );
+        assert_eq!(condition(&status, "Ready").0, "False");
+
+        let running = spec(json!({"live": {"image": "m:1", "replicas": 2}}));
+        let status =
+            compute_model_deployment_status(&running, &child(1), &None, Ok(()), false).await;
+        assert_eq!(
+            condition(&status, "Progressing"),
+            ("True".into(), "Reconciling".into())
+        );
+    }
+
+    #[test]
+    fn network_policy_admits_listed_sources_on_every_port() {
+        let policy = |spec| {
+            let md = model_deployment(spec);
+            let config = md.spec.network_policy.clone().unwrap();
+            desired_network_policy(&md, "model-np", "model", &config)
+                .spec
+                .unwrap()
+                .ingress
+                .unwrap()
+        };
+
+        let rules = policy(json!({
+            "live": {"image": "m:1", "port": 8080},
+            "shadow": {"image": "m:2", "port": 9090},
+            "extraPorts": [{"name": "grpc", "containerPort": 9000}],
+            "networkPolicy": {"fromNamespaces": ["traefik"]},
+        }));
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].from.as_ref().unwrap().len(), 1);
+        let ports: Vec<IntOrString> = rules[0]
+            .ports
+            .iter()
+            .flatten()
+            .filter_map(|p| p.port.clone())
+            .collect();
+        assert_eq!(
+            ports,
+            [
+                IntOrString::Int(8080),
+                IntOrString::String("grpc".into()),
+                IntOrString::Int(9090),
+            ]
+        );
+
+        // no sources is no rule at all; an empty `from` would admit everyone
+        let rules = policy(json!({"live": {"image": "m:1"}, "networkPolicy": {}}));
+        assert!(rules.is_empty());
+    }
+
+    #[test]
+    fn drift_is_detected_until_restored() {
+        let desired = json!({
+            "metadata": {"name": "model-live"},
+            "spec": {"template": {"spec": {"containers": [{"name": "model", "image": "m:1"}]}}},
+        });
+        let fp = desired_fingerprint(&desired).unwrap();
+        let mut live = desired.clone();
+        live["metadata"]["annotations"] = json!({ FP_ANN: fp });
+        // fields the server fills in are not drift
+        live["metadata"]["resourceVersion"] = json!("7");
+        live["spec"]["replicas"] = json!(1);
+        assert_eq!(drift(&live, &desired, &fp), Drift::None);
+
+        let image = "/spec/template/spec/containers/0/image";
+        *live.pointer_mut(image).unwrap() = json!("m:2");
+        assert_eq!(drift(&live, &desired, &fp), Drift::Changed);
+
+        *live.pointer_mut(image).unwrap() = json!("m:1");
+        assert_eq!(drift(&live, &desired, &fp), Drift::None);
+        assert_eq!(drift(&live, &desired, "v2:other"), Drift::Changed);
+    }
+
+    #[test]
+    fn unversioned_fingerprint_is_restamped_without_a_rollout() {
+        let md = model_deployment(json!({"live": {"image": "m:1"}}));
+        let desired = desired_deployment(
+            &md,
+            "model-live",
+            "model",
+            &md.spec.live,
+            Some(1),
+            DeploymentType::Live,
+        );
+        let fp = desired_fingerprint(&desired).unwrap();
+        let want = serde_json::to_value(&desired).unwrap();
+
+        // stamped before fingerprints were versioned: a bare hash
+        let mut live = want.clone();
+        let bare = fp.split_once(':').unwrap().1;
+        live["metadata"]["annotations"] = json!({ FP_ANN: bare });
+        assert_eq!(fingerprint_version(bare), "v1");
+        assert_eq!(drift(&live, &want, &fp), Drift::Restamp);
+        // the stamp lives on the Deployment, not its pod template, so rewriting it rolls nothing
+        let template = desired.spec.unwrap().template.metadata.unwrap();
+        assert!(
+            !template
+                .annotations
+                .unwrap_or_default()
+                .contains_key(FP_ANN)
+        );
+
+        // once re-stamped it is left alone
+        live["metadata"]["annotations"] = json!({ FP_ANN: fp });
+        assert_eq!(drift(&live, &want, &fp), Drift::None);
+        // an out-of-date hash of the current version is a spec change, not a migration
+        live["metadata"]["annotations"] = json!({ FP_ANN: format!("{}:0", FP_VERSION) });
+        assert_eq!(drift(&live, &want, &fp), Drift::Changed);
+    }
+
+    #[test]
+    fn traffic_distribution_is_dropped_only_on_old_servers() {
+        let server = |minor: &str| Info {
+            major: "1".into(),
+            minor: minor.into(),
+            git_version: format!("v1.{}.0", minor),
+            ..Default::default()
+        };
+        let preferred =
+            || spec(json!({"live": {"image": "m:1"}, "trafficDistribution": "PreferClose"}));
+
+        let mut old = preferred();
+        let version = strip_traffic_distribution(Some(&server("30+")), &mut old);
+        assert_eq!(version.as_deref(), Some("v1.30+.0"));
+        assert!(old.traffic_distribution.is_none());
+
+        let mut current = preferred();
+        assert!(strip_traffic_distribution(Some(&server("31")), &mut current).is_none());
+        assert!(current.traffic_distribution.is_some());
+
+        let mut unknown = preferred();
+        assert!(strip_traffic_distribution(None, &mut unknown).is_none());
+        assert!(unknown.traffic_distribution.is_some());
+    }
+
+    #[test]
+    fn unserializable_objects_fail_instead_of_sharing_a_fingerprint() {
+        // JSON map keys must be strings
+        let unserializable = BTreeMap::from([(vec![1u8], 1)]);
+        assert!(matches!(
+            desired_fingerprint(&unserializable),
+            Err(Error::Serialize(_))
+        ));
+        assert!(desired_fingerprint(&BTreeMap::from([("a", 1)])).is_ok());
+    }
+
+    #[test]
+    fn rollout_timing_is_overridden_per_variant() {
+        let md = model_deployment(json!({
+            "live": {"image": "m:1", "progressDeadlineSeconds": 120},
+            "shadow": {"image": "m:2", "minReadySeconds": 5},
+            "minReadySeconds": 10,
+            "progressDeadlineSeconds": 900,
+        }));
+        let build = |variant: &ModelVariant, role| {
+            desired_deployment(&md, "model-x", "model", variant, Some(1), role)
+                .spec
+                .unwrap()
+        };
+
+        let live = build(&md.spec.live, DeploymentType::Live);
+        assert_eq!(live.min_ready_seconds, Some(10));
+        assert_eq!(live.progress_deadline_seconds, Some(120));
+
+        let shadow = build(md.spec.shadow.as_ref().unwrap(), DeploymentType::Shadow);
+        assert_eq!(shadow.min_ready_seconds, Some(5));
+        assert_eq!(shadow.progress_deadline_seconds, Some(900));
+    }
+
+    #[test]
+    fn rollout_timing_is_left_to_kubernetes_when_unset() {
+        let md = model_deployment(json!({"live": {"image": "m:1"}}));
+        let live = desired_deployment(
+            &md,
+            "model-x",
+            "model",
+            &md.spec.live,
+            Some(1),
+            DeploymentType::Live,
+        )
+        .spec
+        .unwrap();
+        assert_eq!(live.min_ready_seconds, None);
+        assert_eq!(live.progress_deadline_seconds, None);
+    }
+
+    #[test]
+    fn route_priority_and_sticky_cookie_reach_every_route() {
+        let md = model_deployment(json!({
+            "live": {"image": "m:1"},
+            "shadow": {"image": "m:2"},
+            "trafficMirror": true,
+            "mirrorMatch": "Header(`X-Mirror`, `1`)",
+            "routePriority": 100,
+            "stickyCookie": {"name": "model", "sameSite": "strict", "httpOnly": true},
+        }));
+        let route = RouteSpec {
+            entry_point: "web".into(),
+            tls: None,
+        };
+        let routes = desired_ingress_route(&md, "model", "default", &route)
+            .spec
+            .routes;
+
+        assert_eq!(routes.len(), 2);
+        // the mirrored route ranks first
+        assert_eq!(routes[0].priority, Some(101));
+        assert_eq!(routes[1].priority, Some(100));
+        for route in &routes {
+            let services = route.services.as_ref().unwrap();
+            let cookie = services[0]
+                .sticky
+                .as_ref()
+                .unwrap()
+                .cookie
+                .as_ref()
+                .unwrap();
+            assert_eq!(cookie.name.as_deref(), Some("model"));
+            assert_eq!(cookie.http_only, Some(true));
+            assert_eq!(cookie.secure, None);
+            assert!(matches!(
+                cookie.same_site,
+                Some(IngressRouteRoutesServicesStickyCookieSameSite::Strict)
+            ));
+        }
+    }
+
+    #[test]
+    fn only_built_in_kinds_are_strategic_merge_patched() {
+        assert!(built_in(&Deployment::group(&())));
+        assert!(built_in(&Service::group(&())));
+        assert!(built_in(&HorizontalPodAutoscaler::group(&())));
+        assert!(built_in(&NetworkPolicy::group(&())));
+        assert!(!built_in(&IngressRoute::group(&())));
+        assert!(!built_in("monitoring.coreos.com"));
+        // a CRD in a k8s.io group is still a custom resource
+        assert!(!built_in("gateway.networking.k8s.io"));
+    }
+
+    #[test]
+    fn stripped_owner_reference_is_restored() {
+        let md = model_deployment(json!({"live": {"image": "m:1"}}));
+        let mut desired = desired_deployment(
+            &md,
+            "model-live",
+            "model",
+            &md.spec.live,
+            Some(1),
+            DeploymentType::Live,
+        );
+        let fp = desired_fingerprint(&desired).unwrap();
+        desired.annotations_mut().insert(FP_ANN.into(), fp);
+
+        // edited by hand: still stamped, but no longer pointing at the ModelDeployment
+        let mut existing = desired.clone();
+        existing.metadata.owner_references = None;
+        assert_eq!(guard(&md, &existing), Guard::LostOwner);
+
+        // what the repair applies carries the reference again, so the next pass checks as usual
+        assert!(owned_by(&md, &desired));
+        assert_eq!(guard(&md, &desired), Guard::Check);
+
+        // a child we never stamped is adopted, not repaired
+        existing.annotations_mut().remove(FP_ANN);
+        assert_eq!(guard(&md, &existing), Guard::Check);
+    }
+
+    #[test]
+    fn frozen_child_is_left_alone_even_when_it_drifted() {
+        let md = model_deployment(json!({"live": {"image": "m:2"}}));
+        let desired = desired_deployment(
+            &md,
+            "model-live",
+            "model",
+            &md.spec.live,
+            Some(1),
+            DeploymentType::Live,
+        );
+        let fp = desired_fingerprint(&desired).unwrap();
+
+        // hand-edited to another image and frozen, with an older release's fingerprint
+        let old = model_deployment(json!({"live": {"image": "m:1"}}));
+        let mut existing = desired_deployment(
+            &old,
+            "model-live",
+            "model",
+            &old.spec.live,
+            Some(1),
+            DeploymentType::Live,
+        );
+        let stale = desired_fingerprint(&existing).unwrap();
+        existing.annotations_mut().insert(FP_ANN.into(), stale);
+        existing
+            .annotations_mut()
+            .insert(FREEZE_ANN.into(), "true".into());
+
+        let live = serde_json::to_value(&existing).unwrap();
+        let want = serde_json::to_value(&desired).unwrap();
+        assert_eq!(drift(&live, &want, &fp), Drift::Changed);
+        assert_eq!(guard(&md, &existing), Guard::Frozen);
+
+        // frozen wins over a lost owner reference too
+        existing.metadata.owner_references = None;
+        assert_eq!(guard(&md, &existing), Guard::Frozen);
+
+        // only "true" freezes
+        existing
+            .annotations_mut()
+            .insert(FREEZE_ANN.into(), "false".into());
+        assert_eq!(guard(&md, &existing), Guard::LostOwner);
+    }
+
+    #[test]
+    fn legacy_capitalised_selector_is_detected() {
+        let md = model_deployment(json!({"live": {"image": "m:1"}, "shadow": {"image": "m:2"}}));
+        let shadow = md.spec.shadow.as_ref().unwrap();
+        let desired = desired_deployment(
+            &md,
+            "model-shadow",
+            "model",
+            shadow,
+            Some(1),
+            DeploymentType::Shadow,
+        );
+        let want = serde_json::to_value(&desired).unwrap();
+
+        // as an older release left it
+        let mut live = want.clone();
+        live["spec"]["selector"]["matchLabels"]["role"] = json!("Shadow");
+        assert_eq!(
+            legacy_selector(&live, &want),
+            Some(("Shadow".into(), "shadow".into()))
+        );
+
+        // already migrated, or a different role altogether, is left to the apply
+        assert_eq!(legacy_selector(&want, &want), None);
+        live["spec"]["selector"]["matchLabels"]["role"] = json!("live");
+        assert_eq!(legacy_selector(&live, &want), None);
+    }
+
+    #[test]
+    fn frozen_deployment_is_not_reported_as_updated() {
+        let fake = FakeApi::default();
+        let old = model_deployment(json!({"live": {"image": "m:1"}}));
+        let name = old.spec.deployment_name("model", "live");
+        let mut frozen = desired_deployment(
+            &old,
+            &name,
+            "model",
+            &old.spec.live,
+            Some(1),
+            DeploymentType::Live,
+        );
+        frozen.metadata.owner_references = old.controller_owner_ref(&()).map(|o| vec![o]);
+        frozen
+            .annotations_mut()
+            .insert(FREEZE_ANN.into(), "true".into());
+        let path = format!("/apis/apps/v1/namespaces/ns/deployments/{}", name);
+        fake.insert(&path, serde_json::to_value(&frozen).unwrap());
+        let md = model_deployment(json!({"live": {"image": "m:2"}}));
+        fake.insert(
+            "/apis/ml.jedimindtricks.example/v1alpha1/namespaces/ns/modeldeployments/model",
+            serde_json::to_value(&md).unwrap(),
+        );
+
+        let server = fake.clone();
+        testing::run(async move { reconsile_inner(Arc::new(md), Arc::new(server.ctx())).await })
+            .unwrap();
+
+        let reasons = fake.event_reasons();
+        assert!(!reasons.contains(&"ImageUpdated".into()), "{:?}", reasons);
+        assert!(
+            !reasons.contains(&"LiveDeploymentCreated".into()),
+            "{:?}",
+            reasons
+        );
+        let writes = fake.writes();
+        assert!(writes.iter().all(|(_, p, _)| *p != path), "{:?}", writes);
+        let status: Vec<&serde_json::Value> = writes
+            .iter()
+            .filter(|(_, p, _)| p.ends_with("/modeldeployments/model/status"))
+            .map(|(_, _, body)| body)
+            .collect();
+        assert!(!status.is_empty());
+        assert!(
+            status.iter().all(|s| s
+                .pointer("/status/lastImageChange")
+                .is_none_or(serde_json::Value::is_null)),
+            "{:?}",
+            status
+        );
+    }
+
+    #[test]
+    fn blocked_downgrade_holds_only_the_live_image() {
+        let fake = FakeApi::default();
+        let running = model_deployment(json!({"live": {"image": "m:2"}}));
+        let name = running.spec.deployment_name("model", "live");
+        let mut dep = desired_deployment(
+            &running,
+            &name,
+            "model",
+            &running.spec.live,
+            Some(1),
+            DeploymentType::Live,
+        );
+        dep.metadata.owner_references = running.controller_owner_ref(&()).map(|o| vec![o]);
+        let path = format!("/apis/apps/v1/namespaces/ns/deployments/{}", name);
+        fake.insert(&path, serde_json::to_value(&dep).unwrap());
+        let md = model_deployment(json!({
+            "live": {"image": "m:1", "env": [{"name": "A", "value": "1"}]},
+            "blockImageDowngrades": true,
+        }));
+        fake.insert(
+            "/apis/ml.jedimindtricks.example/v1alpha1/namespaces/ns/modeldeployments/model",
+            serde_json::to_value(&md).unwrap(),
+        );
+
+        let server = fake.clone();
+        let action =
+            testing::run(
+                async move { reconsile_inner(Arc::new(md), Arc::new(server.ctx())).await },
+            )
+            .unwrap();
+
+        // the pass ran to its normal requeue, applying everything but the older image
+        assert_ne!(action, Action::await_change());
+        let writes = fake.writes();
+        let (_, _, applied) = writes.iter().find(|(_, p, _)| *p == path).unwrap();
+        let container = &applied["spec"]["template"]["spec"]["containers"][0];
+        assert_eq!(container["image"], "m:2");
+        assert_eq!(container["env"][0]["name"], "A");
+        let (_, _, status) = writes
+            .iter()
+            .rfind(|(_, p, _)| p.ends_with("/modeldeployments/model/status"))
+            .unwrap();
+        let conditions = status["status"]["conditions"].as_array().unwrap();
+        assert!(
+            conditions
+                .iter()
+                .any(|c| c["type"] == "ImageDowngradeBlocked" && c["status"] == "True"),
+            "{:?}",
+            conditions
+        );
+        assert!(
+            fake.event_reasons()
+                .contains(&"ImageDowngradeBlocked".into())
+        );
+    }
+
+    #[test]
+    fn missing_secrets_are_warned_about_once() {
+        let fake = FakeApi::default();
+        let mut md = model_deployment(json!({
+            "live": {"image": "m:1", "secretRefs": [{"name": "hf-token"}]},
+        }));
+        let path = "/apis/ml.jedimindtricks.example/v1alpha1/namespaces/ns/modeldeployments/model";
+        fake.insert(path, serde_json::to_value(&md).unwrap());
+        let pass = |md: &ModelDeployment| {
+            let (server, md) = (fake.clone(), md.clone());
+            testing::run(
+                async move { reconsile_inner(Arc::new(md), Arc::new(server.ctx())).await },
+            )
+            .unwrap();
+            let writes = fake.writes();
+            let (_, _, status) = writes
+                .iter()
+                .rfind(|(_, p, _)| p.ends_with("/modeldeployments/model/status"))
+                .unwrap();
+            serde_json::from_value::<ModelDeploymentStatus>(status["status"].clone()).unwrap()
+        };
+        let missing = |status: &ModelDeploymentStatus| {
+            status
+                .conditions
+                .iter()
+                .flatten()
+                .any(|c| c.r#type == "SecretsMissing")
+        };
+        let warned = || {
+            fake.event_reasons()
+                .iter()
+                .filter(|r| *r == "SecretsMissing")
+                .count()
+        };
+
+        md.status = Some(pass(&md));
+        assert!(missing(md.status.as_ref().unwrap()));
+        assert_eq!(warned(), 1);
+
+        // still missing: reported in status, but no second event
+        md.status = Some(pass(&md));
+        assert!(missing(md.status.as_ref().unwrap()));
+        assert_eq!(warned(), 1);
+
+        fake.insert(
+            "/api/v1/namespaces/ns/secrets/hf-token",
+            json!({"apiVersion": "v1", "kind": "Secret", "metadata": {"name": "hf-token"}}),
+        );
+        assert!(!missing(&pass(&md)));
+    }
+
+    #[test]
+    fn endpoints_follow_the_match_rule_hosts() {
+        assert_eq!(
+            rule_hosts("Host(`m.example.com`) && Path(`/predict`)"),
+            ["m.example.com"]
+        );
+        assert_eq!(
+            rule_hosts("Host(`a.example`) || (Host(`b.example`) && Method(`POST`))"),
+            ["a.example", "b.example"]
+        );
+        assert_eq!(
+            rule_hosts("Host(`a.example`, `b.example`)"),
+            ["a.example", "b.example"]
+        );
+        assert!(rule_hosts("PathPrefix(`/v1`)").is_empty());
+        assert!(rule_hosts("HostRegexp(`^.+\\.example$`) && ClientHost(`x`)").is_empty());
+    }
+}
@@ -0,0 +1,44 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Longest a settled object goes without its children being re-applied. Children aren't
+/// watched, so this pass is what undoes drift such as a deleted Service or an edited Deployment.
+pub const FULL_RESYNC: Duration = Duration::from_secs(300);
+
+struct Synced {
+    generation: Option<i64>,
+    at: Instant,
+}
+
+/// When each object last had every child applied, and at which generation, keyed by
+/// `namespace/name`. Lets periodic resyncs of settled objects skip the per-child reads.
+#[derive(Clone, Default)]
+pub struct SyncTracker(Arc<Mutex<HashMap<String, Synced>>>);
+
+impl SyncTracker {
+    pub fn record(&self, key: &str, generation: Option<i64>) {
+        self.0.lock().unwrap().insert(
+            key.into(),
+            Synced {
+                generation,
+                at: Instant::now(),
+            },
+        );
+    }
+
+    /// Whether `generation` was fully applied within [`FULL_RESYNC`].
+    pub fn is_fresh(&self, key: &str, generation: Option<i64>) -> bool {
+        self.0
+            .lock()
+            .unwrap()
+            .get(key)
+            .is_some_and(|s| s.generation == generation && s.at.elapsed() < FULL_RESYNC)
+    }
+
+    pub fn forget(&self, key: &str) {
+        self.0.lock().unwrap().remove(key);
+    }
+}
@@ -0,0 +1,218 @@
+use std::{future::Future, time::Duration};
+
+use http::{Response, StatusCode, header::RETRY_AFTER};
+use http_body_util::BodyExt;
+use kube::{
+    client::{Body, DynBody},
+    core::ErrorResponse,
+};
+use rand::Rng;
+use serde_json::Value;
+use thiserror::Error;
+use tower::BoxError;
+
+const MAX_ATTEMPTS: u32 = 4;
+const BASE_DELAY: Duration = Duration::from_millis(100);
+const MAX_DELAY: Duration = Duration::from_secs(2);
+
+/// Wait after a 429 that did not say how long to wait.
+const RATE_LIMITED_DELAY: Duration = Duration::from_secs(1);
+/// Longest server-requested wait we honour, so a bogus value cannot stall a reconcile.
+const MAX_RATE_LIMITED_DELAY: Duration = Duration::from_secs(30);
+
+/// A `429 Too Many Requests` along with the wait the server asked for. kube keeps only the
+/// message, reason and code of an error body and none of its headers, so [`surface_retry_after`]
+/// fails the request with this instead, and kube hands it back as `kube::Error::Service`.
+#[derive(Debug, Error)]
+#[error("{status}")]
+pub struct Throttled {
+    pub status: ErrorResponse,
+    pub retry_after: Option<Duration>,
+}
+
+/// Only server-side hiccups are worth retrying; 4xx responses won't change on a second try.
+pub fn is_transient(err: &kube::Error) -> bool {
+    matches!(err, kube::Error::Api(resp) if matches!(resp.code, 500 | 502 | 503 | 504))
+}
+
+/// The server-suggested wait for a `429 Too Many Requests`.
+pub fn retry_after(err: &kube::Error) -> Option<Duration> {
+    match err {
+        kube::Error::Api(resp) if resp.code == 429 => Some(RATE_LIMITED_DELAY),
+        kube::Error::Service(e) => e.downcast_ref::<Throttled>().map(|t| {
+            t.retry_after
+                .map_or(RATE_LIMITED_DELAY, |d| d.min(MAX_RATE_LIMITED_DELAY))
+        }),
+        _ => None,
+    }
+}
+
+/// Client middleware that turns a 429 into a [`Throttled`] error carrying its requested wait
+/// (`details.retryAfterSeconds`, else a `Retry-After` in seconds) for [`retry_after`].
+pub async fn surface_retry_after(
+    res: Response<Box<DynBody>>,
+) -> Result<Response<Box<DynBody>>, BoxError> {
+    if res.status() != StatusCode::TOO_MANY_REQUESTS {
+        return Ok(res);
+    }
+    let header = res
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .map(String::from);
+    let (parts, body) = res.into_parts();
+    let bytes = body.collect().await?.to_bytes().to_vec();
+    match throttled(&bytes, header.as_deref()) {
+        Some(throttled) => Err(Box::new(throttled)),
+        // not a Status body: let kube report it as it would any other error response
+        None => {
+            let body: Box<DynBody> = Box::new(Body::from(bytes).map_err(BoxError::from));
+            Ok(Response::from_parts(parts, body))
+        }
+    }
+}
+
+fn throttled(body: &[u8], header: Option<&str>) -> Option<Throttled> {
+    let status: Value = serde_json::from_slice(body).ok()?;
+    let seconds = status
+        .pointer("/details/retryAfterSeconds")
+        .and_then(Value::as_u64)
+        .or_else(|| header?.trim().parse().ok());
+    Some(Throttled {
+        status: serde_json::from_value(status).ok()?,
+        retry_after: seconds.map(Duration::from_secs),
+    })
+}
+
+/// Retries an API read on transient errors with exponential backoff and full jitter, and on
+/// throttling after the server-suggested delay.
+pub async fn retry_read<T, F, Fut>(op: F) -> Result<T, kube::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, kube::Error>>,
+{
+    retry(op, true).await
+}
+
+/// Retries a write only when it was throttled: a 429 is rejected before it is processed, so
+/// resending is safe, whereas a 5xx may have been applied.
+pub async fn retry_throttled<T, F, Fut>(op: F) -> Result<T, kube::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, kube::Error>>,
+{
+    retry(op, false).await
+}
+
+async fn retry<T, F, Fut>(mut op: F, transient: bool) -> Result<T, kube::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, kube::Error>>,
+{
+    let mut attempt = 0;
+    loop {
+        match op().await {
+            Err(e)
+                if (retry_after(&e).is_some() || transient && is_transient(&e))
+                    && attempt + 1 < MAX_ATTEMPTS =>
+            {
+                let delay = retry_after(&e).unwrap_or_else(|| backoff(attempt));
+                tracing::warn!(
+                    "Transient API error (attempt {}/{}), retrying in {:?}: {}",
+                    attempt + 1,
+                    MAX_ATTEMPTS,
+                    delay,
+                    e
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            res => return res,
+        }
+    }
+}
+
+fn backoff(attempt: u32) -> Duration {
+    let cap = BASE_DELAY.saturating_mul(1 << attempt).min(MAX_DELAY);
+    Duration::from_millis(rand::rng().random_range(0..=cap.as_millis() as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::Request;
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use kube::{Api, Client};
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    /// What a client with [`surface_retry_after`] in front returns for a 429 carrying `status`
+    /// and `header`.
+    async fn throttled(status: Value, header: Option<&str>) -> kube::Error {
+        let status = status.to_string();
+        let header = header.map(String::from);
+        let service = tower::service_fn(move |_: Request<Body>| {
+            let mut res = Response::builder().status(StatusCode::TOO_MANY_REQUESTS);
+            if let Some(header) = &header {
+                res = res.header(RETRY_AFTER, header);
+            }
+            let body: Box<DynBody> =
+                Box::new(Body::from(status.clone().into_bytes()).map_err(BoxError::from));
+            std::future::ready(Ok::<_, BoxError>(res.body(body).unwrap()))
+        })
+        .and_then(surface_retry_after);
+        let api: Api<ConfigMap> = Api::namespaced(Client::new(service, "default"), "ns");
+        api.get("config").await.unwrap_err()
+    }
+
+    #[tokio::test]
+    async fn retry_after_follows_the_server() {
+        let status = json!({
+            "status": "Failure",
+            "message": "Too many requests, please try again later.",
+            "reason": "TooManyRequests",
+            "code": 429,
+        });
+        let mut with_details = status.clone();
+        with_details["details"] = json!({"retryAfterSeconds": 5});
+        // the body's details win over the header
+        let err = throttled(with_details, Some("2")).await;
+        assert_eq!(retry_after(&err), Some(Duration::from_secs(5)));
+
+        let err = throttled(status.clone(), Some("3")).await;
+        assert_eq!(retry_after(&err), Some(Duration::from_secs(3)));
+        // the server's message reaches logs and events as it was sent
+        assert!(
+            err.to_string()
+                .ends_with("Too many requests, please try again later.: TooManyRequests")
+        );
+
+        // nothing usable: an HTTP date, or no hint at all
+        let err = throttled(status.clone(), Some("Wed, 21 Oct 2026 07:28:00 GMT")).await;
+        assert_eq!(retry_after(&err), Some(RATE_LIMITED_DELAY));
+        let err = throttled(status.clone(), None).await;
+        assert_eq!(retry_after(&err), Some(RATE_LIMITED_DELAY));
+
+        let err = throttled(status, Some("3600")).await;
+        assert_eq!(retry_after(&err), Some(MAX_RATE_LIMITED_DELAY));
+    }
+
+    #[test]
+    fn only_throttling_is_retried_after_a_delay() {
+        let err = kube::Error::Api(ErrorResponse {
+            status: "Failure".into(),
+            message: "conflict".into(),
+            reason: "Conflict".into(),
+            code: 409,
+        });
+        assert_eq!(retry_after(&err), None);
+        // a 429 whose body was not a Status still gets the default wait
+        let err = kube::Error::Api(ErrorResponse {
+            status: "Failure".into(),
+            message: "too many requests".into(),
+            reason: "TooManyRequests".into(),
+            code: 429,
+        });
+        assert_eq!(retry_after(&err), Some(RATE_LIMITED_DELAY));
+    }
+}
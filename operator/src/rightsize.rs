@@ -0,0 +1,141 @@
+use k8s_openapi::chrono::Utc;
+use kube::{
+    Api, Client, ResourceExt,
+    api::{ApiResource, DynamicObject, GroupVersionKind, ListParams},
+};
+
+use crate::{
+    crd::{ModelDeployment, ResourceRecommendation},
+    retry::retry_read,
+};
+
+/// Headroom over the highest usage seen, so a recommendation isn't sized for a quiet minute.
+const HEADROOM: f64 = 1.25;
+const CPU_STEP_MILLIS: f64 = 10.0;
+const MEMORY_STEP_BYTES: f64 = (1 << 20) as f64;
+
+/// With `rightSize` on and `resources` unset, samples the live model container's usage from
+/// `metrics.k8s.io` and recommends requests `HEADROOM` above the highest sample. Samples
+/// accumulate while the live release stays the same, so the recommendation only grows until
+/// the next rollout. Nothing is applied; without a metrics server the last one is kept.
+pub async fn recommend(
+    client: &Client,
+    md: &ModelDeployment,
+    ns: &str,
+    base_name: &str,
+) -> Option<ResourceRecommendation> {
+    let spec = &md.spec;
+    if !spec.right_size || spec.resources.is_some() {
+        return None;
+    }
+    let release = spec.live.release();
+    let previous = md
+        .status
+        .as_ref()
+        .and_then(|s| s.resource_recommendation.clone())
+        .filter(|r| r.release == release);
+
+    let container = spec.deployment_name(base_name, "live");
+    let (cpu, memory) = match peak_usage(client, ns, base_name, &container).await {
+        Ok(Some(peak)) => peak,
+        Ok(None) => return previous,
+        Err(e) => {
+            tracing::debug!(
+                "No usage for {}/{} from metrics.k8s.io: {}",
+                ns,
+                md.name_any(),
+                e
+            );
+            return previous;
+        }
+    };
+
+    let cpu = round_up(cpu * 1000.0 * HEADROOM, CPU_STEP_MILLIS);
+    let memory = round_up(memory * HEADROOM, MEMORY_STEP_BYTES);
+    let (cpu, memory) = match &previous {
+        Some(p) => (
+            cpu.max(parse_cpu(&p.cpu).unwrap_or(0.0) * 1000.0),
+            memory.max(parse_memory(&p.memory).unwrap_or(0.0)),
+        ),
+        None => (cpu, memory),
+    };
+    Some(ResourceRecommendation {
+        release,
+        cpu: format!("{}m", cpu as u64),
+        memory: format!("{}Mi", (memory / MEMORY_STEP_BYTES) as u64),
+        updated_at: Utc::now().to_rfc3339(),
+    })
+}
+
+/// The highest CPU (cores) and memory (bytes) use of `container` over the live pods, or
+/// `None` while no pod has been sampled yet.
+async fn peak_usage(
+    client: &Client,
+    ns: &str,
+    base_name: &str,
+    container: &str,
+) -> Result<Option<(f64, f64)>, kube::Error> {
+    let gvk = GroupVersionKind::gvk("metrics.k8s.io", "v1beta1", "PodMetrics");
+    let ar = ApiResource::from_gvk_with_plural(&gvk, "pods");
+    let api: Api<DynamicObject> = Api::namespaced_with(client.clone(), ns, &ar);
+    let lp = ListParams::default().labels(&format!("app={},role=live", base_name));
+    let metrics = retry_read(|| api.list(&lp)).await?;
+
+    let usage: Vec<(f64, f64)> = metrics
+        .items
+        .iter()
+        .flat_map(|pod| {
+            pod.data["containers"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default()
+        })
+        .filter(|c| c["name"] == container)
+        .filter_map(|c| {
+            let usage = &c["usage"];
+            Some((
+                parse_cpu(usage["cpu"].as_str()?)?,
+                parse_memory(usage["memory"].as_str()?)?,
+            ))
+        })
+        .collect();
+    Ok((!usage.is_empty()).then(|| {
+        usage.iter().fold((0.0, 0.0), |(cpu, mem), &(c, m)| {
+            (f64::max(cpu, c), f64::max(mem, m))
+        })
+    }))
+}
+
+fn round_up(value: f64, step: f64) -> f64 {
+    ((value / step).ceil() * step).max(step)
+}
+
+/// Cores in a CPU quantity as the metrics server reports it (`250m`, `1234567n`, `2`).
+fn parse_cpu(quantity: &str) -> Option<f64> {
+    let (number, scale) = match quantity.char_indices().last()? {
+        (i, 'n') => (&quantity[..i], 1e-9),
+        (i, 'u') => (&quantity[..i], 1e-6),
+        (i, 'm') => (&quantity[..i], 1e-3),
+        _ => (quantity, 1.0),
+    };
+    number.parse::<f64>().ok().map(|n| n * scale)
+}
+
+/// Bytes in a memory quantity (`512Mi`, `123456Ki`, `1G`, `1048576`).
+fn parse_memory(quantity: &str) -> Option<f64> {
+    const SUFFIXES: [(&str, f64); 8] = [
+        ("Ki", 1024.0),
+        ("Mi", 1048576.0),
+        ("Gi", 1073741824.0),
+        ("Ti", 1099511627776.0),
+        ("k", 1e3),
+        ("M", 1e6),
+        ("G", 1e9),
+        ("T", 1e12),
+    ];
+    let (number, scale) = SUFFIXES
+        .iter()
+        .find_map(|(suffix, scale)| Some((quantity.strip_suffix(suffix)?, *scale)))
+        .unwrap_or((quantity, 1.0));
+    number.parse::<f64>().ok().map(|n| n * scale)
+}
@@ -0,0 +1,43 @@
+use std::env;
+
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{Resource, trace::SdkTracerProvider};
+use tracing_subscriber::{EnvFilter, fmt, prelude::*};
+
+const SERVICE_NAME: &str = "model-operator";
+
+/// Installs the global subscriber. When `OTEL_EXPORTER_OTLP_ENDPOINT` is set, spans (the
+/// per-reconcile span and kube's per-request HTTP spans beneath it) are also exported to that
+/// collector over OTLP/gRPC; otherwise nothing OpenTelemetry-related is set up.
+///
+/// The returned provider must be shut down on exit so buffered spans are flushed.
+pub fn init() -> Result<Option<SdkTracerProvider>, Box<dyn std::error::Error>> {
+    let provider = match env::var("OTEL_EXPORTER_OTLP_ENDPOINT") {
+        Ok(endpoint) if !endpoint.is_empty() => {
+            let exporter = SpanExporter::builder()
+                .with_tonic()
+                .with_endpoint(endpoint)
+                .build()?;
+            Some(
+                SdkTracerProvider::builder()
+                    .with_batch_exporter(exporter)
+                    .with_resource(Resource::builder().with_service_name(SERVICE_NAME).build())
+                    .build(),
+            )
+        }
+        _ => None,
+    };
+
+    let otel = provider
+        .as_ref()
+        .map(|p| tracing_opentelemetry::layer().with_tracer(p.tracer(SERVICE_NAME)));
+
+    tracing_subscriber::registry()
+        .with(EnvFilter::from_default_env())
+        .with(fmt::layer())
+        .with(otel)
+        .init();
+
+    Ok(provider)
+}
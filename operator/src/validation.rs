@@ -0,0 +1,1683 @@
+use std::net::IpAddr;
+
+use k8s_openapi::api::core::v1::PodTemplateSpec;
+use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+use kube::ResourceExt;
+
+use crate::{
+    config::Config,
+    crd::{ModelDeployment, render_name},
+    error::Error,
+    image, maintenance,
+    migrate::{ADOPT_FROM_ANN, CHILD_BASE_ANN, child_base_name},
+    reconsile::{
+        DEFAULT_DEBUG_IMAGE, DeploymentType, OPERATOR_DOMAIN, container_ports, desired_deployment,
+        extra_service_name, live_service_managed, pdb_name, probe_port, serving_port,
+    },
+};
+
+/// Kinds that run pods, refused in `extraResources` whatever `EXTRA_RESOURCE_KINDS` allows.
+const WORKLOAD_KINDS: &[&str] = &[
+    "Pod",
+    "PodTemplate",
+    "Deployment",
+    "ReplicaSet",
+    "StatefulSet",
+    "DaemonSet",
+    "Job",
+    "CronJob",
+    "ReplicationController",
+];
+
+/// Kubernetes caps Service names, container names and label values at 63 characters.
+const MAX_NAME_LEN: usize = 63;
+
+/// Every name or label value the operator derives from the ModelDeployment name.
+fn generated_names(md: &ModelDeployment) -> Vec<String> {
+    let base = child_base_name(md);
+    let spec = &md.spec;
+    let mut names = vec![base.clone(), spec.deployment_name(&base, "live")];
+    if live_service_managed(spec) {
+        names.push(spec.service_name(&base, "live"));
+    }
+    if spec.shadow.is_some() {
+        names.push(spec.deployment_name(&base, "shadow"));
+        names.push(spec.service_name(&base, "shadow"));
+    }
+    if md.spec.disruption_budget.is_some() {
+        names.push(pdb_name(&base));
+    }
+    if md.spec.network_policy.is_some() {
+        names.push(format!("{}-netpol", base));
+    }
+    if md.spec.inline_config.is_some() {
+        names.push(format!("{}-config", base));
+    }
+    for config in md.spec.services.iter().flatten() {
+        names.push(extra_service_name(spec, &base, config));
+    }
+    names
+}
+
+/// Rejects images from registries outside `allowlist`, before any workload is created. The
+/// images the spec names are checked along with every container of the rendered pod templates,
+/// so `podTemplateOverlay` cannot slip one past; the operator's own busybox helper is not.
+pub fn check_registries(md: &ModelDeployment, allowlist: &[String]) -> Result<(), Error> {
+    if allowlist.is_empty() {
+        return Ok(());
+    }
+    let spec = &md.spec;
+    let base = child_base_name(md);
+    let variants = [
+        (Some(&spec.live), DeploymentType::Live),
+        (spec.shadow.as_ref(), DeploymentType::Shadow),
+    ];
+    let mut images: Vec<String> = variants
+        .iter()
+        .filter_map(|(v, _)| *v)
+        .flat_map(|v| {
+            std::iter::once(&v.image)
+                .chain(v.companion.as_ref().map(|c| &c.image))
+                .chain(v.models.iter().flatten().map(|m| &m.image))
+        })
+        .chain(&spec.fallback_image)
+        .chain(&spec.debug_sidecar_image)
+        .cloned()
+        .collect();
+    for (variant, role) in variants {
+        let Some(variant) = variant else { continue };
+        let name = spec.deployment_name(&base, role.as_ref());
+        let deployment = desired_deployment(md, &name, &base, variant, None, role);
+        let Some(pod) = deployment.spec.and_then(|s| s.template.spec) else {
+            continue;
+        };
+        let containers = pod
+            .containers
+            .into_iter()
+            .chain(pod.init_containers.into_iter().flatten());
+        let ephemeral = pod
+            .ephemeral_containers
+            .into_iter()
+            .flatten()
+            .map(|c| c.image);
+        images.extend(
+            containers
+                .map(|c| c.image)
+                .chain(ephemeral)
+                .flatten()
+                .filter(|image| image != DEFAULT_DEBUG_IMAGE),
+        );
+    }
+    for name in &images {
+        let Ok(reference) = image::parse(name) else {
+            return Err(Error::Validation(format!(
+                "image {:?} is not a valid image reference",
+                name
+            )));
+        };
+        if !image::allowed(&reference, allowlist) {
+            return Err(Error::Validation(format!(
+                "image {:?} is from {}, which is not in the registry allowlist ({})",
+                name,
+                reference.registry,
+                allowlist.join(", ")
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Rejects specs the API server would refuse part-way through a reconcile, or that the
+/// operator's `config` does not permit.
+pub fn validate(md: &ModelDeployment, config: &Config) -> Result<(), Error> {
+    let base_len = child_base_name(md).len();
+
+    for (field, template) in md.spec.naming.iter().flat_map(|n| {
+        [
+            ("naming.deployment", n.deployment.as_deref()),
+            ("naming.service", n.service.as_deref()),
+        ]
+    }) {
+        let Some(template) = template else {
+            continue;
+        };
+        // placeholders once each keep names unique and the length hint below right
+        if template.matches("{name}").count() != 1
+            || template.matches("{role}").count() != 1
+            || !dns_label(&render_name(template, "name", "role"))
+        {
+            return Err(Error::Validation(format!(
+                "{} {:?} must contain {{name}} and {{role}} once each and otherwise only lowercase alphanumerics and '-'",
+                field, template
+            )));
+        }
+    }
+
+    if let Some(longest) = generated_names(md)
+        .into_iter()
+        .max_by_key(|name| name.len())
+        .filter(|name| name.len() > MAX_NAME_LEN)
+    {
+        return Err(Error::Validation(format!(
+            "generated name {} is {} characters (limit {}); shorten the ModelDeployment name to at most {} characters",
+            longest,
+            longest.len(),
+            MAX_NAME_LEN,
+            MAX_NAME_LEN - (longest.len() - base_len)
+        )));
+    }
+
+    for annotation in [ADOPT_FROM_ANN, CHILD_BASE_ANN] {
+        if let Some(old) = md
+            .annotations()
+            .get(annotation)
+            .filter(|old| !dns_label(old))
+        {
+            return Err(Error::Validation(format!(
+                "{} {:?} is not a ModelDeployment name",
+                annotation, old
+            )));
+        }
+    }
+
+    if let Some(ns) = md
+        .spec
+        .target_namespace
+        .as_ref()
+        .filter(|ns| !dns_label(ns))
+    {
+        return Err(Error::Validation(format!(
+            "targetNamespace {:?} is not a namespace name",
+            ns
+        )));
+    }
+    if let Some(target) = &md.spec.target_namespace {
+        let source = md.namespace().unwrap_or_else(|| "default".into());
+        if !config.target_namespace_allowed(&source, target) {
+            return Err(Error::Validation(format!(
+                "targetNamespace {} is not allowed for ModelDeployments in {} \
+                 (TARGET_NAMESPACES)",
+                target, source
+            )));
+        }
+    }
+
+    if let Some(key) = md
+        .spec
+        .pod_annotations
+        .iter()
+        .flat_map(|a| a.keys())
+        .find(|k| k.starts_with(OPERATOR_DOMAIN))
+    {
+        return Err(Error::Validation(format!(
+            "podAnnotations key {:?} is in the operator's own domain",
+            key
+        )));
+    }
+
+    for (key, value) in md.spec.cost_labels.iter().flatten() {
+        if !qualified_name(key) || key.starts_with(OPERATOR_DOMAIN) || key == "app" || key == "role"
+        {
+            return Err(Error::Validation(format!(
+                "costLabels key {:?} is not a label key the operator leaves free",
+                key
+            )));
+        }
+        if !label_value(value) {
+            return Err(Error::Validation(format!(
+                "costLabels value {:?} of {:?} must be up to 63 alphanumerics, '-', '_' or '.', \
+                 starting and ending alphanumeric",
+                value, key
+            )));
+        }
+    }
+
+    if let Some(config) = &md.spec.scale_to_zero {
+        if md.spec.autoscaling.is_some() || md.spec.is_canary() {
+            return Err(Error::Validation(
+                "scaleToZero cannot be combined with autoscaling or a canary".into(),
+            ));
+        }
+        if !http_url(&config.prometheus_url) || config.query.trim().is_empty() {
+            return Err(Error::Validation(
+                "scaleToZero needs an http(s):// prometheusUrl and a query".into(),
+            ));
+        }
+        if config.idle_seconds == 0 {
+            return Err(Error::Validation(
+                "scaleToZero idleSeconds must be at least 1".into(),
+            ));
+        }
+        if let Some(svc) = config.activator_service.as_ref().filter(|s| !dns_label(s)) {
+            return Err(Error::Validation(format!(
+                "scaleToZero activatorService {:?} is not a Service name",
+                svc
+            )));
+        }
+    }
+
+    if let Some(overlay) = &md.spec.pod_template_overlay {
+        if !overlay.is_object() {
+            return Err(Error::Validation(
+                "podTemplateOverlay must be an object".into(),
+            ));
+        }
+        if let Err(e) = serde_json::from_value::<PodTemplateSpec>(overlay.clone()) {
+            return Err(Error::Validation(format!(
+                "podTemplateOverlay is not a pod template: {}",
+                e
+            )));
+        }
+    }
+
+    if let Some(inline) = &md.spec.inline_config {
+        if md.spec.config_ref.is_some() {
+            return Err(Error::Validation(
+                "set either inlineConfig or configRef, not both".into(),
+            ));
+        }
+        if let Some(key) = inline.keys().find(|k| !config_key(k)) {
+            return Err(Error::Validation(format!(
+                "inlineConfig key {:?} must be non-empty and consist of alphanumerics, '-', '_' or '.'",
+                key
+            )));
+        }
+    }
+
+    // live gets the rest of the traffic, so a weight past 100 would hand it a negative share
+    if let Some(step) = md
+        .spec
+        .canary_steps
+        .iter()
+        .flatten()
+        .find(|s| !(0..=100).contains(&s.weight))
+    {
+        return Err(Error::Validation(format!(
+            "canarySteps weight {} must be between 0 and 100",
+            step.weight
+        )));
+    }
+
+    if let Some(percent) = md.spec.mirror_percent.filter(|p| !(0..=100).contains(p)) {
+        return Err(Error::Validation(format!(
+            "mirrorPercent {} must be between 0 and 100",
+            percent
+        )));
+    }
+
+    let serving_ports: Vec<i32> = std::iter::once(&md.spec.live)
+        .chain(md.spec.shadow.as_ref())
+        .map(serving_port)
+        .collect();
+    if let Some(port) = serving_ports.iter().find(|p| !(1..=65535).contains(*p)) {
+        return Err(Error::Validation(format!("port {} must be 1-65535", port)));
+    }
+    // one host port cannot be bound to two container ports
+    if md.spec.host_port.is_some() && serving_ports.iter().any(|p| *p != serving_ports[0]) {
+        return Err(Error::Validation(
+            "hostPort needs live and shadow to listen on the same port".into(),
+        ));
+    }
+
+    if let Some(name) = md.spec.port_name.as_ref().filter(|n| !svc_name(n)) {
+        return Err(Error::Validation(format!(
+            "portName {:?} must be 1-15 lowercase alphanumerics or '-' with at least one letter",
+            name
+        )));
+    }
+    // the serving port is named `http` once extra ports exist, unless portName says otherwise
+    let mut port_names = vec![md.spec.port_name.as_deref().unwrap_or("http")];
+    for port in md.spec.extra_ports.iter().flatten() {
+        if !svc_name(&port.name) {
+            return Err(Error::Validation(format!(
+                "extra port name {:?} must be 1-15 lowercase alphanumerics or '-' with at least one letter",
+                port.name
+            )));
+        }
+        if !(1..=65535).contains(&port.container_port)
+            || serving_ports.contains(&port.container_port)
+        {
+            return Err(Error::Validation(format!(
+                "extra port {} must be 1-65535 and not a serving port",
+                port.container_port
+            )));
+        }
+        if port_names.contains(&port.name.as_str()) {
+            return Err(Error::Validation(format!(
+                "port name {} is used more than once",
+                port.name
+            )));
+        }
+        port_names.push(&port.name);
+    }
+
+    let host_ports = std::iter::once((serving_ports[0], md.spec.host_port)).chain(
+        md.spec
+            .extra_ports
+            .iter()
+            .flatten()
+            .map(|p| (p.container_port, p.host_port)),
+    );
+    let mut bound = Vec::new();
+    for (container_port, host_port) in host_ports {
+        let Some(host_port) = host_port else {
+            continue;
+        };
+        if !(1..=65535).contains(&host_port) || bound.contains(&host_port) {
+            return Err(Error::Validation(format!(
+                "hostPort {} must be 1-65535 and used once",
+                host_port
+            )));
+        }
+        if md.spec.host_network == Some(true) && host_port != container_port {
+            return Err(Error::Validation(format!(
+                "hostPort {} must equal its container port {} with hostNetwork",
+                host_port, container_port
+            )));
+        }
+        bound.push(host_port);
+    }
+
+    let one_of = |field: &str, value: &Option<String>, allowed: &[&str]| match value {
+        Some(v) if !allowed.contains(&v.as_str()) => Err(Error::Validation(format!(
+            "{} {:?} must be one of {}",
+            field,
+            v,
+            allowed.join(", ")
+        ))),
+        _ => Ok(()),
+    };
+    one_of(
+        "serviceType",
+        &md.spec.service_type,
+        &["ClusterIP", "NodePort", "LoadBalancer"],
+    )?;
+    one_of(
+        "externalTrafficPolicy",
+        &md.spec.external_traffic_policy,
+        &["Cluster", "Local"],
+    )?;
+    let exposed = std::iter::once(&md.spec.service_type)
+        .chain(md.spec.services.iter().flatten().map(|s| &s.service_type))
+        .any(|t| matches!(t.as_deref(), Some("NodePort" | "LoadBalancer")));
+    if md.spec.external_traffic_policy.is_some() && !exposed {
+        return Err(Error::Validation(
+            "externalTrafficPolicy needs a NodePort or LoadBalancer Service; every Service is ClusterIP".into(),
+        ));
+    }
+    one_of(
+        "internalTrafficPolicy",
+        &md.spec.internal_traffic_policy,
+        &["Cluster", "Local"],
+    )?;
+    one_of(
+        "patchStrategy",
+        &md.spec.patch_strategy,
+        &["Apply", "Merge"],
+    )?;
+    one_of(
+        "rolloutStrategy",
+        &Some(md.spec.rollout_strategy.clone()),
+        &["rolling", "canary", "inPlace"],
+    )?;
+    one_of(
+        "rolloutOrder",
+        &md.spec.rollout_order,
+        &["LiveFirst", "ShadowFirst"],
+    )?;
+    one_of(
+        "terminationMessagePolicy",
+        &md.spec.termination_message_policy,
+        &["File", "FallbackToLogsOnError"],
+    )?;
+    one_of(
+        "trafficDistribution",
+        &md.spec.traffic_distribution,
+        &["PreferClose"],
+    )?;
+
+    if let Some(range) = md
+        .spec
+        .load_balancer_source_ranges
+        .iter()
+        .flatten()
+        .find(|range| !cidr(range))
+    {
+        return Err(Error::Validation(format!(
+            "loadBalancerSourceRanges {:?} is not a CIDR such as 10.0.0.0/8",
+            range
+        )));
+    }
+
+    let mut service_names: Vec<&str> = vec!["live", "shadow"];
+    for config in md.spec.services.iter().flatten() {
+        if !dns_label(&config.name) {
+            return Err(Error::Validation(format!(
+                "services name {:?} must be lowercase alphanumerics and '-'",
+                config.name
+            )));
+        }
+        if service_names.contains(&config.name.as_str()) {
+            return Err(Error::Validation(format!(
+                "services name {} is reserved or used more than once",
+                config.name
+            )));
+        }
+        service_names.push(&config.name);
+        one_of(
+            "services serviceType",
+            &config.service_type,
+            &["ClusterIP", "NodePort", "LoadBalancer"],
+        )?;
+        // the extra Services select the live pods, so live's models are theirs to expose
+        let known: Vec<&str> = port_names
+            .iter()
+            .copied()
+            .chain(
+                md.spec
+                    .live
+                    .models
+                    .iter()
+                    .flatten()
+                    .map(|m| m.name.as_str()),
+            )
+            .collect();
+        for port in config.ports.iter().flatten() {
+            if !known.contains(&port.as_str()) {
+                return Err(Error::Validation(format!(
+                    "services {} exposes unknown port {}; known ports are {}",
+                    config.name,
+                    port,
+                    known.join(", ")
+                )));
+            }
+        }
+    }
+
+    if let Some(service) = md.spec.service.as_ref().filter(|s| !s.manage) {
+        match service.existing_name.as_deref() {
+            None | Some("") => {
+                return Err(Error::Validation(
+                    "service.existingName is required when service.manage is false".into(),
+                ));
+            }
+            Some(name) if !dns_label(name) => {
+                return Err(Error::Validation(format!(
+                    "service.existingName {:?} is not a valid Service name",
+                    name
+                )));
+            }
+            Some(_) => {}
+        }
+    }
+
+    match md.spec.session_affinity.as_deref() {
+        None | Some("None") | Some("ClientIP") => {}
+        Some(other) => {
+            return Err(Error::Validation(format!(
+                "sessionAffinity {:?} must be None or ClientIP",
+                other
+            )));
+        }
+    }
+    if let Some(timeout) = md.spec.session_affinity_timeout_seconds {
+        if md.spec.session_affinity.as_deref() != Some("ClientIP") {
+            return Err(Error::Validation(
+                "sessionAffinityTimeoutSeconds requires sessionAffinity ClientIP".into(),
+            ));
+        }
+        if !(1..=86400).contains(&timeout) {
+            return Err(Error::Validation(format!(
+                "sessionAffinityTimeoutSeconds {} must be between 1 and 86400",
+                timeout
+            )));
+        }
+    }
+
+    if md
+        .spec
+        .match_rule
+        .as_ref()
+        .is_some_and(|rule| rule.trim().is_empty())
+    {
+        return Err(Error::Validation("matchRule must not be empty".into()));
+    }
+    if let Some(rule) = &md.spec.mirror_match {
+        if !md.spec.traffic_mirror || md.spec.is_canary() {
+            return Err(Error::Validation(
+                "mirrorMatch only applies with trafficMirror and no canary".into(),
+            ));
+        }
+        traefik_rule(rule)
+            .map_err(|e| Error::Validation(format!("mirrorMatch {:?}: {}", rule, e)))?;
+    }
+    if let Some(autoscaling) = md.spec.autoscaling.as_ref().filter(|a| a.enabled) {
+        let metrics = autoscaling.metrics.as_deref().unwrap_or_default();
+        if autoscaling.target_cpu_utilization_percentage.is_none() && metrics.is_empty() {
+            return Err(Error::Validation(
+                "autoscaling needs targetCpuUtilizationPercentage or at least one metric".into(),
+            ));
+        }
+        if let Some(target) = autoscaling
+            .target_cpu_utilization_percentage
+            .filter(|t| *t < 1)
+        {
+            return Err(Error::Validation(format!(
+                "autoscaling targetCpuUtilizationPercentage {} must be at least 1",
+                target
+            )));
+        }
+        for metric in metrics {
+            if !["Pods", "External"].contains(&metric.r#type.as_str()) {
+                return Err(Error::Validation(format!(
+                    "autoscaling metric type {:?} must be one of Pods, External",
+                    metric.r#type
+                )));
+            }
+            if metric.name.trim().is_empty() || metric.target.trim().is_empty() {
+                return Err(Error::Validation(
+                    "autoscaling metrics need a name and a target".into(),
+                ));
+            }
+            if !metric_target(&metric.target) {
+                return Err(Error::Validation(format!(
+                    "autoscaling metric {} target {:?} must be a positive quantity, e.g. 100 or 500m",
+                    metric.name, metric.target
+                )));
+            }
+            if metric.r#type == "Pods" && (metric.per_pod || metric.selector.is_some()) {
+                return Err(Error::Validation(format!(
+                    "autoscaling metric {}: perPod and selector only apply to External metrics",
+                    metric.name
+                )));
+            }
+        }
+    }
+
+    if let Some(behavior) = md
+        .spec
+        .autoscaling
+        .as_ref()
+        .and_then(|a| a.behavior.as_ref())
+    {
+        // the HPA's own limits
+        let windows = [
+            behavior.scale_down_stabilization_seconds,
+            behavior.scale_up_stabilization_seconds,
+        ];
+        if windows
+            .into_iter()
+            .flatten()
+            .any(|s| !(0..=3600).contains(&s))
+        {
+            return Err(Error::Validation(
+                "autoscaling behavior stabilization windows must be 0-3600 seconds".into(),
+            ));
+        }
+        if [behavior.scale_up_pods, behavior.scale_up_percent]
+            .into_iter()
+            .flatten()
+            .any(|v| v < 1)
+            || behavior
+                .scale_up_period_seconds
+                .is_some_and(|s| !(1..=1800).contains(&s))
+        {
+            return Err(Error::Validation(
+                "autoscaling behavior scaleUpPods and scaleUpPercent must be positive and scaleUpPeriodSeconds 1-1800".into(),
+            ));
+        }
+    }
+
+    if let Some(size) = md
+        .spec
+        .shared_memory
+        .as_ref()
+        .filter(|size| !quantity(size))
+    {
+        return Err(Error::Validation(format!(
+            "sharedMemory {:?} must be a positive size such as 2Gi or 512Mi",
+            size
+        )));
+    }
+    if let Some(seconds) = md
+        .spec
+        .probes
+        .as_ref()
+        .and_then(|p| p.warmup_seconds)
+        .filter(|s| !(10..=86400).contains(s))
+    {
+        return Err(Error::Validation(format!(
+            "probes.warmupSeconds {} must be between 10 and 86400",
+            seconds
+        )));
+    }
+    for (role, variant) in [
+        ("live", Some(&md.spec.live)),
+        ("shadow", md.spec.shadow.as_ref()),
+    ] {
+        let Some(variant) = variant.filter(|_| md.spec.probes.is_some()) else {
+            continue;
+        };
+        let declared = container_ports(&md.spec, variant);
+        let found = match probe_port(&md.spec, variant) {
+            IntOrString::Int(port) => declared.iter().any(|p| p.container_port == port),
+            IntOrString::String(name) => declared.iter().any(|p| p.name.as_ref() == Some(&name)),
+        };
+        if !found {
+            let listed: Vec<String> = declared
+                .iter()
+                .map(|p| match &p.name {
+                    Some(name) => format!("{} ({})", p.container_port, name),
+                    None => p.container_port.to_string(),
+                })
+                .collect();
+            return Err(Error::Validation(format!(
+                "probes.port {:?} is not a port the {} container declares: {}",
+                md.spec
+                    .probes
+                    .as_ref()
+                    .and_then(|p| p.port.as_deref())
+                    .unwrap_or_default(),
+                role,
+                listed.join(", ")
+            )));
+        }
+    }
+    if let Some(seconds) = md.spec.drain_seconds.filter(|s| !(1..=3600).contains(s)) {
+        return Err(Error::Validation(format!(
+            "drainSeconds {} must be between 1 and 3600",
+            seconds
+        )));
+    }
+    if let Some(fallback) = &md.spec.fallback_image {
+        if let Err(reason) = image::parse(fallback) {
+            return Err(Error::Validation(format!(
+                "fallbackImage {:?} is not a valid image reference: {}",
+                fallback, reason
+            )));
+        }
+        if *fallback == md.spec.live.image {
+            return Err(Error::Validation(
+                "fallbackImage is the live image itself".into(),
+            ));
+        }
+    }
+    if let Some(budget) = &md.spec.disruption_budget {
+        let (normal, maintenance) = (budget.max_unavailable(false), budget.max_unavailable(true));
+        if maintenance < 0 || normal < maintenance {
+            return Err(Error::Validation(format!(
+                "disruptionBudget maxUnavailableDuringMaintenance {} must be between 0 and maxUnavailable {}",
+                maintenance, normal
+            )));
+        }
+    }
+    // the mirrored route is ranked one above it
+    if let Some(priority) = md.spec.route_priority.filter(|p| *p < 1 || *p == i64::MAX) {
+        return Err(Error::Validation(format!(
+            "routePriority {} must be positive and below {}",
+            priority,
+            i64::MAX
+        )));
+    }
+    if let Some(cookie) = &md.spec.sticky_cookie {
+        one_of(
+            "stickyCookie sameSite",
+            &cookie.same_site,
+            &["none", "lax", "strict"],
+        )?;
+        if let Some(max_age) = cookie.max_age.filter(|s| *s < 0) {
+            return Err(Error::Validation(format!(
+                "stickyCookie maxAge {} must not be negative",
+                max_age
+            )));
+        }
+    }
+
+    let scheduling = std::iter::once(md.spec.scheduling.as_ref())
+        .chain([Some(&md.spec.live), md.spec.shadow.as_ref()].map(|v| v?.scheduling.as_ref()));
+    for spread in scheduling
+        .clone()
+        .flatten()
+        .flat_map(|s| s.topology_spread.iter().flatten())
+    {
+        if !qualified_name(&spread.topology_key) || spread.max_skew < 1 {
+            return Err(Error::Validation(format!(
+                "topologySpread on {:?} needs a label key as topologyKey and a maxSkew of at least 1",
+                spread.topology_key
+            )));
+        }
+        one_of(
+            "topologySpread whenUnsatisfiable",
+            &spread.when_unsatisfiable,
+            &["DoNotSchedule", "ScheduleAnyway"],
+        )?;
+        let schedule_anyway = spread.when_unsatisfiable.as_deref() == Some("ScheduleAnyway");
+        if let Some(min_domains) = spread.min_domains.filter(|m| *m < 1 || schedule_anyway) {
+            return Err(Error::Validation(format!(
+                "topologySpread minDomains {} must be at least 1 and needs DoNotSchedule",
+                min_domains
+            )));
+        }
+        // the operator's selector already holds these, and Kubernetes rejects a key in both
+        if let Some(key) = spread
+            .match_label_keys
+            .iter()
+            .flatten()
+            .find(|k| !qualified_name(k) || *k == "app" || *k == "role")
+        {
+            return Err(Error::Validation(format!(
+                "topologySpread matchLabelKeys entry {:?} is not a label key outside app and role",
+                key
+            )));
+        }
+    }
+    for expr in scheduling
+        .flatten()
+        .flat_map(|s| s.node_affinity.iter().flatten())
+    {
+        let needs_values = match expr.operator.as_str() {
+            "In" | "NotIn" | "Gt" | "Lt" => true,
+            "Exists" | "DoesNotExist" => false,
+            other => {
+                return Err(Error::Validation(format!(
+                    "node affinity operator {:?} must be one of In, NotIn, Exists, DoesNotExist, Gt, Lt",
+                    other
+                )));
+            }
+        };
+        if needs_values == expr.values.is_empty() {
+            return Err(Error::Validation(format!(
+                "node affinity on {} with {} {} values",
+                expr.key,
+                expr.operator,
+                if needs_values { "needs" } else { "takes no" }
+            )));
+        }
+    }
+
+    for (role, variant) in [
+        ("live", Some(&md.spec.live)),
+        ("shadow", md.spec.shadow.as_ref()),
+    ] {
+        let Some(variant) = variant else {
+            continue;
+        };
+        if let Err(reason) = image::parse(&variant.image) {
+            return Err(Error::Validation(format!(
+                "{} image {:?} is not a valid image reference: {}",
+                role, variant.image, reason
+            )));
+        }
+        let min_ready = variant.min_ready_seconds.or(md.spec.min_ready_seconds);
+        let deadline = variant
+            .progress_deadline_seconds
+            .or(md.spec.progress_deadline_seconds);
+        if min_ready.is_some_and(|s| s < 0) || deadline.is_some_and(|s| s < 1) {
+            return Err(Error::Validation(format!(
+                "{} minReadySeconds must be at least 0 and progressDeadlineSeconds at least 1",
+                role
+            )));
+        }
+        if let Some(companion) = &variant.companion {
+            if companion.image.trim().is_empty() {
+                return Err(Error::Validation(format!(
+                    "{} companion image must not be empty",
+                    role
+                )));
+            }
+            let taken = std::iter::once(serving_port(variant)).chain(
+                md.spec
+                    .extra_ports
+                    .iter()
+                    .flatten()
+                    .map(|p| p.container_port),
+            );
+            if let Some(port) = companion
+                .port
+                .filter(|p| !(1..=65535).contains(p) || taken.clone().any(|t| t == *p))
+            {
+                return Err(Error::Validation(format!(
+                    "{} companion port {} must be 1-65535 and not a model server port",
+                    role, port
+                )));
+            }
+        }
+        let mut names = port_names.clone();
+        names.extend(["companion", "debug", "verify-model"]);
+        let mut ports: Vec<i32> = std::iter::once(serving_port(variant))
+            .chain(
+                md.spec
+                    .extra_ports
+                    .iter()
+                    .flatten()
+                    .map(|p| p.container_port),
+            )
+            .chain(variant.companion.as_ref().and_then(|c| c.port))
+            .collect();
+        for model in variant.models.iter().flatten() {
+            // the name is also the model's port name
+            if !svc_name(&model.name) || names.contains(&model.name.as_str()) {
+                return Err(Error::Validation(format!(
+                    "{} model name {:?} must be a port name of at most 15 characters, not used by another container or port",
+                    role, model.name
+                )));
+            }
+            if model.image.trim().is_empty() {
+                return Err(Error::Validation(format!(
+                    "{} model {} image must not be empty",
+                    role, model.name
+                )));
+            }
+            if !(1..=65535).contains(&model.port) || ports.contains(&model.port) {
+                return Err(Error::Validation(format!(
+                    "{} model {} port {} must be 1-65535 and not used by another container",
+                    role, model.name, model.port
+                )));
+            }
+            names.push(&model.name);
+            ports.push(model.port);
+        }
+        // Kubernetes rejects a deadline that doesn't exceed minReadySeconds
+        if deadline.unwrap_or(600) <= min_ready.unwrap_or(0) {
+            return Err(Error::Validation(format!(
+                "{} progressDeadlineSeconds {} must be greater than minReadySeconds {}",
+                role,
+                deadline.unwrap_or(600),
+                min_ready.unwrap_or(0)
+            )));
+        }
+    }
+
+    let gates = md.spec.readiness_gates.as_deref().unwrap_or_default();
+    for (i, gate) in gates.iter().enumerate() {
+        if !qualified_name(gate) || gates[..i].contains(gate) {
+            return Err(Error::Validation(format!(
+                "readinessGates {:?} must be a unique condition type such as example.com/ready",
+                gate
+            )));
+        }
+    }
+
+    let secret_refs = [Some(&md.spec.live), md.spec.shadow.as_ref()]
+        .into_iter()
+        .flatten()
+        .flat_map(|v| v.secret_refs.as_deref().unwrap_or_default());
+    for secret in secret_refs {
+        if secret.name.is_empty() {
+            return Err(Error::Validation(
+                "secretRefs name must not be empty".into(),
+            ));
+        }
+        if let Some(path) = secret.mount_path.as_ref().filter(|p| !p.starts_with('/')) {
+            return Err(Error::Validation(format!(
+                "secretRefs mountPath {:?} for {} must be absolute",
+                path, secret.name
+            )));
+        }
+    }
+
+    // the verifier is busybox wget, which cannot do TLS; the checksum is what the download relies on
+    let checksums = [Some(&md.spec.live), md.spec.shadow.as_ref()]
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.verify_checksum.as_ref());
+    for checksum in checksums {
+        if !checksum.url.starts_with("http://") {
+            return Err(Error::Validation(format!(
+                "verifyChecksum url {:?} must be plain http://; the verifier cannot fetch over TLS",
+                checksum.url
+            )));
+        }
+        if checksum.sha256.len() != 64 || !checksum.sha256.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(Error::Validation(format!(
+                "verifyChecksum sha256 {:?} must be 64 hex digits",
+                checksum.sha256
+            )));
+        }
+    }
+
+    for variant in [Some(&md.spec.live), md.spec.shadow.as_ref()]
+        .into_iter()
+        .flatten()
+    {
+        let mut env_names = Vec::new();
+        if variant.verify_checksum.is_some() {
+            env_names.push("MODEL_PATH");
+        }
+        for entry in variant.env.iter().flatten() {
+            if entry.name.is_empty() || env_names.contains(&entry.name.as_str()) {
+                return Err(Error::Validation(format!(
+                    "env name {:?} is empty, reserved or used more than once",
+                    entry.name
+                )));
+            }
+            env_names.push(&entry.name);
+            if entry.value.is_some() == entry.field_ref.is_some() {
+                return Err(Error::Validation(format!(
+                    "env {} needs exactly one of value or fieldRef",
+                    entry.name
+                )));
+            }
+            if let Some(path) = entry
+                .field_ref
+                .as_deref()
+                .filter(|path| !downward_env_field(path))
+            {
+                return Err(Error::Validation(format!(
+                    "env {} fieldRef {:?} is not a pod field available as an environment variable",
+                    entry.name, path
+                )));
+            }
+        }
+    }
+
+    for variant in [Some(&md.spec.live), md.spec.shadow.as_ref()]
+        .into_iter()
+        .flatten()
+    {
+        let mut volume_names = Vec::new();
+        // the checksum-verified model and mounted secrets claim their paths first
+        let mut mount_paths: Vec<&str> = variant
+            .secret_refs
+            .iter()
+            .flatten()
+            .filter_map(|secret| secret.mount_path.as_deref())
+            .collect();
+        if variant.verify_checksum.is_some() {
+            mount_paths.push("/models");
+        }
+        if md.spec.shared_memory.is_some() {
+            mount_paths.push("/dev/shm");
+        }
+        for volume in variant.volumes.iter().flatten() {
+            let name = volume.name.as_str();
+            // `model`, `inline-config`, `dshm` and `secret-<n>` are the operator's own volumes
+            if !dns_label(name)
+                || ["model", "inline-config", "dshm"].contains(&name)
+                || name.starts_with("secret-")
+                || volume_names.contains(&name)
+            {
+                return Err(Error::Validation(format!(
+                    "volume name {:?} must be a DNS label, not model, inline-config, dshm or secret-*, and unique",
+                    name
+                )));
+            }
+            volume_names.push(name);
+            if !volume.mount_path.starts_with('/')
+                || mount_paths.contains(&volume.mount_path.as_str())
+            {
+                return Err(Error::Validation(format!(
+                    "volume {} mountPath {:?} must be absolute and not shared",
+                    name, volume.mount_path
+                )));
+            }
+            mount_paths.push(&volume.mount_path);
+
+            let sources = [
+                (
+                    "persistentVolumeClaim",
+                    volume.persistent_volume_claim.is_some(),
+                ),
+                ("emptyDir", volume.empty_dir.is_some()),
+                ("configMap", volume.config_map.is_some()),
+                ("secret", volume.secret.is_some()),
+                ("csi", volume.csi.is_some()),
+                ("hostPath", volume.host_path.is_some()),
+            ];
+            let set: Vec<&str> = sources
+                .iter()
+                .filter(|(_, set)| *set)
+                .map(|(source, _)| *source)
+                .collect();
+            if set.len() != 1 {
+                return Err(Error::Validation(format!(
+                    "volume {} needs exactly one source, got {}",
+                    name,
+                    if set.is_empty() {
+                        "none".into()
+                    } else {
+                        set.join(", ")
+                    }
+                )));
+            }
+            if volume.csi.as_ref().is_some_and(|csi| csi.driver.is_empty()) {
+                return Err(Error::Validation(format!(
+                    "volume {} csi.driver must not be empty",
+                    name
+                )));
+            }
+            if volume.host_path.is_some() && !config.allow_host_path {
+                return Err(Error::Validation(format!(
+                    "volume {} uses hostPath, which this operator does not allow \
+                     (ALLOW_HOST_PATH)",
+                    name
+                )));
+            }
+            if let Some(path) = volume.host_path.as_ref().filter(|p| !p.starts_with('/')) {
+                return Err(Error::Validation(format!(
+                    "volume {} hostPath {:?} must be absolute",
+                    name, path
+                )));
+            }
+        }
+    }
+
+    for (i, raw) in md.spec.extra_resources.iter().flatten().enumerate() {
+        let field = |path: &str| {
+            raw.pointer(path)
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+        };
+        for path in ["/apiVersion", "/kind", "/metadata/name"] {
+            if field(path).is_empty() {
+                return Err(Error::Validation(format!(
+                    "extraResources[{}] needs {}",
+                    i,
+                    path[1..].replace('/', ".")
+                )));
+            }
+        }
+        if field("/kind") == "ModelDeployment" {
+            return Err(Error::Validation(format!(
+                "extraResources[{}] cannot be a ModelDeployment",
+                i
+            )));
+        }
+        // their pods would run images no registry check has seen
+        if WORKLOAD_KINDS.contains(&field("/kind")) {
+            return Err(Error::Validation(format!(
+                "extraResources[{}] is a {}; workloads cannot be extra resources",
+                i,
+                field("/kind")
+            )));
+        }
+        // a CRD can call its kind anything, so the group has to match too
+        let kind = match field("/apiVersion").split_once('/') {
+            Some((group, _)) => format!("{}/{}", group, field("/kind")),
+            None => field("/kind").to_string(),
+        };
+        let kinds = &config.extra_resource_kinds;
+        if !kinds.iter().any(|k| k == "*" || *k == kind) {
+            return Err(Error::Validation(format!(
+                "extraResources[{}] is a {}, which is not an allowed kind ({})",
+                i,
+                kind,
+                kinds.join(", ")
+            )));
+        }
+    }
+
+    if let Some(window) = &md.spec.maintenance_window {
+        maintenance::bounds("maintenanceWindow", window).map_err(Error::Validation)?;
+    }
+    if let Some(window) = &md.spec.mirror_window {
+        maintenance::bounds("mirrorWindow", window).map_err(Error::Validation)?;
+    }
+
+    if let Some(analysis) = &md.spec.canary_analysis {
+        if !http_url(&analysis.prometheus_url) {
+            return Err(Error::Validation(format!(
+                "canaryAnalysis.prometheusUrl {:?} must be an http:// or https:// URL",
+                analysis.prometheus_url
+            )));
+        }
+        if analysis.interval_seconds == Some(0) || analysis.failure_limit == Some(0) {
+            return Err(Error::Validation(
+                "canaryAnalysis intervalSeconds and failureLimit must be at least 1".into(),
+            ));
+        }
+        if analysis.query.trim().is_empty() {
+            return Err(Error::Validation(
+                "canaryAnalysis.query must not be empty".into(),
+            ));
+        }
+        if !analysis.threshold.is_finite() {
+            return Err(Error::Validation(
+                "canaryAnalysis.threshold must be a finite number".into(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn config_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.len() <= 253
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// A positive byte quantity: a decimal number with an optional binary or decimal suffix.
+fn quantity(value: &str) -> bool {
+    let number = value.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let suffix = &value[number.len()..];
+    [
+        "", "Ki", "Mi", "Gi", "Ti", "Pi", "Ei", "k", "M", "G", "T", "P", "E",
+    ]
+    .contains(&suffix)
+        && number
+            .parse::<f64>()
+            .is_ok_and(|n| n > 0.0 && n.is_finite())
+        && number.bytes().all(|b| b.is_ascii_digit() || b == b'.')
+}
+
+/// A positive metric quantity, which unlike a byte size may be in thousandths (`500m`).
+fn metric_target(value: &str) -> bool {
+    quantity(value) || value.strip_suffix('m').is_some_and(quantity)
+}
+
+fn http_url(url: &str) -> bool {
+    ["http://", "https://"].iter().any(|scheme| {
+        url.strip_prefix(scheme)
+            .is_some_and(|rest| !rest.is_empty())
+    })
+}
+
+fn dns_label(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && !name.starts_with('-')
+        && !name.ends_with('-')
+}
+
+/// An IANA service name, which Kubernetes requires of port names: at most 15 lowercase
+/// alphanumerics or `-`, with at least one letter and no leading, trailing or doubled `-`.
+fn svc_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 15
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && name.chars().any(|c| c.is_ascii_lowercase())
+        && !name.starts_with('-')
+        && !name.ends_with('-')
+        && !name.contains("--")
+}
+
+/// Traefik v3 request matchers.
+const MATCHERS: &[&str] = &[
+    "ClientIP",
+    "Header",
+    "HeaderRegexp",
+    "Host",
+    "HostRegexp",
+    "Method",
+    "Path",
+    "PathPrefix",
+    "PathRegexp",
+    "Query",
+    "QueryRegexp",
+];
+
+/// Checks the shape of a Traefik rule: known matchers with backquoted arguments, combined with
+/// `&&`, `||`, `!` and parentheses. Regexps inside the arguments are left to Traefik.
+fn traefik_rule(rule: &str) -> Result<(), String> {
+    let mut rest = rule.trim_start();
+    rule_expr(&mut rest)?;
+    if !rest.is_empty() {
+        return Err(format!("unexpected {:?}", rest));
+    }
+    Ok(())
+}
+
+fn rule_expr(rest: &mut &str) -> Result<(), String> {
+    loop {
+        rule_term(rest)?;
+        match rest.get(..2) {
+            Some("&&" | "||") => *rest = rest[2..].trim_start(),
+            _ => return Ok(()),
+        }
+    }
+}
+
+fn rule_term(rest: &mut &str) -> Result<(), String> {
+    if let Some(negated) = rest.strip_prefix('!') {
+        *rest = negated.trim_start();
+        return rule_term(rest);
+    }
+    if let Some(inner) = rest.strip_prefix('(') {
+        *rest = inner.trim_start();
+        rule_expr(rest)?;
+        *rest = rest
+            .strip_prefix(')')
+            .ok_or("unbalanced parentheses")?
+            .trim_start();
+        return Ok(());
+    }
+
+    let end = rest
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(rest.len());
+    let matcher = &rest[..end];
+    if !MATCHERS.contains(&matcher) {
+        return Err(format!(
+            "expected one of {} at {:?}",
+            MATCHERS.join(", "),
+            rest
+        ));
+    }
+    *rest = rest[end..]
+        .strip_prefix('(')
+        .ok_or_else(|| format!("{} needs arguments", matcher))?;
+    loop {
+        let arg = rest
+            .trim_start()
+            .strip_prefix('`')
+            .ok_or_else(|| format!("{} arguments must be backquoted", matcher))?;
+        let close = arg
+            .find('`')
+            .ok_or_else(|| format!("unterminated {} argument", matcher))?;
+        *rest = arg[close + 1..].trim_start();
+        if let Some(next) = rest.strip_prefix(',') {
+            *rest = next;
+        } else {
+            break;
+        }
+    }
+    *rest = rest
+        .strip_prefix(')')
+        .ok_or_else(|| format!("unclosed {}(", matcher))?
+        .trim_start();
+    Ok(())
+}
+
+/// A Kubernetes qualified name: an optional DNS subdomain prefix and `/`, then up to 63
+/// alphanumerics, `-`, `_` or `.`, starting and ending alphanumeric.
+fn qualified_name(value: &str) -> bool {
+    let (prefix, name) = match value.split_once('/') {
+        Some((prefix, name)) => (Some(prefix), name),
+        None => (None, value),
+    };
+    let subdomain = |p: &str| p.len() <= 253 && p.split('.').all(dns_label);
+    prefix.is_none_or(subdomain)
+        && !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && name.ends_with(|c: char| c.is_ascii_alphanumeric())
+}
+
+fn label_value(value: &str) -> bool {
+    value.is_empty()
+        || (value.len() <= MAX_NAME_LEN
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            && value.starts_with(|c: char| c.is_ascii_alphanumeric())
+            && value.ends_with(|c: char| c.is_ascii_alphanumeric()))
+}
+
+fn cidr(range: &str) -> bool {
+    let Some((addr, prefix)) = range.split_once('/') else {
+        return false;
+    };
+    let max = match addr.parse::<IpAddr>() {
+        Ok(IpAddr::V4(_)) => 32,
+        Ok(IpAddr::V6(_)) => 128,
+        Err(_) => return false,
+    };
+    prefix.parse::<u8>().is_ok_and(|p| p <= max)
+}
+
+/// Pod fields the downward API can put in an environment variable.
+fn downward_env_field(path: &str) -> bool {
+    const FIELDS: [&str; 9] = [
+        "metadata.name",
+        "metadata.namespace",
+        "metadata.uid",
+        "spec.nodeName",
+        "spec.serviceAccountName",
+        "status.hostIP",
+        "status.hostIPs",
+        "status.podIP",
+        "status.podIPs",
+    ];
+    FIELDS.contains(&path)
+        || ["metadata.labels['", "metadata.annotations['"]
+            .iter()
+            .any(|prefix| path.starts_with(prefix) && path.ends_with("']"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crd::testing::model_deployment;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    fn named(name: &str, spec: serde_json::Value) -> ModelDeployment {
+        let mut md = model_deployment(spec);
+        md.metadata.name = Some(name.into());
+        md
+    }
+
+    fn config() -> Config {
+        Config {
+            extra_resource_kinds: vec![
+                "ConfigMap".into(),
+                "monitoring.coreos.com/ServiceMonitor".into(),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn extra_of(api_version: &str, kind: &str) -> ModelDeployment {
+        model_deployment(json!({"live": {"image": "m:1"}, "extraResources": [{
+            "apiVersion": api_version,
+            "kind": kind,
+            "metadata": {"name": "extra"},
+        }]}))
+    }
+
+    fn rejection(md: &ModelDeployment) -> String {
+        match validate(md, &config()) {
+            Err(Error::Validation(message)) => message,
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn checksum_url_must_be_plain_http() {
+        let checksum = |url: &str| {
+            model_deployment(json!({"live": {"image": "m:1", "verifyChecksum": {
+                "url": url,
+                "sha256": "ab".repeat(32),
+            }}}))
+        };
+        assert!(validate(&checksum("http://models.internal/m.bin"), &config()).is_ok());
+        assert!(rejection(&checksum("https://models.example/m.bin")).contains("TLS"));
+    }
+
+    #[test]
+    fn longest_generated_name_may_be_exactly_63_characters() {
+        // `<name>-live-svc` is the longest name for a live-only spec
+        let md = named(&"a".repeat(54), json!({"live": {"image": "m:1"}}));
+        assert_eq!(md.spec.service_name(&md.name_any(), "live").len(), 63);
+        assert!(validate(&md, &config()).is_ok());
+    }
+
+    #[test]
+    fn generated_name_of_64_characters_is_rejected() {
+        let md = named(&"a".repeat(55), json!({"live": {"image": "m:1"}}));
+        let message = rejection(&md);
+        assert!(
+            message.contains("is 64 characters (limit 63)"),
+            "{}",
+            message
+        );
+        assert!(message.contains("at most 54 characters"), "{}", message);
+    }
+
+    #[test]
+    fn shadow_suffix_lowers_the_name_limit() {
+        let spec = json!({"live": {"image": "m:1"}, "shadow": {"image": "m:2"}});
+        // `<name>-shadow-svc` adds 11 characters
+        assert!(validate(&named(&"a".repeat(52), spec.clone()), &config()).is_ok());
+        let message = rejection(&named(&"a".repeat(53), spec));
+        assert!(
+            message.contains("-shadow-svc is 64 characters"),
+            "{}",
+            message
+        );
+        assert!(message.contains("at most 52 characters"), "{}", message);
+    }
+
+    #[test]
+    fn naming_templates_count_towards_the_limit() {
+        let spec = json!({
+            "live": {"image": "m:1"},
+            "naming": {"service": "{name}-{role}-service-endpoint"},
+        });
+        assert!(validate(&named(&"a".repeat(41), spec.clone()), &config()).is_ok());
+        let message = rejection(&named(&"a".repeat(42), spec));
+        assert!(message.contains("at most 41 characters"), "{}", message);
+    }
+
+    #[test]
+    fn extra_resources_are_limited_to_allowed_kinds() {
+        let extra = |kind: &str| extra_of("v1", kind);
+        assert!(validate(&extra("ConfigMap"), &config()).is_ok());
+        let message = rejection(&extra("Secret"));
+        assert!(
+            message.contains("Secret, which is not an allowed kind"),
+            "{}",
+            message
+        );
+        let monitor = extra_of("monitoring.coreos.com/v1", "ServiceMonitor");
+        assert!(validate(&monitor, &config()).is_ok());
+        // the same kind name from another group is another kind
+        let message = rejection(&extra_of("evil.example/v1", "ServiceMonitor"));
+        assert!(
+            message.contains("evil.example/ServiceMonitor, which is not"),
+            "{}",
+            message
+        );
+        let message = rejection(&extra_of("example.com/v1", "ConfigMap"));
+        assert!(message.contains("example.com/ConfigMap"), "{}", message);
+
+        let any = Config {
+            extra_resource_kinds: vec!["*".into()],
+            ..Default::default()
+        };
+        assert!(validate(&extra("Secret"), &any).is_ok());
+    }
+
+    #[test]
+    fn host_path_volumes_need_the_operator_opt_in() {
+        let md = model_deployment(json!({"live": {"image": "m:1", "volumes": [{
+            "name": "models",
+            "mountPath": "/models",
+            "hostPath": "/var/lib/models",
+        }]}}));
+        let message = rejection(&md);
+        assert!(message.contains("ALLOW_HOST_PATH"), "{}", message);
+
+        let allowed = Config {
+            allow_host_path: true,
+            ..config()
+        };
+        assert!(validate(&md, &allowed).is_ok());
+    }
+
+    #[test]
+    fn variant_rollout_timing_is_checked_after_overrides() {
+        let timing = |live: serde_json::Value| {
+            let mut spec = json!({"live": {"image": "m:1"}, "progressDeadlineSeconds": 0});
+            spec["live"]
+                .as_object_mut()
+                .unwrap()
+                .extend(live.as_object().unwrap().clone());
+            model_deployment(spec)
+        };
+        // the spec-level 0 is invalid, but live overrides it
+        assert!(validate(&timing(json!({"progressDeadlineSeconds": 60})), &config()).is_ok());
+        let message = rejection(&timing(json!({})));
+        assert!(message.starts_with("live "), "{}", message);
+        let message = rejection(&timing(
+            json!({"progressDeadlineSeconds": 60, "minReadySeconds": -1}),
+        ));
+        assert!(message.contains("minReadySeconds"), "{}", message);
+    }
+
+    #[test]
+    fn route_priority_leaves_room_for_the_mirrored_route() {
+        let priority =
+            |p: i64| model_deployment(json!({"live": {"image": "m:1"}, "routePriority": p}));
+        assert!(validate(&priority(1), &config()).is_ok());
+        assert!(validate(&priority(i64::MAX - 1), &config()).is_ok());
+        assert!(rejection(&priority(0)).contains("must be positive"));
+        assert!(rejection(&priority(i64::MAX)).contains("must be positive"));
+    }
+
+    #[test]
+    fn sticky_cookie_same_site_must_be_known() {
+        let cookie = |same_site: &str| {
+            model_deployment(
+                json!({"live": {"image": "m:1"}, "stickyCookie": {"sameSite": same_site}}),
+            )
+        };
+        assert!(validate(&cookie("lax"), &config()).is_ok());
+        assert!(rejection(&cookie("Lax")).contains("stickyCookie sameSite"));
+    }
+
+    #[test]
+    fn autoscaling_targets_are_checked_before_the_hpa_is_applied() {
+        let target = |target: &str| {
+            model_deployment(json!({"live": {"image": "m:1"}, "autoscaling": {
+                "enabled": true,
+                "metrics": [{"type": "Pods", "name": "rps", "target": target}],
+            }}))
+        };
+        for ok in ["100", "500m", "1.5", "2k"] {
+            assert!(validate(&target(ok), &config()).is_ok(), "{}", ok);
+        }
+        for bad in ["0", "-1", "fast", "10 m", "1e3"] {
+            let message = rejection(&target(bad));
+            assert!(
+                message.contains("must be a positive quantity"),
+                "{}",
+                message
+            );
+        }
+
+        let cpu = model_deployment(json!({"live": {"image": "m:1"}, "autoscaling": {
+            "enabled": true,
+            "targetCpuUtilizationPercentage": 0,
+        }}));
+        assert!(rejection(&cpu).contains("targetCpuUtilizationPercentage 0"));
+    }
+
+    #[test]
+    fn target_namespace_must_be_allowed_for_the_source() {
+        let targeting = |ns: &str, target: &str| {
+            let mut md =
+                model_deployment(json!({"live": {"image": "m:1"}, "targetNamespace": target}));
+            md.metadata.namespace = Some(ns.into());
+            md
+        };
+        let allowing = Config {
+            target_namespaces: BTreeMap::from([
+                ("models".into(), vec!["serving".into()]),
+                ("platform".into(), vec!["*".into()]),
+            ]),
+            ..config()
+        };
+
+        assert!(validate(&targeting("models", "serving"), &allowing).is_ok());
+        assert!(validate(&targeting("platform", "anything"), &allowing).is_ok());
+        // naming its own namespace is not a move at all
+        assert!(validate(&targeting("team", "team"), &config()).is_ok());
+
+        let denied = validate(&targeting("models", "kube-system"), &allowing);
+        assert!(
+            matches!(denied, Err(Error::Validation(m)) if m.contains("not allowed for ModelDeployments in models"))
+        );
+        let message = rejection(&targeting("models", "serving"));
+        assert!(message.contains("TARGET_NAMESPACES"), "{}", message);
+    }
+
+    #[test]
+    fn workloads_are_refused_as_extra_resources_even_with_every_kind_allowed() {
+        let md = model_deployment(json!({"live": {"image": "m:1"}, "extraResources": [{
+            "apiVersion": "apps/v1",
+            "kind": "DaemonSet",
+            "metadata": {"name": "miner"},
+        }]}));
+        let any = Config {
+            extra_resource_kinds: vec!["*".into()],
+            ..Default::default()
+        };
+        let Err(Error::Validation(message)) = validate(&md, &any) else {
+            panic!("a DaemonSet was accepted");
+        };
+        assert!(
+            message.contains("workloads cannot be extra resources"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn overlay_images_are_checked_against_the_registry_allowlist() {
+        let allowlist = vec!["registry.corp.example".to_string()];
+        let overlaid = |image: &str| {
+            model_deployment(json!({
+                "live": {"image": "registry.corp.example/model:1"},
+                "podTemplateOverlay": {"spec": {"containers": [
+                    {"name": "model", "image": "registry.corp.example/model:1"},
+                    {"name": "extra", "image": image},
+                ]}},
+            }))
+        };
+        assert!(check_registries(&overlaid("registry.corp.example/sidecar:1"), &allowlist).is_ok());
+        let Err(Error::Validation(message)) =
+            check_registries(&overlaid("evil.example/miner:1"), &allowlist)
+        else {
+            panic!("an overlay image from another registry was accepted");
+        };
+        assert!(message.contains("evil.example"), "{}", message);
+
+        // the operator's own helper is not the user's to allowlist
+        let debug = model_deployment(json!({
+            "live": {"image": "registry.corp.example/model:1"},
+            "debug": true,
+        }));
+        assert!(check_registries(&debug, &allowlist).is_ok());
+    }
+
+    #[test]
+    fn probe_port_must_be_one_the_container_declares() {
+        let probed = |port: &str| {
+            model_deployment(json!({
+                "live": {"image": "m:1"},
+                "shadow": {"image": "m:2", "port": 9000},
+                "extraPorts": [{"name": "health", "containerPort": 8081}],
+                "probes": {"port": port},
+            }))
+        };
+        // numbers and names declared by both variants
+        assert!(validate(&probed("8081"), &config()).is_ok());
+        assert!(validate(&probed("health"), &config()).is_ok());
+
+        let message = rejection(&probed("8082"));
+        assert!(message.contains(r#"probes.port "8082""#), "{}", message);
+        assert!(message.contains("8081 (health)"), "{}", message);
+        let message = rejection(&probed("healthz"));
+        assert!(message.contains(r#"probes.port "healthz""#), "{}", message);
+
+        // the serving port is checked per variant, by number and by its fallback name
+        let message = rejection(&probed("8000"));
+        assert!(message.contains("the shadow container"), "{}", message);
+        assert!(validate(&probed("http"), &config()).is_ok());
+    }
+
+    #[test]
+    fn external_traffic_policy_needs_an_exposed_service() {
+        let message = rejection(&model_deployment(json!({
+            "live": {"image": "m:1"},
+            "externalTrafficPolicy": "Local",
+        })));
+        assert!(message.contains("externalTrafficPolicy"), "{}", message);
+
+        let node_port = model_deployment(json!({
+            "live": {"image": "m:1"},
+            "serviceType": "NodePort",
+            "externalTrafficPolicy": "Local",
+        }));
+        assert!(validate(&node_port, &config()).is_ok());
+        // an extra LoadBalancer Service is enough; the ClusterIP ones leave it off
+        let extra = model_deployment(json!({
+            "live": {"image": "m:1"},
+            "services": [{"name": "public", "serviceType": "LoadBalancer"}],
+            "externalTrafficPolicy": "Local",
+        }));
+        assert!(validate(&extra, &config()).is_ok());
+    }
+}